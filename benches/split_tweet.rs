@@ -2,6 +2,7 @@ use std::fs;
 
 use criterion::{BenchmarkId, criterion_main};
 use criterion::{Criterion, criterion_group};
+use twitter::twitter::text;
 use twitter::twitter::tweet::Tweet;
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -10,6 +11,19 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_with_input(BenchmarkId::new("split_tweet", &tweet), &tweet, |b, s| {
         b.iter(|| new_tweet.split_tweet(s, "---"));
     });
+
+    // The same thread without separators, left to the automatic splitter.
+    let text = tweet.replace("\n---\n", " ");
+    let format = text::ThreadFormat {
+        numbering: text::Numbering::Suffix,
+        ..Default::default()
+    };
+    c.bench_with_input(BenchmarkId::new("auto_split", &text), &text, |b, s| {
+        b.iter(|| text::split_thread_indexed(std::slice::from_ref(s), &format));
+    });
+    c.bench_with_input(BenchmarkId::new("weighted_length", &text), &text, |b, s| {
+        b.iter(|| text::weighted_length(s));
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -93,6 +93,10 @@ pub fn init() {
     let config = Config {
         current_account: 0,
         accounts: vec![account],
+        api_base_url: None,
+        retry: None,
+        thread: None,
+        schedule_retry: None,
     };
 
     let serialized_config = match toml::to_string(&config) {
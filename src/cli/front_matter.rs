@@ -0,0 +1,246 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::{
+    schedule::recurrence::Recurrence,
+    twitter::{
+        error::TwitterError,
+        media::Image,
+        text::{Numbering, ThreadFormat},
+        tweet::parse_tweet_id,
+    },
+};
+
+/// Opens and closes the header block. `---` is left to thread separators.
+const DELIMITER: &str = "+++";
+
+/// Settings at the top of a tweet written in the editor or piped in, as TOML between `+++` lines:
+///
+/// ```txt
+/// +++
+/// reply_to = "https://x.com/user/status/1460323737035677698"
+/// images = [{ path = "chart.png", alt = "Signups per week" }, "logo.png"]
+/// numbering = true
+/// +++
+/// First tweet
+/// ---
+/// Second tweet
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FrontMatter {
+    pub reply_to: Option<String>,
+    pub quote: Option<String>,
+    #[serde(default)]
+    images: Vec<HeaderImage>,
+    /// When to post, in any format `schedule new --on` accepts.
+    pub schedule: Option<String>,
+    /// Post it again and again, by a rule `schedule new --repeat` accepts.
+    repeat: Option<String>,
+    /// The index of the configured account to post from.
+    pub account: Option<usize>,
+    pub separator: Option<String>,
+    /// Start a new tweet at every blank line.
+    pub paragraphs: Option<bool>,
+    /// `true` for counters in the configured style, or `"prefix"`, `"suffix"` or `"off"`.
+    pub numbering: Option<HeaderNumbering>,
+    /// Added to the end of the first tweet of a thread.
+    pub continuation: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum HeaderNumbering {
+    On(bool),
+    Style(Numbering),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum HeaderImage {
+    Path(PathBuf),
+    WithAlt { path: PathBuf, alt: Option<String> },
+}
+
+impl FrontMatter {
+    /// Splits the header off `text`. Text without one is returned unchanged with default settings.
+    pub fn parse(text: &str) -> Result<(Self, &str), TwitterError> {
+        let text = text.trim_start_matches('\u{feff}');
+        let Some(rest) = strip_delimiter_line(text) else {
+            return Ok((Self::default(), text));
+        };
+
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim() == DELIMITER {
+                let header = &rest[..offset];
+                let body = rest[offset + line.len()..].trim();
+                let front_matter = toml::from_str::<Self>(header).map_err(|err| {
+                    TwitterError::local(format!("Invalid header: {}", err.message()))
+                })?;
+                front_matter.validate()?;
+                return Ok((front_matter, body));
+            }
+            offset += line.len();
+        }
+
+        Err(TwitterError::local(format!(
+            "The header is missing its closing {DELIMITER} line."
+        )))
+    }
+
+    pub fn images(&self) -> Vec<Image> {
+        self.images
+            .iter()
+            .map(|image| match image {
+                HeaderImage::Path(path) => Image {
+                    path: path.clone(),
+                    alt: None,
+                },
+                HeaderImage::WithAlt { path, alt } => Image {
+                    path: path.clone(),
+                    alt: alt.clone(),
+                },
+            })
+            .collect()
+    }
+
+    /// Overrides the thread settings the header sets.
+    pub fn apply(&self, format: &mut ThreadFormat) {
+        if let Some(separator) = &self.separator {
+            format.separator = separator.trim().to_string();
+        }
+        if let Some(paragraphs) = self.paragraphs {
+            format.paragraphs = paragraphs;
+        }
+        match self.numbering {
+            Some(HeaderNumbering::On(true)) => format.numbered(),
+            Some(HeaderNumbering::On(false)) => format.numbering = Numbering::Off,
+            Some(HeaderNumbering::Style(style)) => format.numbering = style,
+            None => {}
+        }
+        if let Some(continuation) = &self.continuation {
+            format.continuation = Some(continuation.clone());
+        }
+    }
+
+    /// The reply target as a bare tweet id.
+    pub fn reply_to(&self) -> Result<Option<String>, TwitterError> {
+        tweet_id("reply_to", self.reply_to.as_deref())
+    }
+
+    /// The rule a scheduled post repeats by.
+    pub fn repeat(&self) -> Result<Option<Recurrence>, TwitterError> {
+        self.repeat
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|err| TwitterError::local(format!("Invalid header repeat: {err}")))
+    }
+
+    /// The quoted tweet as a bare tweet id.
+    pub fn quote(&self) -> Result<Option<String>, TwitterError> {
+        tweet_id("quote", self.quote.as_deref())
+    }
+
+    fn validate(&self) -> Result<(), TwitterError> {
+        self.reply_to()?;
+        self.quote()?;
+        self.repeat()?;
+        if let Some(separator) = &self.separator
+            && separator.trim().is_empty()
+        {
+            return Err(TwitterError::local("Invalid header: separator is empty."));
+        }
+        Ok(())
+    }
+}
+
+fn strip_delimiter_line(text: &str) -> Option<&str> {
+    let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+    (first.trim() == DELIMITER).then_some(rest)
+}
+
+fn tweet_id(key: &str, value: Option<&str>) -> Result<Option<String>, TwitterError> {
+    value
+        .map(parse_tweet_id)
+        .transpose()
+        .map_err(|err| TwitterError::local(format!("Invalid header {key}: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_is_split_from_the_body() {
+        let text = "+++\nreply_to = \"https://x.com/user/status/42\"\nquote = \"7\"\nimages = [\"a.png\", { path = \"b.png\", alt = \"A chart\" }]\nschedule = \"tomorrow 9am\"\nrepeat = \"every friday 17:00\"\naccount = 1\nseparator = \"===\"\nnumbering = true\n+++\n\nOne\n===\nTwo\n";
+        let (front_matter, body) = FrontMatter::parse(text).unwrap();
+
+        assert_eq!(body, "One\n===\nTwo");
+        assert_eq!(front_matter.reply_to().unwrap().as_deref(), Some("42"));
+        assert_eq!(front_matter.quote().unwrap().as_deref(), Some("7"));
+        assert_eq!(
+            front_matter.images(),
+            vec![
+                Image {
+                    path: PathBuf::from("a.png"),
+                    alt: None
+                },
+                Image {
+                    path: PathBuf::from("b.png"),
+                    alt: Some("A chart".to_string())
+                },
+            ]
+        );
+        assert_eq!(front_matter.schedule.as_deref(), Some("tomorrow 9am"));
+        assert_eq!(
+            front_matter.repeat().unwrap().unwrap().to_string(),
+            "every friday 17:00"
+        );
+        assert_eq!(front_matter.account, Some(1));
+        assert_eq!(front_matter.separator.as_deref(), Some("==="));
+        assert_eq!(front_matter.numbering, Some(HeaderNumbering::On(true)));
+    }
+
+    #[test]
+    fn test_header_overrides_the_thread_format() {
+        let text =
+            "+++\nparagraphs = true\nnumbering = \"prefix\"\ncontinuation = \"🧵\"\n+++\nOne";
+        let (front_matter, _) = FrontMatter::parse(text).unwrap();
+        let mut format = ThreadFormat {
+            separator: "===".to_string(),
+            ..Default::default()
+        };
+        front_matter.apply(&mut format);
+
+        assert_eq!(
+            format,
+            ThreadFormat {
+                separator: "===".to_string(),
+                paragraphs: true,
+                numbering: Numbering::Prefix,
+                continuation: Some("🧵".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_text_without_header_is_unchanged() {
+        let (front_matter, body) = FrontMatter::parse("One\n---\nTwo").unwrap();
+
+        assert_eq!(body, "One\n---\nTwo");
+        assert!(front_matter.images().is_empty());
+        assert!(front_matter.separator.is_none());
+    }
+
+    #[test]
+    fn test_invalid_headers_are_rejected() {
+        assert!(FrontMatter::parse("+++\nnumbering = true\nHello").is_err());
+        assert!(FrontMatter::parse("+++\nreplyto = \"1\"\n+++\nHello").is_err());
+        assert!(FrontMatter::parse("+++\nquote = \"not a tweet\"\n+++\nHello").is_err());
+        assert!(FrontMatter::parse("+++\nseparator = \" \"\n+++\nHello").is_err());
+        assert!(FrontMatter::parse("+++\nrepeat = \"every 9am\"\n+++\nHello").is_err());
+    }
+}
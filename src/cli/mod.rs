@@ -1,24 +1,50 @@
 pub mod config;
+pub mod front_matter;
+pub mod output;
+pub mod scheduler;
 pub mod update;
 
 use std::{
-    env::temp_dir,
     fs,
     io::{self, IsTerminal, Read},
     path::PathBuf,
+    process,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use jiff::{Timestamp, tz::TimeZone};
 use tabled::builder::Builder;
+use tempfile::NamedTempFile;
 
-use crate::{schedule, utils::send_due_tweets};
+use crate::{
+    cli::{front_matter::FrontMatter, output::OutputFormat, scheduler::Runner},
+    drafts,
+    schedule::{
+        self,
+        daemon::{Daemon, SystemClock},
+        recurrence::Recurrence,
+        runs::ScheduleRuns,
+    },
+    utils::send_due_tweets,
+};
 use crate::{
     twitter::{
         self,
-        tweet::{self, Media, TweetBody, TwitterApi},
+        error::TwitterError,
+        markdown::{self, Emphasis},
+        media::Image,
+        pagination::Pagination,
+        preflight::MediaOptions,
+        raw::{AuthScheme, RawRequest},
+        text::{Numbering, PartLength, ThreadFormat},
+        tweet::{self, Poll, Reply, TweetBody, TwitterApi},
     },
     usage,
-    utils::{self, gracefully_exit},
+    utils::{self, exit_with_error, gracefully_exit},
 };
 
 #[derive(Parser, Debug)]
@@ -26,6 +52,18 @@ use crate::{
 pub struct Args {
     #[command(subcommand)]
     command: Commands,
+
+    /// Fail on a rate limit instead of waiting for the window to reset
+    #[arg(long, global = true)]
+    no_wait: bool,
+
+    /// Print every request (method, URL, auth scheme and body) instead of sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// How to print results
+    #[arg(long, short, global = true, value_enum, default_value_t = OutputFormat::Plain)]
+    output: OutputFormat,
 }
 
 #[derive(Debug, Subcommand)]
@@ -36,9 +74,90 @@ enum Commands {
         #[arg(long, short, name = "body")]
         body: Option<String>,
 
-        /// An image to attach to the tweet
-        #[arg(long, short, name = "image")]
-        image: Option<PathBuf>,
+        /// An image, GIF or video to attach to the tweet. Repeat for up to 4 images
+        #[arg(long, short, name = "image", visible_alias = "media")]
+        image: Vec<PathBuf>,
+
+        /// Alt text for the image given right before it
+        #[arg(long, name = "alt", requires = "image")]
+        alt: Vec<String>,
+
+        /// Upload images with their EXIF metadata, including the GPS position
+        #[arg(long, requires = "image")]
+        keep_metadata: bool,
+
+        /// Shrink JPEG and PNG images that are over the size or dimension limits
+        #[arg(long, requires = "image")]
+        downscale: bool,
+
+        /// Post as a reply to this tweet (id or url). A thread continues below it
+        #[arg(long, value_parser = tweet::parse_tweet_id)]
+        reply_to: Option<String>,
+
+        /// Quote this tweet (id or url) in the first tweet
+        #[arg(long, value_parser = tweet::parse_tweet_id)]
+        quote: Option<String>,
+
+        /// A poll choice. Repeat for 2 to 4 choices
+        #[arg(long, name = "poll_option", conflicts_with_all = ["image", "quote"])]
+        poll_option: Vec<String>,
+
+        /// How long the poll stays open: minutes, or a duration like 30m, 6h or 1d
+        #[arg(
+            long,
+            requires = "poll_option",
+            default_value = "1d",
+            value_parser = tweet::parse_poll_duration
+        )]
+        poll_duration: u32,
+
+        /// Print the weighted length of every part and exit without posting
+        #[arg(long)]
+        check: bool,
+
+        /// Split parts that are too long into a thread at sentence or word boundaries
+        #[arg(long)]
+        auto_split: bool,
+
+        /// Where the n/N counters go, suffix when no style is given. Overrides the [thread]
+        /// config. `--number` is short for `--numbering suffix`
+        #[arg(
+            long,
+            value_enum,
+            value_name = "STYLE",
+            alias = "number",
+            num_args = 0..=1,
+            default_missing_value = "suffix"
+        )]
+        numbering: Option<Numbering>,
+
+        /// Split the thread at lines equal to this instead of `---`
+        #[arg(long, value_name = "LINE", conflicts_with = "paragraphs")]
+        separator: Option<String>,
+
+        /// Start a new tweet at every blank line instead of at separators
+        #[arg(long)]
+        paragraphs: bool,
+
+        /// Add this to the end of the first tweet of a thread, e.g. 🧵
+        #[arg(long, value_name = "MARKER")]
+        continuation: Option<String>,
+
+        /// Render the text from Markdown: headings start new tweets, lists become • lines
+        #[arg(long)]
+        markdown: bool,
+
+        /// How Markdown bold and italic text is shown
+        #[arg(long, value_enum, requires = "markdown", default_value_t)]
+        emphasis: Emphasis,
+
+        /// Continue a thread that failed part way, using the handle printed when it stopped
+        #[arg(
+            long,
+            value_name = "HANDLE",
+            conflicts_with_all = ["body", "editor", "image", "poll_option", "reply_to", "quote", "check"]
+        )]
+        resume: Option<i64>,
 
         /// Launch the editor
         #[arg(long, short, name = "editor")]
@@ -70,12 +189,54 @@ enum Commands {
         validate: bool,
     },
 
+    /// Send a signed request to any API endpoint
+    Api {
+        /// The HTTP method: GET, POST, PUT, PATCH or DELETE
+        method: String,
+
+        /// The API path, e.g. /2/users/:id/pinned_lists. `:id` is replaced with your user id
+        path: String,
+
+        /// Add a string field: a query parameter for GET and DELETE, a JSON body field otherwise
+        #[arg(long = "raw-field", short = 'f', value_name = "KEY=VALUE")]
+        raw_fields: Vec<String>,
+
+        /// Add a typed field: numbers, true, false and null are sent as JSON, @file reads a file
+        #[arg(long = "field", short = 'F', value_name = "KEY=VALUE")]
+        fields: Vec<String>,
+
+        /// Add a request header
+        #[arg(long, short = 'H', value_name = "NAME: VALUE")]
+        header: Vec<String>,
+
+        /// Send a file as the request body, or `-` for stdin. Fields are then sent as query parameters
+        #[arg(long)]
+        input: Option<PathBuf>,
+
+        /// How to sign the request
+        #[arg(long, value_enum, default_value_t)]
+        auth: AuthScheme,
+
+        /// Follow `meta.next_token` and merge every page
+        #[arg(long)]
+        paginate: bool,
+    },
+
     /// API usage
     Usage {},
 
+    /// Show the last known rate-limit budget per endpoint
+    Limits {},
+
     /// Self update
     Update {},
 
+    /// Write tweets now and post them later
+    Drafts {
+        #[command(subcommand)]
+        command: DraftsEnum,
+    },
+
     /// Schedule Tweets
     Schedule {
         #[command(subcommand)]
@@ -130,7 +291,10 @@ enum Commands {
     },
 
     /// Mentions
-    Mentions {},
+    Mentions {
+        #[command(flatten)]
+        page: PageArgs,
+    },
 
     /// Filtered stream
     Streams {
@@ -152,13 +316,37 @@ enum Commands {
 enum ScheduleEnum {
     /// Add a new tweet to the Schedule
     New {
-        /// The body of the new tweet
-        #[arg(long)]
-        body: String,
+        /// The body of the new tweet. Read from stdin or the editor when missing
+        #[arg(long, short, name = "body")]
+        body: Option<String>,
+
+        /// An image, GIF or video to attach. Repeat for up to 4 images
+        #[arg(long, short, name = "image", visible_alias = "media")]
+        image: Vec<PathBuf>,
 
-        /// The time to send the tweet
-        #[arg(long, visible_aliases = ["at", "in"])]
-        on: String,
+        /// Alt text for the image given right before it
+        #[arg(long, name = "alt", requires = "image")]
+        alt: Vec<String>,
+
+        /// Post as a reply to this tweet (id or url)
+        #[arg(long, value_parser = tweet::parse_tweet_id)]
+        reply_to: Option<String>,
+
+        /// Quote this tweet (id or url)
+        #[arg(long, value_parser = tweet::parse_tweet_id)]
+        quote: Option<String>,
+
+        /// Launch the editor
+        #[arg(long, short, name = "editor")]
+        editor: bool,
+
+        /// The time to send the tweet. Defaults to the next occurrence of --repeat
+        #[arg(long, visible_aliases = ["at", "in"], required_unless_present = "repeat")]
+        on: Option<String>,
+
+        /// Send it again and again: a cron expression ("0 9 * * 1") or "every monday 09:00"
+        #[arg(long, name = "repeat")]
+        repeat: Option<String>,
     },
 
     /// List all the scheduled tweets
@@ -168,6 +356,135 @@ enum ScheduleEnum {
 
     /// Run all ready-to-send scheduled tweets
     Run {},
+
+    /// Send failed scheduled tweets again now
+    Retry {
+        /// The id of the failed tweet, as shown by `schedule list`
+        #[arg(
+            long,
+            conflicts_with = "all_failed",
+            required_unless_present = "all_failed"
+        )]
+        id: Option<u32>,
+
+        /// Retry every failed tweet
+        #[arg(long)]
+        all_failed: bool,
+    },
+
+    /// Keep running and send every scheduled tweet when it is due. Stops on SIGTERM or Ctrl-C
+    Daemon {},
+
+    /// Run `schedule run` every minute with a systemd user timer, or print a crontab line
+    Install(RunnerArgs),
+
+    /// Remove what `schedule install` set up
+    Uninstall(RunnerArgs),
+
+    /// Show whether scheduled tweets are being sent and when that last worked
+    Status {},
+}
+
+#[derive(Debug, clap::Args)]
+struct RunnerArgs {
+    /// Write twitter-schedule.service and .timer under ~/.config/systemd/user
+    #[arg(long, conflicts_with = "cron", required_unless_present = "cron")]
+    systemd: bool,
+
+    /// Print the crontab line that runs it
+    #[arg(long)]
+    cron: bool,
+}
+
+impl RunnerArgs {
+    fn runner(&self) -> Runner {
+        if self.systemd {
+            Runner::Systemd
+        } else {
+            Runner::Cron
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum DraftsEnum {
+    /// Save a new draft
+    New {
+        /// The body of the draft. Read from stdin or the editor when missing
+        #[arg(long, short, name = "body")]
+        body: Option<String>,
+
+        /// An image, GIF or video to attach. Repeat for up to 4 images
+        #[arg(long, short, name = "image", visible_alias = "media")]
+        image: Vec<PathBuf>,
+
+        /// Alt text for the image given right before it
+        #[arg(long, name = "alt", requires = "image")]
+        alt: Vec<String>,
+
+        /// Post as a reply to this tweet (id or url)
+        #[arg(long, value_parser = tweet::parse_tweet_id)]
+        reply_to: Option<String>,
+
+        /// Quote this tweet (id or url)
+        #[arg(long, value_parser = tweet::parse_tweet_id)]
+        quote: Option<String>,
+
+        /// Launch the editor
+        #[arg(long, short, name = "editor")]
+        editor: bool,
+    },
+
+    /// List all the drafts
+    List {},
+
+    /// Change the body of a draft, in the editor unless --body is given
+    Edit {
+        /// The draft id
+        id: i64,
+
+        /// The new body
+        #[arg(long, short)]
+        body: Option<String>,
+    },
+
+    /// Show a draft in full
+    Show {
+        /// The draft id
+        id: i64,
+    },
+
+    /// Post a draft from the account it was written for, then delete it
+    Publish {
+        /// The draft id
+        id: i64,
+
+        /// Split parts that are too long into a thread at sentence or word boundaries
+        #[arg(long)]
+        auto_split: bool,
+
+        /// Where the n/N counters go, suffix when no style is given. Overrides the [thread]
+        /// config. `--number` is short for `--numbering suffix`
+        #[arg(
+            long,
+            value_enum,
+            value_name = "STYLE",
+            alias = "number",
+            num_args = 0..=1,
+            default_missing_value = "suffix"
+        )]
+        numbering: Option<Numbering>,
+
+        /// Add this to the end of the first tweet of a thread, e.g. 🧵
+        #[arg(long, value_name = "MARKER")]
+        continuation: Option<String>,
+    },
+
+    /// Delete a draft
+    Delete {
+        /// The draft id
+        id: i64,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -181,6 +498,9 @@ enum LikesEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Like a tweet for the current authenticated user
@@ -198,7 +518,10 @@ enum LikesEnum {
     },
 
     /// Fetch tweets liked by the current authenticated user
-    Tweets {},
+    Tweets {
+        #[command(flatten)]
+        page: PageArgs,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -208,6 +531,9 @@ enum BookmarksEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Bookmark a tweet for the current authenticated user
@@ -229,6 +555,9 @@ enum BookmarksEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Fetch tweets from a bookmark folder for the current authenticated user
@@ -240,6 +569,9 @@ enum BookmarksEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 }
 
@@ -272,6 +604,9 @@ enum ListsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Update a list
@@ -308,6 +643,9 @@ enum ListsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Fetch the tweets in a list
@@ -319,6 +657,9 @@ enum ListsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Add a user to a list
@@ -347,6 +688,9 @@ enum ListsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 }
 
@@ -361,6 +705,9 @@ enum DmsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Show DM events for the current authenticated user
@@ -368,6 +715,9 @@ enum DmsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Show DM events with a participant
@@ -379,6 +729,9 @@ enum DmsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Create a DM conversation and send the initial message
@@ -426,6 +779,9 @@ enum RetweetsEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Create a retweet for the current authenticated user
@@ -456,6 +812,9 @@ enum MutesEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Unmute a user for the current authenticated user
@@ -480,6 +839,9 @@ enum BlocksEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Unblock a user for the current authenticated user
@@ -494,7 +856,10 @@ enum BlocksEnum {
 enum TimelineEnum {
     /// Fetch the reverse-chronological home timeline
     #[command(visible_alias = "reverse")]
-    ReverseChronological {},
+    ReverseChronological {
+        #[command(flatten)]
+        page: PageArgs,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -601,6 +966,9 @@ enum UsersEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Fetch a user's followers
@@ -612,6 +980,9 @@ enum UsersEnum {
         /// Number of results to fetch
         #[arg(long, default_value_t = 10)]
         max_results: u8,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Follow a user for the current authenticated user
@@ -669,6 +1040,31 @@ enum StreamRulesEnum {
     },
 }
 
+#[derive(Debug, clap::Args)]
+struct PageArgs {
+    /// Follow the pagination cursor until every page has been fetched
+    #[arg(long, conflicts_with = "limit")]
+    all: bool,
+
+    /// Fetch pages until this many results have been collected
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Start from a page returned by a previous call
+    #[arg(long)]
+    pagination_token: Option<String>,
+}
+
+impl PageArgs {
+    fn pagination(&self) -> Pagination {
+        Pagination {
+            all: self.all,
+            limit: self.limit,
+            pagination_token: self.pagination_token.clone(),
+        }
+    }
+}
+
 #[derive(Debug, clap::Args)]
 struct ListArgs {
     #[arg(long, value_enum, default_value_t = ListFilter::All)]
@@ -682,98 +1078,463 @@ enum ListFilter {
     Sent,
 }
 
+fn build_raw_request(
+    method: &str,
+    path: &str,
+    raw_fields: &[String],
+    fields: &[String],
+    headers: &[String],
+    input: Option<PathBuf>,
+    auth: AuthScheme,
+) -> Result<RawRequest, TwitterError> {
+    let path = twitter::raw::expand_path(path)?;
+    let mut request = RawRequest::new(method, &path)?.auth(auth);
+
+    for header in headers {
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(TwitterError::local(format!(
+                "Invalid header, expected NAME: VALUE: {header}"
+            )));
+        };
+        request = request.header(name.trim(), value.trim());
+    }
+
+    if let Some(input) = input {
+        request = request.input(read_input(&input)?);
+    }
+
+    for field in raw_fields {
+        let (key, value) = split_field(field)?;
+        request = request.field(key, serde_json::Value::String(value.to_string()))?;
+    }
+
+    for field in fields {
+        let (key, value) = split_field(field)?;
+        let value = match value {
+            "true" => serde_json::Value::Bool(true),
+            "false" => serde_json::Value::Bool(false),
+            "null" => serde_json::Value::Null,
+            _ => match value.strip_prefix('@') {
+                Some(file) => {
+                    let contents = read_input(&PathBuf::from(file))?;
+                    serde_json::Value::String(
+                        String::from_utf8_lossy(&contents).trim_end().to_string(),
+                    )
+                }
+                None => serde_json::from_str::<serde_json::Number>(value)
+                    .map(serde_json::Value::Number)
+                    .unwrap_or_else(|_| serde_json::Value::String(value.to_string())),
+            },
+        };
+        request = request.field(key, value)?;
+    }
+
+    Ok(request)
+}
+
+/// Pairs every `--alt` with the `--image` given right before it.
+fn images_with_alt(matches: &ArgMatches) -> Result<Vec<Image>, TwitterError> {
+    let paths = matches.get_many::<PathBuf>("image").unwrap_or_default();
+    let path_indices = matches.indices_of("image").unwrap_or_default();
+    let mut images: Vec<(usize, Image)> = path_indices
+        .zip(paths)
+        .map(|(index, path)| {
+            let image = Image {
+                path: path.clone(),
+                alt: None,
+            };
+            (index, image)
+        })
+        .collect();
+
+    let alts = matches.get_many::<String>("alt").unwrap_or_default();
+    let alt_indices = matches.indices_of("alt").unwrap_or_default();
+    for (alt_index, alt) in alt_indices.zip(alts) {
+        let Some((_, image)) = images
+            .iter_mut()
+            .rev()
+            .find(|(index, _)| *index < alt_index)
+        else {
+            return Err(TwitterError::local(
+                "--alt must follow the --image it describes.",
+            ));
+        };
+        if image.alt.is_some() {
+            return Err(TwitterError::local(format!(
+                "{:?} has more than one --alt.",
+                image.path
+            )));
+        }
+        image.alt = Some(alt.clone());
+    }
+
+    Ok(images.into_iter().map(|(_, image)| image).collect())
+}
+
+/// The parts of a tweet as written, joined the way `format` splits them, for drafts and
+/// scheduled posts that are formatted when they go out. Images are written back as
+/// `![alt](path)` lines, `images` in the first part, so posting the text uploads them.
+fn thread_text(tweet: &tweet::Tweet, images: &[Image], format: &ThreadFormat) -> String {
+    let mut parts = tweet.sections();
+    if let Some(first) = parts.first_mut() {
+        first.images.splice(0..0, images.iter().cloned());
+    }
+
+    let parts: Vec<String> = parts
+        .into_iter()
+        .map(|part| {
+            let mut text = part.text;
+            for image in absolute_images(part.images) {
+                let alt = image.alt.unwrap_or_default();
+                text.push_str(&format!("\n![{alt}]({})", image.path.display()));
+            }
+            text
+        })
+        .collect::<Vec<_>>();
+    format.join(&parts)
+}
+
+/// Resolves image paths against the current directory, for posts sent from elsewhere later.
+fn absolute_images(images: Vec<Image>) -> Vec<Image> {
+    images
+        .into_iter()
+        .map(|image| Image {
+            path: std::path::absolute(&image.path).unwrap_or(image.path),
+            alt: image.alt,
+        })
+        .collect()
+}
+
+/// A schedule first sent `on`, or at the next occurrence of `repeat` when `on` is missing.
+fn new_schedule(body: &str, on: Option<&str>, repeat: Option<Recurrence>) -> schedule::Schedule {
+    match (on, repeat) {
+        (Some(on), Some(repeat)) => schedule::Schedule::new(body, on).repeat(repeat),
+        (Some(on), None) => schedule::Schedule::new(body, on),
+        (None, Some(repeat)) => schedule::Schedule::recurring(body, repeat),
+        (None, None) => gracefully_exit("A scheduled tweet needs a time or a rule to repeat by."),
+    }
+}
+
+/// Saves a scheduled tweet once its text and images pass the checks made before posting.
+fn save_schedule(schedule: schedule::Schedule, on: Option<&str>) {
+    if let Err(err) = schedule.check() {
+        exit_with_error(&err);
+    }
+    let when = match on {
+        Some(on) => on.to_string(),
+        None => schedule::local_time(schedule.send_time()),
+    };
+    let repeat = schedule
+        .recurrence()
+        .map(|recurrence| format!(", then {recurrence}"))
+        .unwrap_or_default();
+    if schedule.save() {
+        println!("Tweet scheduled for {when}{repeat}.");
+    } else {
+        eprintln!("Could not schedule tweet.");
+    }
+}
+
+/// Keeps the text, images and targets of a tweet that failed to post, unless it was a dry run.
+fn save_failed_draft(draft: Option<drafts::Draft>, err: &TwitterError) {
+    let Some(draft) = draft else {
+        return;
+    };
+    if err.is_dry_run() {
+        return;
+    }
+
+    let draft = drafts::Draft {
+        account: utils::load_config().current_account,
+        last_error: Some(err.to_string()),
+        ..draft
+    };
+    match drafts::Drafts::default().save(&draft) {
+        Ok(id) => eprintln!("> Saved as draft {id}. Publish it with: twitter drafts publish {id}"),
+        Err(err) => eprintln!("{err}"),
+    }
+}
+
+/// Reads the text of a tweet from `--body`, piped stdin or the editor, in that order.
+fn read_tweet_text(body: Option<String>, editor: bool, file_name: &str) -> Option<String> {
+    match body {
+        Some(tweet) => Some(tweet),
+        None => {
+            if !io::stdin().is_terminal() {
+                let mut buf = String::new();
+                let read_stdin_string = io::stdin().read_to_string(&mut buf);
+
+                if read_stdin_string.is_ok() {
+                    Some(buf.trim().to_string())
+                } else {
+                    gracefully_exit(
+                        "Failed to read stdin as text.\nMake sure you are piping UTF-8 text.",
+                    )
+                }
+            } else if editor {
+                let temp_file = editor_file(file_name);
+                let status = utils::open_editor(temp_file.path());
+
+                if status.success() {
+                    match fs::read_to_string(temp_file.path()) {
+                        Ok(tweet) => Some(tweet),
+                        Err(_) => gracefully_exit("Failed to read the tweet from the editor."),
+                    }
+                } else {
+                    gracefully_exit("Failed to open the default editor");
+                }
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// A new file for the editor that only this user can open, deleted when it is dropped.
+fn editor_file(file_name: &str) -> NamedTempFile {
+    let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, "txt"));
+    match tempfile::Builder::new()
+        .prefix(&format!("twitter-{stem}-"))
+        .suffix(&format!(".{extension}"))
+        .tempfile()
+    {
+        Ok(file) => file,
+        Err(err) => gracefully_exit(&format!("Failed to create a file for the editor: {err}")),
+    }
+}
+
+fn split_field(field: &str) -> Result<(&str, &str), TwitterError> {
+    field
+        .split_once('=')
+        .ok_or_else(|| TwitterError::local(format!("Invalid field, expected KEY=VALUE: {field}")))
+}
+
+/// Reads a file, or stdin for `-`.
+fn read_input(path: &PathBuf) -> Result<Vec<u8>, TwitterError> {
+    if path.as_os_str() == "-" {
+        let mut buf = vec![];
+        io::stdin()
+            .read_to_end(&mut buf)
+            .map_err(TwitterError::local)?;
+        return Ok(buf);
+    }
+
+    fs::read(path).map_err(|err| TwitterError::local(format!("Failed to read {path:?}: {err}")))
+}
+
 pub fn run() {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let args = match Args::from_arg_matches(&matches) {
+        Ok(args) => args,
+        Err(err) => err.exit(),
+    };
+    twitter::rate_limit::set_no_wait(args.no_wait);
+    twitter::client::set_dry_run(args.dry_run);
+    let output = args.output;
 
     match args.command {
         Commands::Tweet {
             body,
-            image,
             editor,
+            keep_metadata,
+            downscale,
+            reply_to,
+            quote,
+            poll_option,
+            poll_duration,
+            check,
+            auto_split,
+            numbering,
+            separator,
+            paragraphs,
+            continuation,
+            markdown,
+            emphasis,
+            resume,
+            ..
         } => {
-            let mut media_id: Option<String> = None;
-
-            if let Some(image_path) = image {
-                let upload_result = twitter::media::upload(image_path);
-                media_id = match upload_result {
-                    Ok(media) => Some(media),
-                    Err(err) => gracefully_exit(&err.message),
-                };
+            if let Some(thread_id) = resume {
+                match tweet::Tweet::resume(thread_id).and_then(|mut tweet| tweet.create()) {
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
+                }
+                return;
             }
 
-            let tweet_body: Option<String> = match body {
-                Some(tweet) => Some(tweet),
-                None => {
-                    if !io::stdin().is_terminal() {
-                        let mut buf = String::new();
-                        let read_stdin_string = io::stdin().read_to_string(&mut buf);
+            let poll = if poll_option.is_empty() {
+                None
+            } else {
+                match Poll::new(poll_option, poll_duration) {
+                    Ok(poll) => Some(poll),
+                    Err(err) => exit_with_error(&err),
+                }
+            };
 
-                        if read_stdin_string.is_ok() {
-                            Some(buf.trim().to_string())
-                        } else {
-                            gracefully_exit(
-                                "Failed to read stdin as text.\nMake sure you are piping UTF-8 text.",
-                            )
-                        }
-                    } else if editor {
-                        let temp_file = temp_dir().join("tweet.txt");
-                        let status = utils::open_editor(&temp_file);
-
-                        if status.success() {
-                            match fs::read_to_string(&temp_file) {
-                                Ok(tweet) => {
-                                    let _ = fs::remove_file(temp_file);
-                                    Some(tweet)
-                                }
-                                Err(_) => {
-                                    gracefully_exit("Failed to read the tweet from the editor.")
-                                }
-                            }
-                        } else {
-                            gracefully_exit("Failed to open the default editor");
-                        }
-                    } else {
-                        None
-                    }
+            // A header is only read from the editor or stdin, never from --body.
+            let from_flag = body.is_some();
+            let text = read_tweet_text(body, editor, "tweet.txt");
+            let (front_matter, tweet_body) = match text {
+                Some(text) if !from_flag => match FrontMatter::parse(&text) {
+                    Ok((front_matter, body)) => (front_matter, Some(body.to_string())),
+                    Err(err) => exit_with_error(&err),
+                },
+                text => (FrontMatter::default(), text),
+            };
+            if let Some(account) = front_matter.account {
+                utils::use_account(account);
+            }
+            // Flags win over the header.
+            let targets = front_matter
+                .reply_to()
+                .and_then(|header_reply| Ok((header_reply, front_matter.quote()?)));
+            let (reply_to, quote) = match targets {
+                Ok((header_reply, header_quote)) => {
+                    (reply_to.or(header_reply), quote.or(header_quote))
+                }
+                Err(err) => exit_with_error(&err),
+            };
+            // The [thread] config, then the header, then flags.
+            let mut format = utils::thread_format();
+            front_matter.apply(&mut format);
+            if let Some(separator) = separator {
+                if separator.trim().is_empty() {
+                    exit_with_error(&TwitterError::local("--separator cannot be empty."));
+                }
+                format.separator = separator.trim().to_string();
+                format.paragraphs = false;
+            }
+            if paragraphs {
+                format.paragraphs = true;
+            }
+            if let Some(numbering) = numbering {
+                format.numbering = numbering;
+            }
+            if continuation.is_some() {
+                format.continuation = continuation;
+            }
+            if let Err(err) = format.validate() {
+                exit_with_error(&err);
+            }
+            let has_poll = poll.is_some();
+            let tweet_body = match tweet_body {
+                Some(body) if markdown => {
+                    Some(format.join_exactly(&markdown::render(&body, emphasis)))
                 }
+                body => body,
             };
 
-            let mut payload = TweetBody {
+            let payload = TweetBody {
                 text: tweet_body,
-                reply: None,
+                reply: reply_to.clone().map(|in_reply_to_tweet_id| Reply {
+                    in_reply_to_tweet_id,
+                }),
                 media: None,
+                quote_tweet_id: quote.clone(),
+                poll,
+            };
+            let tweet = tweet::Tweet::new(payload.clone())
+                .format(format.clone())
+                .auto_split(auto_split);
+
+            if check {
+                let lengths = twitter::text::measure(&tweet.parts());
+                let too_long = lengths.iter().any(PartLength::is_too_long);
+                output.print(lengths, |lengths| {
+                    let total = lengths.len();
+                    for length in lengths {
+                        let status = if length.is_too_long() {
+                            format!(" (over by {})", length.length - length.max)
+                        } else {
+                            String::new()
+                        };
+                        println!(
+                            "Tweet {}/{total}: {}/{}{status}\n{}\n",
+                            length.part, length.length, length.max, length.text
+                        );
+                    }
+                });
+                process::exit(if too_long { 1 } else { 0 });
+            }
+
+            if let Err(err) = tweet.check_length() {
+                exit_with_error(&err);
+            }
+
+            let images = matches
+                .subcommand_matches("tweet")
+                .map(images_with_alt)
+                .unwrap_or(Ok(vec![]));
+            let media_options = MediaOptions {
+                keep_metadata,
+                downscale,
+            };
+            let images = match images {
+                Ok(images) if images.is_empty() => front_matter.images(),
+                Ok(images) => images,
+                Err(err) => exit_with_error(&err),
             };
 
-            if let Some(media) = media_id {
-                let media_body = Media {
-                    media_ids: [media].to_vec(),
-                };
-                payload.media = Some(media_body);
+            let repeat = match front_matter.repeat() {
+                Ok(repeat) => repeat,
+                Err(err) => exit_with_error(&err),
+            };
+            if front_matter.schedule.is_some() || repeat.is_some() {
+                let on = front_matter.schedule.as_deref();
+                let body = thread_text(&tweet, &[], &format);
+                let schedule = new_schedule(&body, on, repeat)
+                    .payload(TweetBody {
+                        text: Some(body),
+                        ..payload
+                    })
+                    .images(absolute_images(images), media_options)
+                    .format(format)
+                    .auto_split(auto_split)
+                    .account(utils::load_config().current_account);
+                save_schedule(schedule, on);
+                return;
+            }
+
+            // Kept so a failed post can be saved as a draft. Polls have no place in a draft.
+            let draft = (!has_poll).then(|| drafts::Draft {
+                body: thread_text(&tweet, &[], &format),
+                images: absolute_images(images.clone()),
+                reply_to,
+                quote,
+                media_options,
+                format: Some(format),
+                auto_split,
+                ..Default::default()
+            });
+            let mut tweet = tweet;
+            if let Err(err) = tweet.upload_media(images, &media_options) {
+                save_failed_draft(draft, &err);
+                exit_with_error(&err)
             }
-            let mut tweet = tweet::Tweet::new(payload);
-            let api_res = tweet.create();
 
-            match api_res {
-                Ok(ok) => {
-                    println!("{}", ok.content)
+            match tweet.create() {
+                Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                Err(err) => {
+                    if !tweet.is_partially_posted() {
+                        save_failed_draft(draft, &err);
+                    }
+                    exit_with_error(&err)
                 }
-                Err(err) => println!("{}", err.message),
             }
         }
         Commands::Tweets { command } => match command {
             TweetsEnum::ById { id } => {
                 let tweet_res = twitter::tweets::TweetLookup::new(id).fetch();
                 match tweet_res {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             TweetsEnum::ByIds { ids } => {
                 let tweets = twitter::tweets::TweetsLookup::new(ids).fetch();
                 match tweets {
-                    Ok(ok) => {
-                        let tweets = ok.content.data;
-                        let includes = ok.content.includes;
+                    Ok(ok) => output.print(ok.content, |content| {
+                        let tweets = content.data;
+                        let includes = content.includes;
                         if tweets.is_empty() {
                             println!("No tweets found.");
                             return;
@@ -788,30 +1549,30 @@ pub fn run() {
                                 }
                             );
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             TweetsEnum::Delete { id } => {
                 let delete = twitter::tweet::DeleteTweet::new(id);
 
                 match delete.send() {
-                    Ok(ok) => {
-                        if ok.content.data.deleted {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.deleted {
                             println!("Deleted tweet.");
                         } else {
                             eprintln!("Tweet was not deleted.");
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             TweetsEnum::User { id } => {
                 let tweets = twitter::tweets::UserTweets::new(id).max_results(10).fetch();
                 match tweets {
-                    Ok(ok) => {
-                        let tweets = ok.content.data;
-                        let includes = ok.content.includes;
+                    Ok(ok) => output.print(ok.content, |content| {
+                        let tweets = content.data;
+                        let includes = content.includes;
                         if tweets.is_empty() {
                             println!("No tweets found.");
                             return;
@@ -826,8 +1587,8 @@ pub fn run() {
                                 }
                             );
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             TweetsEnum::Recent { query, max_results } => {
@@ -835,9 +1596,9 @@ pub fn run() {
                     .max_results(max_results)
                     .fetch();
                 match tweets {
-                    Ok(ok) => {
-                        let tweets = ok.content.data;
-                        let includes = ok.content.includes;
+                    Ok(ok) => output.print(ok.content, |content| {
+                        let tweets = content.data;
+                        let includes = content.includes;
                         if tweets.is_empty() {
                             println!("No recent tweets found.");
                             return;
@@ -852,22 +1613,22 @@ pub fn run() {
                                 }
                             );
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             TweetsEnum::CountRecent { query } => {
                 let counts = twitter::tweets::RecentTweetCounts::new(query).fetch();
                 match counts {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No tweet counts found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             TweetsEnum::All { query, max_results } => {
@@ -875,9 +1636,9 @@ pub fn run() {
                     .max_results(max_results)
                     .fetch();
                 match tweets {
-                    Ok(ok) => {
-                        let tweets = ok.content.data;
-                        let includes = ok.content.includes;
+                    Ok(ok) => output.print(ok.content, |content| {
+                        let tweets = content.data;
+                        let includes = content.includes;
                         if tweets.is_empty() {
                             println!("No tweets found.");
                             return;
@@ -892,22 +1653,22 @@ pub fn run() {
                                 }
                             );
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             TweetsEnum::CountAll { query } => {
                 let counts = twitter::tweets::AllTweetCounts::new(query).fetch();
                 match counts {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No tweet counts found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
@@ -929,16 +1690,323 @@ pub fn run() {
                 Args::parse_from(["", "config", "--help"]);
             }
         }
-        Commands::Usage {} => usage::show(),
+        Commands::Api {
+            method,
+            path,
+            raw_fields,
+            fields,
+            header,
+            input,
+            auth,
+            paginate,
+        } => {
+            let request =
+                build_raw_request(&method, &path, &raw_fields, &fields, &header, input, auth);
+            let response = match request {
+                Ok(request) if paginate => request.fetch_all(),
+                Ok(request) => request.fetch(),
+                Err(err) => exit_with_error(&err),
+            };
+
+            match response {
+                Ok(ok) => output.print(ok.content, |content| {
+                    if !content.is_null() {
+                        println!("{}", OutputFormat::Json.render(&content));
+                    }
+                }),
+                Err(err) => exit_with_error(&err),
+            }
+        }
+        Commands::Usage {} => usage::show(output),
+        Commands::Limits {} => {
+            let limits = twitter::rate_limit::RateLimits::default().all();
+            output.print(limits, |limits| {
+                if limits.is_empty() {
+                    println!("No rate limits recorded yet. They are saved after each API call.");
+                    return;
+                }
+
+                let now = Timestamp::now();
+                let mut table_builder = Builder::new();
+                table_builder.push_record(["Endpoint", "Remaining", "Limit", "Resets", "Updated"]);
+                for row in &limits {
+                    let resets = match row.reset_time() {
+                        Some(reset) if reset > now => reset
+                            .to_zoned(TimeZone::system())
+                            .strftime("%Y-%m-%d %H:%M:%S")
+                            .to_string(),
+                        Some(_) => "already reset".to_string(),
+                        None => "-".to_string(),
+                    };
+                    let remaining = if row.reset_time().is_some_and(|reset| reset <= now) {
+                        row.limit
+                    } else {
+                        row.remaining
+                    };
+                    table_builder.push_record([
+                        row.endpoint.clone(),
+                        remaining.to_string(),
+                        row.limit.to_string(),
+                        resets,
+                        row.updated_at.clone(),
+                    ]);
+                }
+
+                println!("{}", table_builder.build());
+            });
+        }
         Commands::Update {} => update::run(),
-        Commands::Schedule { command } => match command {
-            ScheduleEnum::New { body, on } => {
-                let schedule = schedule::Schedule::new(&body, &on);
-                if schedule.save() {
-                    println!("Tweet scheduled for {on}.");
-                } else {
-                    eprintln!("Could not schedule tweet.");
+        Commands::Drafts { command } => {
+            let store = drafts::Drafts::default();
+            match command {
+                DraftsEnum::New {
+                    body,
+                    reply_to,
+                    quote,
+                    editor,
+                    ..
+                } => {
+                    let Some(body) = read_tweet_text(body, editor, "draft.txt") else {
+                        gracefully_exit(
+                            "A draft needs a body: pass --body, pipe it or use --editor.",
+                        );
+                    };
+                    let images = matches
+                        .subcommand_matches("drafts")
+                        .and_then(|matches| matches.subcommand_matches("new"))
+                        .map(images_with_alt)
+                        .unwrap_or(Ok(vec![]));
+                    let draft = images.map(|images| drafts::Draft {
+                        body,
+                        images: absolute_images(images),
+                        reply_to,
+                        quote,
+                        account: utils::load_config().current_account,
+                        ..Default::default()
+                    });
+                    match draft.and_then(|draft| store.save(&draft)) {
+                        Ok(id) => println!("Saved draft {id}."),
+                        Err(err) => exit_with_error(&err),
+                    }
                 }
+                DraftsEnum::List {} => {
+                    let drafts = match store.all() {
+                        Ok(drafts) => drafts,
+                        Err(err) => exit_with_error(&err),
+                    };
+                    output.print(drafts, |drafts| {
+                        if drafts.is_empty() {
+                            println!("No drafts were found.");
+                            return;
+                        }
+
+                        let mut table_builder = Builder::new();
+                        table_builder.push_record([
+                            "Id",
+                            "Body",
+                            "Images",
+                            "Reply to",
+                            "Quote",
+                            "Account",
+                            "Last error",
+                            "Updated at",
+                        ]);
+                        for draft in &drafts {
+                            table_builder.push_record([
+                                draft.id.to_string(),
+                                if draft.body.chars().count() > 80 {
+                                    format!(
+                                        "{}...",
+                                        draft.body.chars().take(77).collect::<String>()
+                                    )
+                                } else {
+                                    draft.body.clone()
+                                },
+                                draft.images.len().to_string(),
+                                draft.reply_to.clone().unwrap_or_else(|| "-".to_string()),
+                                draft.quote.clone().unwrap_or_else(|| "-".to_string()),
+                                draft.account.to_string(),
+                                draft.last_error.clone().unwrap_or_else(|| "-".to_string()),
+                                draft.updated_at.clone(),
+                            ]);
+                        }
+                        println!("{}", table_builder.build());
+                        println!("Total: {}", drafts.len());
+                    });
+                }
+                DraftsEnum::Edit { id, body } => {
+                    let body = match body {
+                        Some(body) => body,
+                        None => {
+                            let draft = match store.get(id) {
+                                Ok(draft) => draft,
+                                Err(err) => exit_with_error(&err),
+                            };
+                            let temp_file = editor_file(&format!("draft-{id}.txt"));
+                            if fs::write(temp_file.path(), &draft.body).is_err() {
+                                gracefully_exit("Failed to write the draft for the editor.");
+                            }
+                            if !utils::open_editor(temp_file.path()).success() {
+                                gracefully_exit("Failed to open the default editor");
+                            }
+                            match fs::read_to_string(temp_file.path()) {
+                                Ok(body) => body.trim_end().to_string(),
+                                Err(_) => {
+                                    gracefully_exit("Failed to read the draft from the editor.")
+                                }
+                            }
+                        }
+                    };
+                    match store.update_body(id, &body) {
+                        Ok(()) => println!("Updated draft {id}."),
+                        Err(err) => exit_with_error(&err),
+                    }
+                }
+                DraftsEnum::Show { id } => match store.get(id) {
+                    Ok(draft) => output.print(draft, |draft| {
+                        println!("Draft {} (account {})", draft.id, draft.account);
+                        if let Some(reply_to) = &draft.reply_to {
+                            println!("Reply to: {reply_to}");
+                        }
+                        if let Some(quote) = &draft.quote {
+                            println!("Quote: {quote}");
+                        }
+                        for image in &draft.images {
+                            match &image.alt {
+                                Some(alt) => println!("Image: {} ({alt})", image.path.display()),
+                                None => println!("Image: {}", image.path.display()),
+                            }
+                        }
+                        if let Some(last_error) = &draft.last_error {
+                            println!("Last error: {last_error}");
+                        }
+                        println!("Updated at: {}\n\n{}", draft.updated_at, draft.body);
+                    }),
+                    Err(err) => exit_with_error(&err),
+                },
+                DraftsEnum::Publish {
+                    id,
+                    auto_split,
+                    numbering,
+                    continuation,
+                } => {
+                    let draft = match store.get(id) {
+                        Ok(draft) => draft,
+                        Err(err) => exit_with_error(&err),
+                    };
+                    utils::use_account(draft.account);
+
+                    let payload = TweetBody {
+                        text: Some(draft.body),
+                        reply: draft.reply_to.map(|in_reply_to_tweet_id| Reply {
+                            in_reply_to_tweet_id,
+                        }),
+                        media: None,
+                        quote_tweet_id: draft.quote,
+                        poll: None,
+                    };
+                    let mut format = draft.format.unwrap_or_else(utils::thread_format);
+                    if let Some(numbering) = numbering {
+                        format.numbering = numbering;
+                    }
+                    if continuation.is_some() {
+                        format.continuation = continuation;
+                    }
+                    let mut tweet = tweet::Tweet::new(payload)
+                        .format(format)
+                        .auto_split(auto_split || draft.auto_split);
+                    let result = tweet
+                        .check_length()
+                        .and_then(|()| tweet.upload_media(draft.images, &draft.media_options));
+                    if let Err(err) = result {
+                        if !err.is_dry_run()
+                            && let Err(err) = store.failed(id, &err)
+                        {
+                            eprintln!("{err}");
+                        }
+                        exit_with_error(&err)
+                    }
+
+                    match tweet.create() {
+                        Ok(ok) => {
+                            if let Err(err) = store.delete(id) {
+                                eprintln!("{err}");
+                            }
+                            output.print(ok.content, |content| println!("{}", content))
+                        }
+                        Err(err) if err.is_dry_run() => exit_with_error(&err),
+                        Err(err) => {
+                            // Once part of a thread is live, `tweet --resume` takes over.
+                            let recorded = if tweet.is_partially_posted() {
+                                store.delete(id).map(|_| ())
+                            } else {
+                                store.failed(id, &err)
+                            };
+                            if let Err(err) = recorded {
+                                eprintln!("{err}");
+                            }
+                            exit_with_error(&err)
+                        }
+                    }
+                }
+                DraftsEnum::Delete { id } => match store.delete(id) {
+                    Ok(true) => println!("Deleted draft {id}."),
+                    Ok(false) => {
+                        exit_with_error(&TwitterError::local(format!("No draft with id {id}.")))
+                    }
+                    Err(err) => exit_with_error(&err),
+                },
+            }
+        }
+        Commands::Schedule { command } => match command {
+            ScheduleEnum::New {
+                body,
+                on,
+                repeat,
+                reply_to,
+                quote,
+                editor,
+                ..
+            } => {
+                let repeat = match repeat.as_deref().map(str::parse).transpose() {
+                    Ok(repeat) => repeat,
+                    Err(err) => exit_with_error(&TwitterError::local(err)),
+                };
+                let Some(body) = read_tweet_text(body, editor, "schedule.txt") else {
+                    gracefully_exit(
+                        "A scheduled tweet needs a body: pass --body, pipe it or use --editor.",
+                    );
+                };
+                let images = matches
+                    .subcommand_matches("schedule")
+                    .and_then(|matches| matches.subcommand_matches("new"))
+                    .map(images_with_alt)
+                    .unwrap_or(Ok(vec![]));
+                let images = match images {
+                    Ok(images) => images,
+                    Err(err) => exit_with_error(&err),
+                };
+
+                let format = utils::thread_format();
+                let tweet = tweet::Tweet::new(TweetBody {
+                    text: Some(body),
+                    ..Default::default()
+                })
+                .format(format.clone());
+                let body = thread_text(&tweet, &[], &format);
+                let schedule = new_schedule(&body, on.as_deref(), repeat)
+                    .payload(TweetBody {
+                        text: Some(body.clone()),
+                        reply: reply_to.map(|in_reply_to_tweet_id| Reply {
+                            in_reply_to_tweet_id,
+                        }),
+                        quote_tweet_id: quote,
+                        ..Default::default()
+                    })
+                    .images(absolute_images(images), MediaOptions::default())
+                    .format(format)
+                    .account(utils::load_config().current_account);
+                save_schedule(schedule, on.as_deref());
             }
             ScheduleEnum::Clear {} => {
                 let schedule = schedule::Schedule::default();
@@ -949,109 +2017,144 @@ pub fn run() {
             ScheduleEnum::Run {} => {
                 send_due_tweets();
             }
+            ScheduleEnum::Retry { id, .. } => utils::retry_failed_tweets(id),
+            ScheduleEnum::Daemon {} => {
+                let stop = Arc::new(AtomicBool::new(false));
+                let signal = stop.clone();
+                if let Err(err) = ctrlc::set_handler(move || signal.store(true, Ordering::Relaxed))
+                {
+                    gracefully_exit(&format!("Failed to listen for stop signals: {err}"));
+                }
+                let retry = utils::schedule_retry();
+                let runs = ScheduleRuns::default();
+                Daemon::new(schedule::Schedule::default(), SystemClock, stop)
+                    .retry(retry.clone())
+                    .record_runs(ScheduleRuns::default())
+                    .run(|schedule, tweet| {
+                        // The outcome of every send is the last result for `schedule status`.
+                        // Tweets saved without an account go out from the one selected now.
+                        let account = utils::configured_account();
+                        match utils::send_scheduled_tweet(schedule, tweet, account, &retry) {
+                            Ok(()) => runs.record(None),
+                            Err(err) if err.is_dry_run() => {}
+                            Err(err) => runs.record(Some(&err.to_string())),
+                        }
+                    });
+            }
+            ScheduleEnum::Install(args) => scheduler::install(args.runner()),
+            ScheduleEnum::Uninstall(args) => scheduler::uninstall(args.runner()),
+            ScheduleEnum::Status {} => output.print(scheduler::status(), scheduler::print_status),
             ScheduleEnum::List(list_args) => {
                 let schedule = schedule::Schedule::default();
-                let mut table_builder = Builder::new();
                 let filter = list_args.filter.clone();
                 let mut tweets = match filter {
                     ListFilter::All => schedule.all(),
                     ListFilter::Failed => schedule.failed(),
                     ListFilter::Sent => schedule.sent(),
                 };
-                if tweets.is_empty() {
-                    println!("No scheduled tweets were found.");
-                    return;
-                }
-
                 tweets.sort_by(|a, b| a.scheduled_for.cmp(&b.scheduled_for));
 
-                let show_last_error = matches!(filter, ListFilter::All | ListFilter::Failed);
-                let show_sent_at = matches!(filter, ListFilter::All | ListFilter::Sent);
-
-                let mut headers = vec![
-                    "Id".to_string(),
-                    "Status".to_string(),
-                    "Body".to_string(),
-                    "Send time".to_string(),
-                    "Attempts".to_string(),
-                ];
-
-                if show_last_error {
-                    headers.push("Last error".to_string());
-                }
+                output.print(tweets, |tweets| {
+                    if tweets.is_empty() {
+                        println!("No scheduled tweets were found.");
+                        return;
+                    }
 
-                if show_sent_at {
-                    headers.push("Sent at".to_string());
-                }
+                    let mut table_builder = Builder::new();
 
-                table_builder.push_record(headers);
+                    let show_last_error = matches!(filter, ListFilter::All | ListFilter::Failed);
+                    let show_sent_at = matches!(filter, ListFilter::All | ListFilter::Sent);
 
-                for row in &tweets {
-                    let mut record = vec![
-                        row.id.to_string(),
-                        row.status.to_string(),
-                        if row.body.chars().count() > 80 {
-                            format!("{}...", row.body.chars().take(77).collect::<String>())
-                        } else {
-                            row.body.clone()
-                        },
-                        row.scheduled_for.clone(),
-                        row.attempts.to_string(),
+                    let mut headers = vec![
+                        "Id".to_string(),
+                        "Status".to_string(),
+                        "Body".to_string(),
+                        "Send time".to_string(),
+                        "Repeats".to_string(),
+                        "Attempts".to_string(),
                     ];
+
                     if show_last_error {
-                        record.push(row.last_error.clone().unwrap_or_else(|| "-".to_string()));
+                        headers.push("Last error".to_string());
                     }
+
                     if show_sent_at {
-                        record.push(row.sent_at.clone().unwrap_or_else(|| "-".to_string()));
+                        headers.push("Sent at".to_string());
                     }
-                    table_builder.push_record(record);
-                }
-
-                let table = table_builder.build();
-                println!("{table}");
-
-                if matches!(filter, ListFilter::All) {
-                    let pending = tweets
-                        .iter()
-                        .filter(|row| matches!(row.status, schedule::ScheduleStatus::Pending))
-                        .count();
-                    let failed = tweets
-                        .iter()
-                        .filter(|row| matches!(row.status, schedule::ScheduleStatus::Failed))
-                        .count();
-                    let sent = tweets
-                        .iter()
-                        .filter(|row| matches!(row.status, schedule::ScheduleStatus::Sent))
-                        .count();
-                    println!(
-                        "Total: {} (Pending: {}, Failed: {}, Sent: {})",
-                        tweets.len(),
-                        pending,
-                        failed,
-                        sent
-                    );
-                } else {
-                    println!("Total: {}", tweets.len());
-                }
+
+                    table_builder.push_record(headers);
+
+                    for row in &tweets {
+                        let mut record = vec![
+                            row.id.to_string(),
+                            row.status.to_string(),
+                            if row.body.chars().count() > 80 {
+                                format!("{}...", row.body.chars().take(77).collect::<String>())
+                            } else {
+                                row.body.clone()
+                            },
+                            row.scheduled_for.clone(),
+                            row.recurrence.clone().unwrap_or_else(|| "-".to_string()),
+                            row.attempts.to_string(),
+                        ];
+                        if show_last_error {
+                            record.push(row.last_error.clone().unwrap_or_else(|| "-".to_string()));
+                        }
+                        if show_sent_at {
+                            record.push(row.sent_at.clone().unwrap_or_else(|| "-".to_string()));
+                        }
+                        table_builder.push_record(record);
+                    }
+
+                    let table = table_builder.build();
+                    println!("{table}");
+
+                    if matches!(filter, ListFilter::All) {
+                        let pending = tweets
+                            .iter()
+                            .filter(|row| matches!(row.status, schedule::ScheduleStatus::Pending))
+                            .count();
+                        let failed = tweets
+                            .iter()
+                            .filter(|row| matches!(row.status, schedule::ScheduleStatus::Failed))
+                            .count();
+                        let sent = tweets
+                            .iter()
+                            .filter(|row| matches!(row.status, schedule::ScheduleStatus::Sent))
+                            .count();
+                        println!(
+                            "Total: {} (Pending: {}, Failed: {}, Sent: {})",
+                            tweets.len(),
+                            pending,
+                            failed,
+                            sent
+                        );
+                    } else {
+                        println!("Total: {}", tweets.len());
+                    }
+                });
             }
         },
         Commands::Likes { command } => match command {
             LikesEnum::By {
                 tweet_id,
                 max_results,
+                page,
             } => {
-                let users = twitter::likes::LikingUsers::new(tweet_id).max_results(max_results);
+                let page = page.pagination();
+                let users = twitter::likes::LikingUsers::new(tweet_id)
+                    .max_results(page.page_size(max_results));
 
-                match users.fetch() {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                match page.collect(&users) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No liking users found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             LikesEnum::Create { tweet_id } => {
@@ -1059,16 +2162,16 @@ pub fn run() {
 
                 match like {
                     Ok(like) => match like.send() {
-                        Ok(ok) => {
-                            if ok.content.data.liked {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.liked {
                                 println!("Liked tweet.");
                             } else {
                                 eprintln!("Tweet was not liked.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             LikesEnum::Delete { tweet_id } => {
@@ -1076,33 +2179,31 @@ pub fn run() {
 
                 match unlike {
                     Ok(unlike) => match unlike.send() {
-                        Ok(ok) => {
-                            if ok.content.data.liked {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.liked {
                                 eprintln!("Tweet is still liked.");
                             } else {
                                 println!("Removed like.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            LikesEnum::Tweets {} => {
+            LikesEnum::Tweets { page } => {
+                let page = page.pagination();
                 let user_id = match utils::get_current_user_id() {
                     Ok(id) => id,
-                    Err(err) => {
-                        eprintln!("{err}");
-                        return;
-                    }
+                    Err(err) => exit_with_error(&err),
                 };
 
-                let likes = twitter::likes::Likes::new(user_id).max_results(10);
-                let likes_res = likes.fetch();
+                let likes = twitter::likes::Likes::new(user_id).max_results(page.page_size(10));
+                let likes_res = page.collect(&likes);
                 match likes_res {
-                    Ok(ok) => {
-                        let tweets = ok.content.data;
-                        let includes = ok.content.includes;
+                    Ok(ok) => output.print(ok.content, |content| {
+                        let tweets = content.data;
+                        let includes = content.includes;
                         if tweets.is_empty() {
                             println!("No liked tweets found.");
                             return;
@@ -1117,21 +2218,22 @@ pub fn run() {
                                 }
                             );
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
         Commands::Bookmarks { command } => match command {
-            BookmarksEnum::List { max_results } => {
+            BookmarksEnum::List { max_results, page } => {
+                let page = page.pagination();
                 let bookmarks = twitter::bookmarks::Bookmarks::current_user()
-                    .map(|bookmarks| bookmarks.max_results(max_results));
+                    .map(|bookmarks| bookmarks.max_results(page.page_size(max_results)));
 
                 match bookmarks {
-                    Ok(bookmarks) => match bookmarks.fetch() {
-                        Ok(ok) => {
-                            let tweets = ok.content.data;
-                            let includes = ok.content.includes;
+                    Ok(bookmarks) => match page.collect(&bookmarks) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            let tweets = content.data;
+                            let includes = content.includes;
                             if tweets.is_empty() {
                                 println!("No bookmarks found.");
                                 return;
@@ -1146,10 +2248,10 @@ pub fn run() {
                                     }
                                 );
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             BookmarksEnum::Create { tweet_id } => {
@@ -1157,16 +2259,16 @@ pub fn run() {
 
                 match bookmark {
                     Ok(bookmark) => match bookmark.send() {
-                        Ok(ok) => {
-                            if ok.content.data.bookmarked {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.bookmarked {
                                 println!("Bookmarked tweet.");
                             } else {
                                 eprintln!("Tweet was not bookmarked.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             BookmarksEnum::Delete { tweet_id } => {
@@ -1174,49 +2276,52 @@ pub fn run() {
 
                 match bookmark {
                     Ok(bookmark) => match bookmark.send() {
-                        Ok(ok) => {
-                            if ok.content.data.bookmarked {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.bookmarked {
                                 eprintln!("Tweet is still bookmarked.");
                             } else {
                                 println!("Removed bookmark.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            BookmarksEnum::Folders { max_results } => {
+            BookmarksEnum::Folders { max_results, page } => {
+                let page = page.pagination();
                 let folders = twitter::bookmarks::BookmarkFolders::current_user()
-                    .map(|folders| folders.max_results(max_results));
+                    .map(|folders| folders.max_results(page.page_size(max_results)));
 
                 match folders {
-                    Ok(folders) => match folders.fetch() {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                    Ok(folders) => match page.collect(&folders) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("No bookmark folders found.");
                                 return;
                             }
 
-                            println!("{}", ok.content);
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                            println!("{}", content);
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             BookmarksEnum::Folder {
                 folder_id,
                 max_results,
+                page,
             } => {
+                let page = page.pagination();
                 let bookmarks = twitter::bookmarks::BookmarkFolderTweets::current_user(folder_id)
-                    .map(|bookmarks| bookmarks.max_results(max_results));
+                    .map(|bookmarks| bookmarks.max_results(page.page_size(max_results)));
 
                 match bookmarks {
-                    Ok(bookmarks) => match bookmarks.fetch() {
-                        Ok(ok) => {
-                            let tweets = ok.content.data;
-                            let includes = ok.content.includes;
+                    Ok(bookmarks) => match page.collect(&bookmarks) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            let tweets = content.data;
+                            let includes = content.includes;
                             if tweets.is_empty() {
                                 println!("No bookmarks found in the folder.");
                                 return;
@@ -1231,10 +2336,10 @@ pub fn run() {
                                     }
                                 );
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
@@ -1243,8 +2348,8 @@ pub fn run() {
                 let list = twitter::lists::ListLookup::new(list_id);
 
                 match list.fetch() {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             ListsEnum::Create {
@@ -1257,27 +2362,28 @@ pub fn run() {
                     .private(private);
 
                 match create.send() {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            ListsEnum::Owned { max_results } => {
+            ListsEnum::Owned { max_results, page } => {
+                let page = page.pagination();
                 let lists = twitter::lists::OwnedLists::current_user()
-                    .map(|lists| lists.max_results(max_results));
+                    .map(|lists| lists.max_results(page.page_size(max_results)));
 
                 match lists {
-                    Ok(lists) => match lists.fetch() {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                    Ok(lists) => match page.collect(&lists) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("No owned lists found.");
                                 return;
                             }
 
-                            println!("{}", ok.content);
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                            println!("{}", content);
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             ListsEnum::Update {
@@ -1297,58 +2403,64 @@ pub fn run() {
                 }
 
                 match update.send() {
-                    Ok(ok) => {
-                        if ok.content.data.updated {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.updated {
                             println!("Updated list.");
                         } else {
                             eprintln!("List was not updated.");
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             ListsEnum::Delete { list_id } => {
                 let delete = twitter::lists::DeleteList::new(list_id);
 
                 match delete.send() {
-                    Ok(ok) => {
-                        if ok.content.data.deleted {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.deleted {
                             println!("Deleted list.");
                         } else {
                             eprintln!("List was not deleted.");
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             ListsEnum::Members {
                 list_id,
                 max_results,
+                page,
             } => {
-                let members = twitter::lists::ListMembers::new(list_id).max_results(max_results);
+                let page = page.pagination();
+                let members = twitter::lists::ListMembers::new(list_id)
+                    .max_results(page.page_size(max_results));
 
-                match members.fetch() {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                match page.collect(&members) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No list members found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             ListsEnum::Tweets {
                 list_id,
                 max_results,
+                page,
             } => {
-                let tweets = twitter::lists::ListTweets::new(list_id).max_results(max_results);
-
-                match tweets.fetch() {
-                    Ok(ok) => {
-                        let tweets = ok.content.data;
-                        let includes = ok.content.includes;
+                let page = page.pagination();
+                let tweets = twitter::lists::ListTweets::new(list_id)
+                    .max_results(page.page_size(max_results));
+
+                match page.collect(&tweets) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        let tweets = content.data;
+                        let includes = content.includes;
                         if tweets.is_empty() {
                             println!("No list tweets found.");
                             return;
@@ -1363,22 +2475,22 @@ pub fn run() {
                                 }
                             );
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             ListsEnum::AddMember { list_id, user_id } => {
                 let add = twitter::lists::CreateListMember::new(list_id, user_id);
 
                 match add.send() {
-                    Ok(ok) => {
-                        if ok.content.data.is_member {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_member {
                             println!("Added user to the list.");
                         } else {
                             eprintln!("User was not added to the list.");
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             ListsEnum::RemoveMember { list_id, user_id } => {
@@ -1389,35 +2501,36 @@ pub fn run() {
 
                 match remove {
                     Ok(remove) => match remove.send() {
-                        Ok(ok) => {
-                            if ok.content.data.is_member {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_member {
                                 eprintln!("User is still a member of the list.");
                             } else {
                                 println!("Removed user from the list.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            ListsEnum::Memberships { max_results } => {
+            ListsEnum::Memberships { max_results, page } => {
+                let page = page.pagination();
                 let lists = twitter::lists::ListMemberships::current_user()
-                    .map(|lists| lists.max_results(max_results));
+                    .map(|lists| lists.max_results(page.page_size(max_results)));
 
                 match lists {
-                    Ok(lists) => match lists.fetch() {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                    Ok(lists) => match page.collect(&lists) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("No list memberships found.");
                                 return;
                             }
 
-                            println!("{}", ok.content);
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                            println!("{}", content);
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
@@ -1425,58 +2538,63 @@ pub fn run() {
             DmsEnum::ConversationEvents {
                 conversation_id,
                 max_results,
+                page,
             } => {
+                let page = page.pagination();
                 let events = twitter::dms::ConversationDmEvents::new(conversation_id)
-                    .max_results(max_results);
+                    .max_results(page.page_size(max_results));
 
-                match events.fetch() {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                match page.collect(&events) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No conversation DM events found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            DmsEnum::Events { max_results } => {
+            DmsEnum::Events { max_results, page } => {
+                let page = page.pagination();
                 let events = twitter::dms::UserDmEvents::current_user()
-                    .map(|events| events.max_results(max_results));
+                    .map(|events| events.max_results(page.page_size(max_results)));
 
                 match events {
-                    Ok(events) => match events.fetch() {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                    Ok(events) => match page.collect(&events) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("No DM events found.");
                                 return;
                             }
 
-                            println!("{}", ok.content);
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                            println!("{}", content);
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             DmsEnum::With {
                 participant_id,
                 max_results,
+                page,
             } => {
-                let events =
-                    twitter::dms::ParticipantDmEvents::new(participant_id).max_results(max_results);
+                let page = page.pagination();
+                let events = twitter::dms::ParticipantDmEvents::new(participant_id)
+                    .max_results(page.page_size(max_results));
 
-                match events.fetch() {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                match page.collect(&events) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No participant DM events found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             DmsEnum::Create {
@@ -1486,8 +2604,8 @@ pub fn run() {
                 let conversation = twitter::dms::CreateConversation::new(participant_ids, text);
 
                 match conversation.send() {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             DmsEnum::SendWith {
@@ -1497,8 +2615,8 @@ pub fn run() {
                 let message = twitter::dms::SendWithParticipantMessage::new(participant_id, text);
 
                 match message.send() {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             DmsEnum::Send {
@@ -1508,8 +2626,8 @@ pub fn run() {
                 let message = twitter::dms::SendConversationMessage::new(conversation_id, text);
 
                 match message.send() {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
@@ -1517,19 +2635,22 @@ pub fn run() {
             RetweetsEnum::By {
                 tweet_id,
                 max_results,
+                page,
             } => {
-                let users = twitter::retweets::RetweetedBy::new(tweet_id).max_results(max_results);
+                let page = page.pagination();
+                let users = twitter::retweets::RetweetedBy::new(tweet_id)
+                    .max_results(page.page_size(max_results));
 
-                match users.fetch() {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                match page.collect(&users) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No retweeters found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             RetweetsEnum::Create { tweet_id } => {
@@ -1537,16 +2658,16 @@ pub fn run() {
 
                 match create {
                     Ok(create) => match create.send() {
-                        Ok(ok) => {
-                            if ok.content.data.retweeted {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.retweeted {
                                 println!("Retweeted tweet.");
                             } else {
                                 eprintln!("Tweet was not retweeted.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             RetweetsEnum::Delete { tweet_id } => {
@@ -1554,16 +2675,16 @@ pub fn run() {
 
                 match delete {
                     Ok(delete) => match delete.send() {
-                        Ok(ok) => {
-                            if ok.content.data.retweeted {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.retweeted {
                                 eprintln!("Tweet is still retweeted.");
                             } else {
                                 println!("Removed retweet.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
@@ -1573,35 +2694,36 @@ pub fn run() {
 
                 match create {
                     Ok(create) => match create.send() {
-                        Ok(ok) => {
-                            if ok.content.data.muting {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.muting {
                                 println!("Muted user.");
                             } else {
                                 eprintln!("User was not muted.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            MutesEnum::List { max_results } => {
+            MutesEnum::List { max_results, page } => {
+                let page = page.pagination();
                 let users = twitter::mutes::MutedUsers::current_user()
-                    .map(|users| users.max_results(max_results));
+                    .map(|users| users.max_results(page.page_size(max_results)));
 
                 match users {
-                    Ok(users) => match users.fetch() {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                    Ok(users) => match page.collect(&users) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("No muted users found.");
                                 return;
                             }
 
-                            println!("{}", ok.content);
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                            println!("{}", content);
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             MutesEnum::Delete { target_user_id } => {
@@ -1609,16 +2731,16 @@ pub fn run() {
 
                 match delete {
                     Ok(delete) => match delete.send() {
-                        Ok(ok) => {
-                            if ok.content.data.muting {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.muting {
                                 eprintln!("Current user is still muting that user.");
                             } else {
                                 println!("Unmuted user.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
@@ -1628,35 +2750,36 @@ pub fn run() {
 
                 match create {
                     Ok(create) => match create.send() {
-                        Ok(ok) => {
-                            if ok.content.data.blocking {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.blocking {
                                 println!("Blocked user.");
                             } else {
                                 eprintln!("User was not blocked.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            BlocksEnum::List { max_results } => {
+            BlocksEnum::List { max_results, page } => {
+                let page = page.pagination();
                 let users = twitter::blocks::BlockedUsers::current_user()
-                    .map(|users| users.max_results(max_results));
+                    .map(|users| users.max_results(page.page_size(max_results)));
 
                 match users {
-                    Ok(users) => match users.fetch() {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                    Ok(users) => match page.collect(&users) {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("No blocked users found.");
                                 return;
                             }
 
-                            println!("{}", ok.content);
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                            println!("{}", content);
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             BlocksEnum::Delete { target_user_id } => {
@@ -1664,35 +2787,34 @@ pub fn run() {
 
                 match delete {
                     Ok(delete) => match delete.send() {
-                        Ok(ok) => {
-                            if ok.content.data.blocking {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.blocking {
                                 eprintln!("Current user is still blocking that user.");
                             } else {
                                 println!("Unblocked user.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
         Commands::Timeline { command } => match command {
-            TimelineEnum::ReverseChronological {} => {
+            TimelineEnum::ReverseChronological { page } => {
+                let page = page.pagination();
                 let user_id = match utils::get_current_user_id() {
                     Ok(id) => id,
-                    Err(err) => {
-                        eprintln!("{err}");
-                        return;
-                    }
+                    Err(err) => exit_with_error(&err),
                 };
 
-                let timeline = twitter::timeline::Timeline::new(user_id).max_results(10);
-                let timeline_res = timeline.fetch();
+                let timeline =
+                    twitter::timeline::Timeline::new(user_id).max_results(page.page_size(10));
+                let timeline_res = page.collect(&timeline);
                 match timeline_res {
-                    Ok(ok) => {
-                        let tweets = ok.content.data;
-                        let includes = ok.content.includes;
+                    Ok(ok) => output.print(ok.content, |content| {
+                        let tweets = content.data;
+                        let includes = content.includes;
                         if tweets.is_empty() {
                             println!("No tweets found in timeline.");
                             return;
@@ -1707,26 +2829,25 @@ pub fn run() {
                                 }
                             );
                         }
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
-        Commands::Mentions {} => {
+        Commands::Mentions { page } => {
+            let page = page.pagination();
             let user_id = match utils::get_current_user_id() {
                 Ok(id) => id,
-                Err(err) => {
-                    eprintln!("{err}");
-                    return;
-                }
+                Err(err) => exit_with_error(&err),
             };
 
-            let mentions = twitter::mentions::Mentions::new(user_id).max_results(10);
-            let mentions_res = mentions.fetch();
+            let mentions =
+                twitter::mentions::Mentions::new(user_id).max_results(page.page_size(10));
+            let mentions_res = page.collect(&mentions);
             match mentions_res {
-                Ok(ok) => {
-                    let tweets = ok.content.data;
-                    let includes = ok.content.includes;
+                Ok(ok) => output.print(ok.content, |content| {
+                    let tweets = content.data;
+                    let includes = content.includes;
                     if tweets.is_empty() {
                         println!("No mentions found.");
                         return;
@@ -1741,8 +2862,8 @@ pub fn run() {
                             }
                         );
                     }
-                }
-                Err(err) => eprintln!("{}", err.message),
+                }),
+                Err(err) => exit_with_error(&err),
             }
         }
         Commands::Streams { command } => match command {
@@ -1750,8 +2871,15 @@ pub fn run() {
                 let stream =
                     twitter::streams::FilteredStream::new().backfill_minutes(backfill_minutes);
 
-                if let Err(err) = stream.connect() {
-                    eprintln!("{}", err.message);
+                let connected =
+                    stream.connect(
+                        |line| match serde_json::from_str::<serde_json::Value>(line) {
+                            Ok(event) => output.print(event, |_| println!("{line}")),
+                            Err(_) => println!("{line}"),
+                        },
+                    );
+                if let Err(err) = connected {
+                    exit_with_error(&err);
                 }
             }
             StreamsEnum::Rules { command } => match command {
@@ -1759,37 +2887,37 @@ pub fn run() {
                     let rules = twitter::streams::StreamRules.fetch();
 
                     match rules {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("No stream rules found.");
                                 return;
                             }
 
-                            println!("{}", ok.content);
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                            println!("{}", content);
+                        }),
+                        Err(err) => exit_with_error(&err),
                     }
                 }
                 StreamRulesEnum::Add { value, tag } => {
                     let create = twitter::streams::AddStreamRule::new(value, tag);
 
                     match create.send() {
-                        Ok(ok) => {
-                            if ok.content.data.is_empty() {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.is_empty() {
                                 println!("Stream rule request sent.");
                             } else {
-                                println!("{}", ok.content.data[0].value);
+                                println!("{}", content.data[0].value);
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     }
                 }
                 StreamRulesEnum::Delete { ids } => {
                     let delete = twitter::streams::DeleteStreamRules::new(ids);
 
                     match delete.send() {
-                        Ok(_) => println!("Deleted stream rules."),
-                        Err(err) => eprintln!("{}", err.message),
+                        Ok(ok) => output.print(ok.content, |_| println!("Deleted stream rules.")),
+                        Err(err) => exit_with_error(&err),
                     }
                 }
             },
@@ -1798,75 +2926,85 @@ pub fn run() {
             UsersEnum::ById { id } => {
                 let user = twitter::user::UserLookup::new(id).fetch();
                 match user {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             UsersEnum::ByIds { ids } => {
                 let users = twitter::user::UsersLookup::new(ids).fetch();
                 match users {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No users found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             UsersEnum::ByUsername { username } => {
                 let user = twitter::user::UserLookupByUsername::new(username).fetch();
                 match user {
-                    Ok(ok) => println!("{}", ok.content),
-                    Err(err) => eprintln!("{}", err.message),
+                    Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             UsersEnum::ByUsernames { usernames } => {
                 let users = twitter::user::UsersLookupByUsernames::new(usernames).fetch();
                 match users {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No users found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            UsersEnum::Following { id, max_results } => {
-                let users = twitter::follows::Following::new(id)
-                    .max_results(max_results)
-                    .fetch();
-                match users {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+            UsersEnum::Following {
+                id,
+                max_results,
+                page,
+            } => {
+                let page = page.pagination();
+                let users =
+                    twitter::follows::Following::new(id).max_results(page.page_size(max_results));
+
+                match page.collect(&users) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No following users found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
-            UsersEnum::Followers { id, max_results } => {
-                let users = twitter::follows::Followers::new(id)
-                    .max_results(max_results)
-                    .fetch();
-                match users {
-                    Ok(ok) => {
-                        if ok.content.data.is_empty() {
+            UsersEnum::Followers {
+                id,
+                max_results,
+                page,
+            } => {
+                let page = page.pagination();
+                let users =
+                    twitter::follows::Followers::new(id).max_results(page.page_size(max_results));
+
+                match page.collect(&users) {
+                    Ok(ok) => output.print(ok.content, |content| {
+                        if content.data.is_empty() {
                             println!("No followers found.");
                             return;
                         }
 
-                        println!("{}", ok.content);
-                    }
-                    Err(err) => eprintln!("{}", err.message),
+                        println!("{}", content);
+                    }),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             UsersEnum::Follow { target_user_id } => {
@@ -1874,16 +3012,16 @@ pub fn run() {
 
                 match follow {
                     Ok(follow) => match follow.send() {
-                        Ok(ok) => {
-                            if ok.content.data.following || ok.content.data.pending_follow {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.following || content.data.pending_follow {
                                 println!("Follow request sent.");
                             } else {
                                 eprintln!("User was not followed.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
             UsersEnum::Unfollow { target_user_id } => {
@@ -1891,25 +3029,59 @@ pub fn run() {
 
                 match unfollow {
                     Ok(unfollow) => match unfollow.send() {
-                        Ok(ok) => {
-                            if ok.content.data.following {
+                        Ok(ok) => output.print(ok.content, |content| {
+                            if content.data.following {
                                 eprintln!("User is still followed.");
                             } else {
                                 println!("Unfollowed user.");
                             }
-                        }
-                        Err(err) => eprintln!("{}", err.message),
+                        }),
+                        Err(err) => exit_with_error(&err),
                     },
-                    Err(err) => eprintln!("{}", err.message),
+                    Err(err) => exit_with_error(&err),
                 }
             }
         },
         Commands::Me {} => {
             let me_res = twitter::user::me();
             match me_res {
-                Ok(ok) => println!("{}", ok.content),
-                Err(err) => eprintln!("{}", err.message),
+                Ok(ok) => output.print(ok.content, |content| println!("{}", content)),
+                Err(err) => exit_with_error(&err),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweet_matches(args: &[&str]) -> ArgMatches {
+        let matches = Args::command()
+            .try_get_matches_from(["twitter", "tweet"].iter().chain(args))
+            .unwrap();
+        matches.subcommand_matches("tweet").unwrap().clone()
+    }
+
+    #[test]
+    fn test_alt_text_pairs_with_the_preceding_image() {
+        let matches = tweet_matches(&[
+            "--image", "a.png", "--alt", "First", "--image", "b.png", "--image", "c.png", "--alt",
+            "Third",
+        ]);
+
+        let images = images_with_alt(&matches).unwrap();
+        let alts: Vec<Option<&str>> = images.iter().map(|image| image.alt.as_deref()).collect();
+        assert_eq!(alts, vec![Some("First"), None, Some("Third")]);
+        assert_eq!(images[1].path, PathBuf::from("b.png"));
+    }
+
+    #[test]
+    fn test_alt_text_must_follow_an_image() {
+        let before = tweet_matches(&["--alt", "Orphan", "--image", "a.png"]);
+        assert!(images_with_alt(&before).is_err());
+
+        let twice = tweet_matches(&["--image", "a.png", "--alt", "One", "--alt", "Two"]);
+        assert!(images_with_alt(&twice).is_err());
+    }
+}
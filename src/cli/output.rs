@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+use tabled::builder::Builder;
+
+use crate::utils::gracefully_exit;
+
+/// How a command prints what it fetched. Set with the global `--output` flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Plain,
+    /// The response as pretty-printed JSON
+    Json,
+    /// One JSON object per line, one line per item
+    Jsonl,
+    /// One row per item, nested fields flattened into dotted columns
+    Csv,
+    /// Like csv, drawn as a table
+    Table,
+}
+
+impl OutputFormat {
+    /// Prints `content` in this format, or hands it to `plain` for the human readable output.
+    pub fn print<T: Serialize>(self, content: T, plain: impl FnOnce(T)) {
+        if self == OutputFormat::Plain {
+            return plain(content);
+        }
+
+        let value = match serde_json::to_value(&content) {
+            Ok(value) => value,
+            Err(err) => gracefully_exit(&format!("Failed to serialize the output: {err}")),
+        };
+        println!("{}", self.render(&value));
+    }
+
+    /// Renders `value` in a structured format. `Plain` renders as compact JSON.
+    pub fn render(self, value: &Value) -> String {
+        match self {
+            OutputFormat::Plain => value.to_string(),
+            OutputFormat::Json => {
+                serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+            }
+            OutputFormat::Jsonl => items(value)
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            OutputFormat::Csv => {
+                let (headers, rows) = rows(value);
+                let mut writer = csv::Writer::from_writer(vec![]);
+                let written = writer
+                    .write_record(&headers)
+                    .and_then(|_| rows.iter().try_for_each(|row| writer.write_record(row)));
+                if let Err(err) = written {
+                    gracefully_exit(&format!("Failed to write CSV: {err}"));
+                }
+                let bytes = writer.into_inner().unwrap_or_default();
+                String::from_utf8_lossy(&bytes).trim_end().to_string()
+            }
+            OutputFormat::Table => {
+                let (headers, rows) = rows(value);
+                let mut table_builder = Builder::new();
+                table_builder.push_record(headers);
+                for row in rows {
+                    table_builder.push_record(row);
+                }
+                table_builder.build().to_string()
+            }
+        }
+    }
+}
+
+/// The records in a response: the `data` array, a lone `data` object, or the value itself.
+fn items(value: &Value) -> Vec<Value> {
+    let items = match value {
+        Value::Object(object) => object.get("data").unwrap_or(value),
+        _ => value,
+    };
+
+    match items {
+        Value::Array(items) => items.clone(),
+        Value::Null => vec![],
+        item => vec![item.clone()],
+    }
+}
+
+/// Flattens every item into one row, with the union of their fields as columns.
+fn rows(value: &Value) -> (Vec<String>, Vec<Vec<String>>) {
+    let flattened: Vec<Vec<(String, String)>> = items(value)
+        .iter()
+        .map(|item| {
+            let mut fields = vec![];
+            flatten(String::new(), item, &mut fields);
+            fields
+        })
+        .collect();
+
+    let mut headers: Vec<String> = vec![];
+    for (key, _) in flattened.iter().flatten() {
+        if !headers.contains(key) {
+            headers.push(key.clone());
+        }
+    }
+
+    let rows = flattened
+        .into_iter()
+        .map(|fields| {
+            let mut fields: HashMap<String, String> = fields.into_iter().collect();
+            headers
+                .iter()
+                .map(|header| fields.remove(header).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+
+    (headers, rows)
+}
+
+/// Collects the scalar fields of `value`, naming nested ones `parent.child`.
+fn flatten(prefix: String, value: &Value, fields: &mut Vec<(String, String)>) {
+    let key = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        }
+    };
+
+    match value {
+        Value::Object(object) => {
+            for (name, value) in object {
+                flatten(key(name), value, fields);
+            }
+        }
+        Value::Array(values) if values.iter().all(|value| !value.is_object()) => {
+            let joined = values.iter().map(scalar).collect::<Vec<_>>().join(",");
+            fields.push((key_or_value(&prefix), joined));
+        }
+        value => fields.push((key_or_value(&prefix), scalar(value))),
+    }
+}
+
+fn key_or_value(prefix: &str) -> String {
+    if prefix.is_empty() {
+        "value".to_string()
+    } else {
+        prefix.to_string()
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn likes() -> Value {
+        json!({
+            "data": [
+                {"id": "1", "text": "a, b", "edit_history_tweet_ids": ["1", "2"]},
+                {"id": "2", "text": "c", "author_id": "42"}
+            ],
+            "meta": {"result_count": 2}
+        })
+    }
+
+    #[test]
+    fn test_jsonl_prints_one_line_per_item() {
+        assert_eq!(
+            OutputFormat::Jsonl.render(&likes()),
+            "{\"edit_history_tweet_ids\":[\"1\",\"2\"],\"id\":\"1\",\"text\":\"a, b\"}\n{\"author_id\":\"42\",\"id\":\"2\",\"text\":\"c\"}"
+        );
+    }
+
+    #[test]
+    fn test_csv_uses_the_union_of_fields_as_columns() {
+        assert_eq!(
+            OutputFormat::Csv.render(&likes()),
+            "edit_history_tweet_ids,id,text,author_id\n\"1,2\",1,\"a, b\",\n,2,c,42"
+        );
+    }
+
+    #[test]
+    fn test_nested_objects_become_dotted_columns() {
+        let usage = json!({"data": {"project_usage": "5", "cap": {"daily": 10}}});
+        let (headers, rows) = rows(&usage);
+
+        assert_eq!(headers, vec!["cap.daily", "project_usage"]);
+        assert_eq!(rows, vec![vec!["10", "5"]]);
+    }
+}
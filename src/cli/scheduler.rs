@@ -0,0 +1,356 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use jiff::{SignedDuration, Timestamp};
+use serde::Serialize;
+
+use crate::{
+    cli::update::resolve_install_path,
+    constants::CACHE_DIR,
+    schedule::{
+        Schedule, local_time,
+        runs::{LastRun, ScheduleRuns},
+        sqlite_time,
+    },
+    utils::gracefully_exit,
+};
+
+const SERVICE: &str = "twitter-schedule.service";
+const TIMER: &str = "twitter-schedule.timer";
+
+/// How late a pending tweet can be before `status` calls it overdue.
+const OVERDUE_AFTER: SignedDuration = SignedDuration::from_mins(5);
+
+/// What runs `twitter schedule run` every minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runner {
+    /// A systemd user timer
+    Systemd,
+    /// A crontab line
+    Cron,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchedulerStatus {
+    /// Whether the systemd unit files are in place.
+    pub timer_installed: bool,
+    /// Whether systemd runs the timer, or `None` without systemd.
+    pub timer_active: Option<bool>,
+    /// Whether the user's crontab runs `schedule run`.
+    pub cron_installed: bool,
+    pub last_run: Option<LastRun>,
+    /// Pending tweets more than five minutes late.
+    pub overdue: usize,
+}
+
+pub fn install(runner: Runner) {
+    let binary = binary();
+    match runner {
+        Runner::Systemd => {
+            let dir = require_systemd_dir();
+            if let Err(err) = fs::create_dir_all(&dir) {
+                gracefully_exit(&format!("Failed to create '{}': {err}", dir.display()));
+            }
+            for (name, unit) in [(SERVICE, service_unit(&binary)), (TIMER, timer_unit())] {
+                let path = dir.join(name);
+                if let Err(err) = fs::write(&path, unit) {
+                    gracefully_exit(&format!("Failed to write '{}': {err}", path.display()));
+                }
+                println!("Wrote {}", path.display());
+            }
+
+            match systemctl(&["daemon-reload"]).and_then(|_| systemctl(&["enable", "--now", TIMER]))
+            {
+                Ok(()) => println!("{TIMER} now runs `twitter schedule run` every minute."),
+                Err(err) => {
+                    eprintln!("Could not start the timer: {err}");
+                    println!("Start it with:");
+                    println!("  systemctl --user daemon-reload");
+                    println!("  systemctl --user enable --now {TIMER}");
+                }
+            }
+        }
+        Runner::Cron => {
+            if crontab().is_some_and(|crontab| has_cron_line(&crontab)) {
+                println!("Your crontab already runs `twitter schedule run`.");
+                return;
+            }
+            println!("Add this line to your crontab with `crontab -e`:");
+            println!("{}", cron_line(&binary, &log_path()));
+        }
+    }
+}
+
+pub fn uninstall(runner: Runner) {
+    match runner {
+        Runner::Systemd => {
+            let dir = require_systemd_dir();
+            let paths: Vec<PathBuf> = [TIMER, SERVICE]
+                .iter()
+                .map(|name| dir.join(name))
+                .filter(|path| path.exists())
+                .collect();
+            if paths.is_empty() {
+                println!("{TIMER} is not installed.");
+                return;
+            }
+
+            if let Err(err) = systemctl(&["disable", "--now", TIMER]) {
+                eprintln!("Could not stop the timer: {err}");
+            }
+            for path in paths {
+                if let Err(err) = fs::remove_file(&path) {
+                    gracefully_exit(&format!("Failed to remove '{}': {err}", path.display()));
+                }
+                println!("Removed {}", path.display());
+            }
+            if let Err(err) = systemctl(&["daemon-reload"]) {
+                eprintln!("Could not reload systemd: {err}");
+            }
+        }
+        Runner::Cron => {
+            let lines: Vec<String> = crontab()
+                .unwrap_or_default()
+                .lines()
+                .filter(|line| has_cron_line(line))
+                .map(str::to_string)
+                .collect();
+            if lines.is_empty() {
+                println!("Your crontab does not run `twitter schedule run`.");
+                return;
+            }
+            println!("Remove this from your crontab with `crontab -e`:");
+            for line in lines {
+                println!("{line}");
+            }
+        }
+    }
+}
+
+pub fn status() -> SchedulerStatus {
+    let timer_installed = systemd_dir()
+        .is_some_and(|dir| [SERVICE, TIMER].iter().all(|name| dir.join(name).exists()));
+    let timer_active = Command::new("systemctl")
+        .args(["--user", "is-active", TIMER])
+        .output()
+        .ok()
+        .and_then(
+            |output| match String::from_utf8_lossy(&output.stdout).trim() {
+                // No user session to ask.
+                "" => None,
+                state => Some(state == "active"),
+            },
+        );
+
+    let late = Timestamp::now() - OVERDUE_AFTER;
+    let overdue = Schedule::default()
+        .pending()
+        .iter()
+        .filter_map(|tweet| tweet.scheduled_for.parse::<Timestamp>().ok())
+        .filter(|send_time| *send_time < late)
+        .count();
+
+    SchedulerStatus {
+        timer_installed,
+        timer_active,
+        cron_installed: crontab().is_some_and(|crontab| has_cron_line(&crontab)),
+        last_run: ScheduleRuns::default().last(),
+        overdue,
+    }
+}
+
+pub fn print_status(status: SchedulerStatus) {
+    let timer = match (status.timer_installed, status.timer_active) {
+        (false, _) => "not installed",
+        (true, Some(true)) => "installed, active",
+        (true, Some(false)) => "installed, inactive",
+        (true, None) => "installed",
+    };
+    println!("systemd timer: {timer}");
+    let cron = if status.cron_installed {
+        "installed"
+    } else {
+        "not installed"
+    };
+    println!("crontab entry: {cron}");
+
+    match &status.last_run {
+        Some(run) => {
+            println!("Last run: {}", utc_to_local(&run.ran_at));
+            let succeeded = run
+                .succeeded_at
+                .as_deref()
+                .map_or("never".into(), utc_to_local);
+            println!("Last successful run: {succeeded}");
+            if let Some(err) = &run.last_error {
+                println!("Last error: {err}");
+            }
+        }
+        None => println!("Last run: never"),
+    }
+
+    if status.overdue > 0 {
+        let verb = if status.overdue == 1 {
+            "tweet is"
+        } else {
+            "tweets are"
+        };
+        println!(
+            "{} scheduled {verb} overdue. Is something running `twitter schedule run`? See `twitter schedule install`.",
+            status.overdue
+        );
+    }
+}
+
+/// The unit that sends the due tweets once.
+fn service_unit(binary: &Path) -> String {
+    // `%` starts a specifier in unit files.
+    let binary = binary.display().to_string().replace('%', "%%");
+    format!(
+        "[Unit]
+Description=Send due scheduled tweets
+
+[Service]
+Type=oneshot
+ExecStart=\"{binary}\" schedule run
+"
+    )
+}
+
+/// The timer that starts the service every minute, and catches up on boot.
+fn timer_unit() -> String {
+    format!(
+        "[Unit]
+Description=Send due scheduled tweets every minute
+
+[Timer]
+OnCalendar=minutely
+AccuracySec=1s
+Persistent=true
+Unit={SERVICE}
+
+[Install]
+WantedBy=timers.target
+"
+    )
+}
+
+fn cron_line(binary: &Path, log: &Path) -> String {
+    format!(
+        "* * * * * {} schedule run >> {} 2>&1",
+        cron_quote(binary),
+        cron_quote(log)
+    )
+}
+
+/// Quotes a path for the shell cron runs. Cron turns a bare `%` into a newline.
+fn cron_quote(path: &Path) -> String {
+    let quoted = path
+        .display()
+        .to_string()
+        .replace('\'', "'\\''")
+        .replace('%', "\\%");
+    format!("'{quoted}'")
+}
+
+/// Whether a crontab has an active line running `schedule run`.
+fn has_cron_line(crontab: &str) -> bool {
+    crontab.lines().any(|line| {
+        let line = line.trim();
+        !line.starts_with('#') && line.contains("schedule run")
+    })
+}
+
+fn binary() -> PathBuf {
+    resolve_install_path(&format!("twitter{}", env::consts::EXE_SUFFIX))
+}
+
+/// Where systemd looks for user units, or `None` off Linux.
+fn systemd_dir() -> Option<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    dirs::config_dir().map(|dir| dir.join("systemd/user"))
+}
+
+fn require_systemd_dir() -> PathBuf {
+    match systemd_dir() {
+        Some(dir) => dir,
+        None => gracefully_exit("systemd timers are only supported on Linux."),
+    }
+}
+
+fn log_path() -> PathBuf {
+    match dirs::data_dir() {
+        Some(dir) => dir.join(CACHE_DIR).join("schedule.log"),
+        None => gracefully_exit("Failed to locate a data directory for the log."),
+    }
+}
+
+/// The user's crontab, or `None` when there is none or no `crontab` command.
+fn crontab() -> Option<String> {
+    let output = Command::new("crontab").arg("-l").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn systemctl(args: &[&str]) -> Result<(), String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|err| format!("systemctl: {err}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+}
+
+/// A `CURRENT_TIMESTAMP` from SQLite, in local time.
+fn utc_to_local(time: &str) -> String {
+    sqlite_time(time).map_or_else(|| time.to_string(), local_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units_run_the_binary() {
+        let service = service_unit(Path::new("/home/me/100% bin/twitter"));
+        assert!(service.contains("ExecStart=\"/home/me/100%% bin/twitter\" schedule run\n"));
+        assert!(service.contains("Type=oneshot"));
+
+        let timer = timer_unit();
+        assert!(timer.contains("OnCalendar=minutely"));
+        assert!(timer.contains("Unit=twitter-schedule.service"));
+    }
+
+    #[test]
+    fn test_cron_line() {
+        let line = cron_line(
+            Path::new("/usr/local/bin/twitter"),
+            Path::new("/home/me/.local/share/twitter-cli/schedule.log"),
+        );
+        assert_eq!(
+            line,
+            "* * * * * '/usr/local/bin/twitter' schedule run >> '/home/me/.local/share/twitter-cli/schedule.log' 2>&1"
+        );
+        assert!(has_cron_line(&format!("MAILTO=\"\"\n{line}\n")));
+        assert!(!has_cron_line(&format!("# {line}\n0 * * * * backup")));
+
+        let line = cron_line(
+            Path::new("/home/o'neil/100%/twitter"),
+            Path::new("/tmp/log"),
+        );
+        assert_eq!(
+            line,
+            r"* * * * * '/home/o'\''neil/100\%/twitter' schedule run >> '/tmp/log' 2>&1"
+        );
+    }
+}
@@ -387,7 +387,7 @@ fn extract_from_zip(
     ))
 }
 
-fn resolve_install_path(binary_name: &str) -> PathBuf {
+pub(crate) fn resolve_install_path(binary_name: &str) -> PathBuf {
     if let Ok(dir) = env::var("TWITTER_INSTALL") {
         return PathBuf::from(dir).join(binary_name);
     }
@@ -2,7 +2,11 @@ use std::{env::var, fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 
-use crate::utils::gracefully_exit;
+use crate::{
+    schedule::retry::ScheduleRetry,
+    twitter::{retry::RetryPolicy, text::ThreadFormat},
+    utils::gracefully_exit,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Account {
@@ -20,6 +24,18 @@ pub struct Account {
 pub struct Config {
     pub current_account: usize,
     pub accounts: Vec<Account>,
+    /// Overrides the X API host, e.g. a local mock server or a recording proxy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_base_url: Option<String>,
+    /// How transient failures are retried. Defaults apply when the table is missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+    /// How threads are split and numbered. Defaults apply when the table is missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread: Option<ThreadFormat>,
+    /// How failed scheduled tweets are sent again. Defaults apply when the table is missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule_retry: Option<ScheduleRetry>,
 }
 
 impl FromStr for Config {
@@ -93,6 +109,23 @@ fn test_load_config() {
     assert_eq!(test_config.current_account, 0);
 }
 
+#[test]
+fn test_load_thread_config() {
+    let s = r#"
+    current_account = 0
+    accounts = []
+
+    [thread]
+    separator = "==="
+    numbering = "prefix"
+    "#;
+    let thread = Config::from_str(s).unwrap().thread.unwrap();
+
+    assert_eq!(thread.separator, "===");
+    assert_eq!(thread.numbering, crate::twitter::text::Numbering::Prefix);
+    assert!(!thread.paragraphs);
+}
+
 #[test]
 #[should_panic]
 fn gracefully_fail_to_load_account() {
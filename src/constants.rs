@@ -2,6 +2,10 @@ pub const CACHE_DIR: &str = "twitter-cli";
 pub const DB_FILENAME: &str = "db.sqlite3";
 pub const SCHEDULE_TABLE_NAME: &str = "scheduled_tweets";
 pub const TOKEN_TABLE_NAME: &str = "access_tokens";
+pub const RATE_LIMITS_TABLE_NAME: &str = "rate_limits";
+pub const THREADS_TABLE_NAME: &str = "threads";
+pub const DRAFTS_TABLE_NAME: &str = "drafts";
+pub const SCHEDULE_RUNS_TABLE_NAME: &str = "schedule_runs";
 
 // Migrations
 pub const CREATE_TOKENS_TABLE: &str = r#"
@@ -39,3 +43,82 @@ pub const CREATE_SCHEDULES_TABLE: &str = r#"
                 )
             );
 "#;
+
+pub const CREATE_RATE_LIMITS_TABLE: &str = r#"
+            CREATE TABLE IF NOT EXISTS rate_limits (
+                endpoint TEXT PRIMARY KEY,
+                rate_limit INTEGER NOT NULL,
+                remaining INTEGER NOT NULL,
+                reset_at INTEGER NOT NULL,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+"#;
+
+pub const CREATE_THREADS_TABLE: &str = r#"
+            CREATE TABLE IF NOT EXISTS threads (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                payload TEXT NOT NULL,
+                parts TEXT NOT NULL,
+                account INTEGER NOT NULL,
+                part_media TEXT NOT NULL DEFAULT '[]',
+                posted_ids TEXT NOT NULL DEFAULT '[]',
+                last_error TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+"#;
+
+pub const CREATE_DRAFTS_TABLE: &str = r#"
+            CREATE TABLE IF NOT EXISTS drafts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                body TEXT NOT NULL,
+                images TEXT NOT NULL DEFAULT '[]',
+                reply_to TEXT,
+                quote TEXT,
+                account INTEGER NOT NULL DEFAULT 0,
+                media_options TEXT NOT NULL DEFAULT '{}',
+                format TEXT,
+                auto_split INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+"#;
+
+pub const CREATE_SCHEDULE_RUNS_TABLE: &str = r#"
+            CREATE TABLE IF NOT EXISTS schedule_runs (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                ran_at DATETIME NOT NULL,
+                succeeded_at DATETIME,
+                last_error TEXT
+            );
+"#;
+
+/// Columns added to a table after it first shipped, as `(table, column, definition)`.
+/// Databases created by older versions get them on their next connection.
+pub const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    // The whole tweet, not just `body`. Rows from before it are posted as their body.
+    (SCHEDULE_TABLE_NAME, "payload", "TEXT"),
+    (SCHEDULE_TABLE_NAME, "images", "TEXT NOT NULL DEFAULT '[]'"),
+    (
+        SCHEDULE_TABLE_NAME,
+        "media_options",
+        "TEXT NOT NULL DEFAULT '{}'",
+    ),
+    (SCHEDULE_TABLE_NAME, "format", "TEXT"),
+    (
+        SCHEDULE_TABLE_NAME,
+        "auto_split",
+        "INTEGER NOT NULL DEFAULT 0",
+    ),
+    (SCHEDULE_TABLE_NAME, "account", "INTEGER"),
+    (SCHEDULE_TABLE_NAME, "recurrence", "TEXT"),
+    // Whether the last failure may go away on its own, so the tweet is sent again.
+    (
+        SCHEDULE_TABLE_NAME,
+        "retryable",
+        "INTEGER NOT NULL DEFAULT 0",
+    ),
+    // The thread a failed attempt left part way posted, which the next attempt resumes.
+    (SCHEDULE_TABLE_NAME, "thread_id", "INTEGER"),
+];
@@ -2,8 +2,10 @@ use rusqlite::Connection;
 
 use crate::{
     constants::{
-        CACHE_DIR, CREATE_SCHEDULES_TABLE, CREATE_TOKENS_TABLE, DB_FILENAME, SCHEDULE_TABLE_NAME,
-        TOKEN_TABLE_NAME,
+        ADDED_COLUMNS, CACHE_DIR, CREATE_DRAFTS_TABLE, CREATE_RATE_LIMITS_TABLE,
+        CREATE_SCHEDULE_RUNS_TABLE, CREATE_SCHEDULES_TABLE, CREATE_THREADS_TABLE,
+        CREATE_TOKENS_TABLE, DB_FILENAME, DRAFTS_TABLE_NAME, RATE_LIMITS_TABLE_NAME,
+        SCHEDULE_RUNS_TABLE_NAME, SCHEDULE_TABLE_NAME, THREADS_TABLE_NAME, TOKEN_TABLE_NAME,
     },
     utils::gracefully_exit,
 };
@@ -44,13 +46,7 @@ impl Database {
             )),
         };
 
-        let migration_query = match self.table_name {
-            SCHEDULE_TABLE_NAME => CREATE_SCHEDULES_TABLE,
-            TOKEN_TABLE_NAME => CREATE_TOKENS_TABLE,
-            _ => todo!(),
-        };
-
-        if let Err(err) = connection.execute(migration_query, []) {
+        if let Err(err) = migrate(&connection, self.table_name) {
             gracefully_exit(&format!(
                 "Failed to initialize {} database schema: {err}",
                 self.table_name
@@ -60,3 +56,70 @@ impl Database {
         connection
     }
 }
+
+/// Creates `table_name` if it is missing and adds any column it gained since.
+pub fn migrate(connection: &Connection, table_name: &str) -> rusqlite::Result<()> {
+    let migration_query = match table_name {
+        SCHEDULE_TABLE_NAME => CREATE_SCHEDULES_TABLE,
+        TOKEN_TABLE_NAME => CREATE_TOKENS_TABLE,
+        RATE_LIMITS_TABLE_NAME => CREATE_RATE_LIMITS_TABLE,
+        THREADS_TABLE_NAME => CREATE_THREADS_TABLE,
+        DRAFTS_TABLE_NAME => CREATE_DRAFTS_TABLE,
+        SCHEDULE_RUNS_TABLE_NAME => CREATE_SCHEDULE_RUNS_TABLE,
+        _ => return Err(rusqlite::Error::InvalidParameterName(table_name.into())),
+    };
+    connection.execute(migration_query, [])?;
+
+    for (_, column, definition) in ADDED_COLUMNS
+        .iter()
+        .filter(|(table, _, _)| *table == table_name)
+    {
+        let exists = connection
+            .prepare(&format!(
+                "SELECT 1 FROM pragma_table_info('{table_name}') WHERE name = ?1"
+            ))?
+            .exists([column])?;
+        if !exists {
+            connection.execute(
+                &format!("ALTER TABLE {table_name} ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_tables_are_an_error() {
+        let connection = Connection::open_in_memory().unwrap();
+        let err = migrate(&connection, "no_such_table").unwrap_err();
+        assert!(
+            matches!(err, rusqlite::Error::InvalidParameterName(name) if name == "no_such_table")
+        );
+    }
+
+    #[test]
+    fn test_added_columns_reach_old_tables() {
+        let connection = Connection::open_in_memory().unwrap();
+        // The table as it first shipped, before any added column.
+        connection.execute(CREATE_SCHEDULES_TABLE, []).unwrap();
+
+        migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+        // Running it again must not try to add the columns twice.
+        migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+
+        let images: String = connection
+            .query_row(
+                "INSERT INTO scheduled_tweets (body, scheduled_for) VALUES ('hi', '2026-10-18T09:00:00Z') RETURNING images",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(images, "[]");
+    }
+}
@@ -0,0 +1,234 @@
+use rusqlite::{Connection, OptionalExtension, Row, types::Type};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    constants::DRAFTS_TABLE_NAME,
+    database::Database,
+    twitter::{error::TwitterError, media::Image, preflight::MediaOptions, text::ThreadFormat},
+};
+
+/// A tweet saved for later, with everything needed to post it.
+#[derive(Debug, Default, Serialize)]
+pub struct Draft {
+    pub id: i64,
+    pub body: String,
+    pub images: Vec<Image>,
+    pub reply_to: Option<String>,
+    pub quote: Option<String>,
+    /// The index of the configured account it is posted from.
+    pub account: usize,
+    /// How the images are prepared for upload.
+    pub media_options: MediaOptions,
+    /// How the body was split into a thread, or `None` for the `[thread]` config.
+    pub format: Option<ThreadFormat>,
+    pub auto_split: bool,
+    /// Why the last attempt to post it failed.
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+pub struct Drafts {
+    connection: Connection,
+}
+
+impl Default for Drafts {
+    fn default() -> Self {
+        let db = Database::new(DRAFTS_TABLE_NAME);
+        Self {
+            connection: db.open_connection(),
+        }
+    }
+}
+
+const COLUMNS: &str = "id, body, images, reply_to, quote, account, media_options, format, auto_split, last_error, created_at, updated_at";
+
+impl Drafts {
+    /// Saves a new draft and returns its id. The `id` and timestamps of `draft` are ignored.
+    pub fn save(&self, draft: &Draft) -> Result<i64, TwitterError> {
+        let query = format!(
+            "
+            INSERT INTO {DRAFTS_TABLE_NAME} (body, images, reply_to, quote, account, media_options, format, auto_split, last_error)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9);
+            "
+        );
+        let images = serde_json::to_string(&draft.images).map_err(TwitterError::local)?;
+        let media_options =
+            serde_json::to_string(&draft.media_options).map_err(TwitterError::local)?;
+        let format = draft
+            .format
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(TwitterError::local)?;
+        self.connection
+            .execute(
+                &query,
+                (
+                    draft.body.as_str(),
+                    images,
+                    draft.reply_to.as_deref(),
+                    draft.quote.as_deref(),
+                    draft.account as i64,
+                    media_options,
+                    format,
+                    draft.auto_split,
+                    draft.last_error.as_deref(),
+                ),
+            )
+            .map_err(|err| TwitterError::local(format!("Failed to save the draft: {err}")))?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    pub fn all(&self) -> Result<Vec<Draft>, TwitterError> {
+        let query = format!("SELECT {COLUMNS} FROM {DRAFTS_TABLE_NAME} ORDER BY id");
+        let mut stmt = self
+            .connection
+            .prepare(&query)
+            .map_err(|err| TwitterError::local(format!("Failed to read drafts: {err}")))?;
+        let drafts = stmt
+            .query_map([], draft_from_row)
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|err| TwitterError::local(format!("Failed to read drafts: {err}")))?;
+
+        Ok(drafts)
+    }
+
+    pub fn get(&self, id: i64) -> Result<Draft, TwitterError> {
+        let query = format!("SELECT {COLUMNS} FROM {DRAFTS_TABLE_NAME} WHERE id = ?1");
+        self.connection
+            .query_row(&query, [id], draft_from_row)
+            .optional()
+            .map_err(|err| TwitterError::local(format!("Failed to read draft {id}: {err}")))?
+            .ok_or_else(|| TwitterError::local(format!("No draft with id {id}.")))
+    }
+
+    pub fn update_body(&self, id: i64, body: &str) -> Result<(), TwitterError> {
+        self.update(
+            id,
+            &format!(
+                "UPDATE {DRAFTS_TABLE_NAME} SET body = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?1"
+            ),
+            body,
+        )
+    }
+
+    /// Records why publishing failed, keeping the draft for another try.
+    pub fn failed(&self, id: i64, err: &TwitterError) -> Result<(), TwitterError> {
+        self.update(
+            id,
+            &format!(
+                "UPDATE {DRAFTS_TABLE_NAME} SET last_error = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?1"
+            ),
+            &err.to_string(),
+        )
+    }
+
+    /// Deletes a draft. Returns whether it existed.
+    pub fn delete(&self, id: i64) -> Result<bool, TwitterError> {
+        let query = format!("DELETE FROM {DRAFTS_TABLE_NAME} WHERE id = ?1");
+        self.connection
+            .execute(&query, [id])
+            .map(|deleted| deleted > 0)
+            .map_err(|err| TwitterError::local(format!("Failed to delete draft {id}: {err}")))
+    }
+
+    fn update(&self, id: i64, query: &str, value: &str) -> Result<(), TwitterError> {
+        match self.connection.execute(query, (id, value)) {
+            Ok(0) => Err(TwitterError::local(format!("No draft with id {id}."))),
+            Ok(_) => Ok(()),
+            Err(err) => Err(TwitterError::local(format!(
+                "Failed to update draft {id}: {err}"
+            ))),
+        }
+    }
+}
+
+fn draft_from_row(row: &Row<'_>) -> rusqlite::Result<Draft> {
+    Ok(Draft {
+        id: row.get(0)?,
+        body: row.get(1)?,
+        images: json_column(row, 2)?,
+        reply_to: row.get(3)?,
+        quote: row.get(4)?,
+        account: row.get::<_, i64>(5)? as usize,
+        media_options: json_column(row, 6)?,
+        format: json_column(row, 7)?,
+        auto_split: row.get(8)?,
+        last_error: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+fn json_column<T: DeserializeOwned>(row: &Row<'_>, index: usize) -> rusqlite::Result<T> {
+    let value: Option<String> = row.get(index)?;
+    serde_json::from_str(value.as_deref().unwrap_or("null"))
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, err.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{constants::CREATE_DRAFTS_TABLE, twitter::text::Numbering};
+
+    fn drafts() -> Drafts {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute(CREATE_DRAFTS_TABLE, []).unwrap();
+        Drafts { connection }
+    }
+
+    #[test]
+    fn test_drafts_keep_images_and_targets() {
+        let drafts = drafts();
+        let id = drafts
+            .save(&Draft {
+                body: "Release notes".to_string(),
+                images: vec![Image {
+                    path: PathBuf::from("/tmp/chart.png"),
+                    alt: Some("A chart".to_string()),
+                }],
+                reply_to: Some("1".to_string()),
+                quote: Some("2".to_string()),
+                account: 1,
+                format: Some(ThreadFormat {
+                    numbering: Numbering::Prefix,
+                    ..Default::default()
+                }),
+                auto_split: true,
+                media_options: MediaOptions {
+                    downscale: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap();
+
+        drafts.update_body(id, "Release notes, v2").unwrap();
+        drafts.failed(id, &TwitterError::local("boom")).unwrap();
+
+        let draft = drafts.get(id).unwrap();
+        assert_eq!(draft.body, "Release notes, v2");
+        assert_eq!(draft.images[0].alt.as_deref(), Some("A chart"));
+        assert_eq!(draft.reply_to.as_deref(), Some("1"));
+        assert_eq!(draft.quote.as_deref(), Some("2"));
+        assert_eq!(draft.account, 1);
+        assert_eq!(draft.format.unwrap().numbering, Numbering::Prefix);
+        assert!(draft.auto_split);
+        assert!(draft.media_options.downscale);
+        assert_eq!(draft.last_error.as_deref(), Some("error[local]: boom"));
+        assert_eq!(drafts.all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_missing_drafts_are_reported() {
+        let drafts = drafts();
+
+        assert!(drafts.get(7).is_err());
+        assert!(drafts.update_body(7, "x").is_err());
+        assert!(!drafts.delete(7).unwrap());
+    }
+}
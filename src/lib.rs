@@ -3,6 +3,7 @@ pub mod cli;
 pub mod config;
 pub mod constants;
 pub mod database;
+pub mod drafts;
 pub mod schedule;
 pub mod twitter;
 pub mod usage;
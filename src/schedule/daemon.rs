@@ -0,0 +1,342 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use jiff::{SignedDuration, Timestamp, tz::TimeZone};
+
+use crate::schedule::{
+    Schedule, ScheduleStatus, ScheduledTweet, local_time, retry::ScheduleRetry, runs::ScheduleRuns,
+};
+
+/// How often the daemon looks for a stop signal and for changes to the schedule while it waits.
+const TICK: Duration = Duration::from_secs(1);
+
+/// The longest the daemon waits before reading the whole schedule again, in case the clock
+/// jumped (e.g. after the machine slept).
+const MAX_WAIT: SignedDuration = SignedDuration::from_secs(60);
+
+/// Where the daemon reads the time and how it waits, so tests can run it without waiting.
+pub trait Clock {
+    fn now(&self) -> Timestamp;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+impl<C: Clock> Clock for &C {
+    fn now(&self) -> Timestamp {
+        (*self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (*self).sleep(duration);
+    }
+}
+
+/// Sends scheduled tweets as they come due, until `stop` is set.
+pub struct Daemon<C> {
+    schedule: Schedule,
+    clock: C,
+    stop: Arc<AtomicBool>,
+    retry: ScheduleRetry,
+    runs: Option<ScheduleRuns>,
+}
+
+impl<C: Clock> Daemon<C> {
+    pub fn new(schedule: Schedule, clock: C, stop: Arc<AtomicBool>) -> Self {
+        Self {
+            schedule,
+            clock,
+            stop,
+            retry: ScheduleRetry::default(),
+            runs: None,
+        }
+    }
+
+    /// Sends tweets that failed for a transient reason again as `retry` says.
+    pub fn retry(mut self, retry: ScheduleRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Updates the last run in `runs` every time the daemon wakes, so `schedule status` shows
+    /// that it is alive even when nothing is due.
+    pub fn record_runs(mut self, runs: ScheduleRuns) -> Self {
+        self.runs = Some(runs);
+        self
+    }
+
+    /// Runs until `stop` is set, calling `send` for every tweet that is due and for every
+    /// retry. `send` records the outcome. A tweet that is unchanged afterwards (a dry run) is
+    /// not sent again.
+    pub fn run(&self, mut send: impl FnMut(&Schedule, &ScheduledTweet)) {
+        self.log("Scheduler started.");
+        let mut attempted = HashSet::new();
+        let mut announced = None;
+
+        while !self.stopped() {
+            if let Some(runs) = &self.runs {
+                runs.heartbeat();
+            }
+            let now = self.clock.now();
+            let mut tweets = self.schedule.pending();
+            tweets.extend(self.schedule.retries(&self.retry));
+            let (due, later): (Vec<_>, Vec<_>) = tweets
+                .into_iter()
+                .filter(|tweet| !attempted.contains(&(tweet.id, tweet.attempts)))
+                .partition(|tweet| self.send_time(tweet).is_none_or(|time| time <= now));
+
+            if !due.is_empty() {
+                for tweet in &due {
+                    if self.stopped() {
+                        break;
+                    }
+                    match tweet.status {
+                        ScheduleStatus::Failed => self.log(&format!(
+                            "Retrying scheduled tweet {}, attempt {}.",
+                            tweet.id,
+                            tweet.attempts + 1
+                        )),
+                        _ => self.log(&format!(
+                            "Sending scheduled tweet {}, due {}.",
+                            tweet.id, tweet.scheduled_for
+                        )),
+                    }
+                    attempted.insert((tweet.id, tweet.attempts));
+                    send(&self.schedule, tweet);
+                }
+                continue;
+            }
+
+            let next = later.iter().filter_map(|tweet| self.send_time(tweet)).min();
+            if announced != Some(next) {
+                match next {
+                    Some(next) => self.log(&format!("Next tweet at {}.", local_time(next))),
+                    None => self.log("No scheduled tweets. Waiting for new ones."),
+                }
+                announced = Some(next);
+            }
+            self.wait(now, next);
+        }
+
+        self.log("Stopped.");
+    }
+
+    /// Sleeps until `next`, for at most `MAX_WAIT`, or until the schedule changes or a stop
+    /// is requested.
+    fn wait(&self, now: Timestamp, next: Option<Timestamp>) {
+        let latest = now.checked_add(MAX_WAIT).unwrap_or(now);
+        let deadline = next.map_or(latest, |next| next.min(latest));
+        let version = self.schedule.data_version();
+
+        while !self.stopped() {
+            let left = deadline.duration_since(self.clock.now());
+            if left <= SignedDuration::ZERO {
+                return;
+            }
+            self.clock.sleep(left.unsigned_abs().min(TICK));
+            if self.schedule.data_version() != version {
+                self.log("The schedule changed.");
+                return;
+            }
+        }
+    }
+
+    /// When a tweet is due, or retried if it failed. Rows with a time that cannot be read are
+    /// sent right away, as `schedule run` would.
+    fn send_time(&self, tweet: &ScheduledTweet) -> Option<Timestamp> {
+        match tweet.status {
+            ScheduleStatus::Failed => self.retry.retry_at(tweet),
+            _ => tweet.scheduled_for.parse().ok(),
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    fn log(&self, message: &str) {
+        let now = self.clock.now().to_zoned(TimeZone::system());
+        println!("[{}] {message}", now.strftime("%Y-%m-%d %H:%M:%S %Z"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, env, fs};
+
+    use rusqlite::Connection;
+
+    use super::*;
+    use crate::{constants::SCHEDULE_TABLE_NAME, database::migrate};
+
+    /// Moves on by however long the daemon sleeps, calling `on_sleep` with the new time.
+    struct FakeClock<F> {
+        now: Cell<Timestamp>,
+        on_sleep: F,
+    }
+
+    impl<F: Fn(Timestamp)> Clock for FakeClock<F> {
+        fn now(&self) -> Timestamp {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.now.set(self.now.get() + duration);
+            (self.on_sleep)(self.now.get());
+        }
+    }
+
+    #[test]
+    fn test_daemon_sends_tweets_when_due() {
+        let path = env::temp_dir().join("twitter-cli-tests/schedule-daemon.sqlite3");
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        let _ = fs::remove_file(&path);
+        let connection = || {
+            let connection = Connection::open(&path).unwrap();
+            migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+            connection
+        };
+        let save = |time: &str| {
+            let mut schedule = Schedule::new("tweet", time);
+            schedule.connection = connection();
+            let send_time = schedule.send_time();
+            assert!(schedule.save());
+            send_time
+        };
+        let later = save("2099-01-01 09:30");
+        let start = later - SignedDuration::from_mins(30);
+        let added = start + SignedDuration::from_secs(40);
+
+        // Another process schedules a tweet for 40 seconds from the start, well before the
+        // daemon would read the schedule again by itself.
+        let clock = FakeClock {
+            now: Cell::new(start),
+            on_sleep: |now: Timestamp| {
+                if now == start + SignedDuration::from_secs(30) {
+                    save("2099-01-01 09:00:40");
+                }
+            },
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let schedule = Schedule {
+            connection: connection(),
+            ..Schedule::new("", "2099-01-01 09:00")
+        };
+        let mut sent = vec![];
+        Daemon::new(schedule, &clock, stop.clone()).run(|schedule, tweet| {
+            sent.push(clock.now());
+            schedule.mark_sent(tweet.id);
+            if sent.len() == 2 {
+                stop.store(true, Ordering::Relaxed);
+            }
+        });
+
+        assert_eq!(sent, vec![added, later]);
+    }
+
+    #[test]
+    fn test_daemon_retries_transient_failures() {
+        let path = env::temp_dir().join("twitter-cli-tests/schedule-daemon-retry.sqlite3");
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        let _ = fs::remove_file(&path);
+        let connection = || {
+            let connection = Connection::open(&path).unwrap();
+            migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+            connection
+        };
+        let mut tweet = Schedule::new("tweet", "2099-01-01 09:00");
+        tweet.connection = connection();
+        let due = tweet.send_time();
+        assert!(tweet.save());
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let give_up = stop.clone();
+        let clock = FakeClock {
+            now: Cell::new(due),
+            on_sleep: move |now: Timestamp| {
+                if now > due + SignedDuration::from_hours(1) {
+                    give_up.store(true, Ordering::Relaxed);
+                }
+            },
+        };
+        let retry = ScheduleRetry {
+            max_attempts: 2,
+            retry_delay_secs: 90,
+            max_retry_delay_secs: 90,
+        };
+        let schedule = Schedule {
+            connection: connection(),
+            ..Schedule::new("", "2099-01-01 09:00")
+        };
+        let mut attempts = vec![];
+        Daemon::new(schedule, &clock, stop.clone())
+            .retry(retry)
+            .run(|schedule, tweet| {
+                attempts.push(clock.now());
+                if attempts.len() > 1 {
+                    schedule.mark_sent(tweet.id);
+                    stop.store(true, Ordering::Relaxed);
+                    return;
+                }
+                schedule.mark_failed(tweet.id, "error[server_error]", true, None);
+                // As if it failed at the time of the fake clock.
+                let failed_at = clock.now().to_zoned(TimeZone::UTC);
+                schedule
+                    .connection
+                    .execute(
+                        "UPDATE scheduled_tweets SET updated_at = ?1",
+                        [failed_at.strftime("%Y-%m-%d %H:%M:%S").to_string()],
+                    )
+                    .unwrap();
+            });
+
+        assert_eq!(attempts, vec![due, due + SignedDuration::from_secs(90)]);
+    }
+
+    #[test]
+    fn test_daemon_stops_while_waiting() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_at = Timestamp::UNIX_EPOCH + SignedDuration::from_secs(5);
+        let signal = stop.clone();
+        // A SIGTERM five seconds in.
+        let clock = FakeClock {
+            now: Cell::new(Timestamp::UNIX_EPOCH),
+            on_sleep: move |now| {
+                if now == stop_at {
+                    signal.store(true, Ordering::Relaxed);
+                }
+            },
+        };
+        let schedule = Schedule {
+            connection: {
+                let connection = Connection::open_in_memory().unwrap();
+                migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+                connection
+            },
+            ..Schedule::new("", "2099-01-01 09:00")
+        };
+
+        let daemon = Daemon::new(schedule, &clock, stop).record_runs(ScheduleRuns::in_memory());
+        daemon.run(|_, _| panic!("nothing is due"));
+        assert_eq!(clock.now(), stop_at);
+        // Idle, but alive.
+        assert!(daemon.runs.unwrap().last().is_some());
+    }
+}
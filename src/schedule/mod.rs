@@ -1,18 +1,39 @@
 use crate::{constants::SCHEDULE_TABLE_NAME, database::Database};
+
+pub mod daemon;
+pub mod recurrence;
+pub mod retry;
+pub mod runs;
 #[cfg(test)]
 use std::path::PathBuf;
 use std::{fmt::Display, str::FromStr};
 
-use jiff::{Timestamp, ToSpan};
+use jiff::{Timestamp, ToSpan, civil::DateTime, tz::TimeZone};
 use parse_datetime::parse_datetime;
 use rusqlite::{
-    Connection,
-    types::{FromSql, FromSqlError, ValueRef},
+    Connection, Row,
+    types::{FromSql, FromSqlError, Type, ValueRef},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    schedule::{
+        recurrence::{Recurrence, fill_template},
+        retry::ScheduleRetry,
+    },
+    twitter::{
+        error::TwitterError,
+        media::Image,
+        preflight::MediaOptions,
+        progress::Threads,
+        text::ThreadFormat,
+        tweet::{Tweet, TweetBody},
+    },
+    utils::{gracefully_exit, thread_format},
 };
 
-use crate::{twitter::tweet::TweetBody, utils::gracefully_exit};
-
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ScheduleStatus {
     Pending,
     Sent,
@@ -48,21 +69,104 @@ impl FromSql for ScheduleStatus {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ScheduledTweet {
     pub id: u32,
     pub body: String,
     pub status: ScheduleStatus,
     pub scheduled_for: String,
     pub attempts: u32,
+    /// Whether the last failure was transient, e.g. a rate limit or a network error.
+    pub retryable: bool,
     pub last_error: Option<String>,
     pub sent_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// The tweet as it is posted. Tweets scheduled by older versions have none and post `body`.
+    pub payload: Option<TweetBody>,
+    /// Attached to the first tweet, ahead of any `![alt](path)` lines in the text.
+    pub images: Vec<Image>,
+    pub media_options: MediaOptions,
+    /// How the text is cut into a thread. The `[thread]` config is used when missing.
+    pub format: Option<ThreadFormat>,
+    pub auto_split: bool,
+    /// The index of the configured account it is posted from, or the current one when missing.
+    pub account: Option<usize>,
+    /// The rule it repeats by, as written.
+    pub recurrence: Option<String>,
+    /// The `tweet --resume` handle of a thread a failed attempt left part way posted.
+    pub thread_id: Option<i64>,
+}
+
+impl ScheduledTweet {
+    /// The tweet to post, ready for its images to be uploaded.
+    pub fn tweet(&self) -> Tweet {
+        let payload = self.payload.clone().unwrap_or_else(|| TweetBody {
+            text: Some(self.body.clone()),
+            ..Default::default()
+        });
+        let send_time = self
+            .scheduled_for
+            .parse()
+            .unwrap_or_else(|_| Timestamp::now());
+        build_tweet(payload, send_time, self.format.clone(), self.auto_split)
+    }
+
+    /// What the next attempt posts: the rest of a thread an earlier attempt left part way
+    /// posted, so its live parts are not posted twice, or else the whole tweet.
+    pub fn next_attempt(&self) -> Result<Tweet, TwitterError> {
+        match self.thread_id {
+            Some(_) => self.next_attempt_from(&Threads::default()),
+            None => Ok(self.tweet()),
+        }
+    }
+
+    /// Like [`ScheduledTweet::next_attempt`], with the thread progress read from `threads`.
+    fn next_attempt_from(&self, threads: &Threads) -> Result<Tweet, TwitterError> {
+        match self.thread_id {
+            Some(thread_id) => Tweet::resume_from(threads, thread_id),
+            None => Ok(self.tweet()),
+        }
+    }
+}
+
+/// A time as shown to the user, in the local time zone.
+pub fn local_time(time: Timestamp) -> String {
+    time.to_zoned(TimeZone::system())
+        .strftime("%a %Y-%m-%d %H:%M %Z")
+        .to_string()
+}
+
+/// Reads a `CURRENT_TIMESTAMP` written by SQLite, which is in UTC.
+pub(crate) fn sqlite_time(time: &str) -> Option<Timestamp> {
+    let time = time.parse::<DateTime>().ok()?;
+    time.to_zoned(TimeZone::UTC)
+        .ok()
+        .map(|time| time.timestamp())
+}
+
+/// Builds the tweet that goes out at `send_time`, with the template variables of its text filled.
+fn build_tweet(
+    mut payload: TweetBody,
+    send_time: Timestamp,
+    format: Option<ThreadFormat>,
+    auto_split: bool,
+) -> Tweet {
+    let date = send_time.to_zoned(TimeZone::system()).date();
+    payload.text = payload.text.map(|text| fill_template(&text, date));
+    Tweet::new(payload)
+        .format(format.unwrap_or_else(thread_format))
+        .auto_split(auto_split)
 }
 
 pub struct Schedule {
     tweet_body: TweetBody,
+    images: Vec<Image>,
+    media_options: MediaOptions,
+    format: Option<ThreadFormat>,
+    auto_split: bool,
+    account: Option<usize>,
+    recurrence: Option<Recurrence>,
     send_time: Timestamp,
     connection: Connection,
 }
@@ -73,12 +177,20 @@ impl Default for Schedule {
         let connection = db.open_connection();
         Self {
             tweet_body: Default::default(),
+            images: vec![],
+            media_options: Default::default(),
+            format: None,
+            auto_split: false,
+            account: None,
+            recurrence: None,
             send_time: Default::default(),
             connection,
         }
     }
 }
 
+const COLUMNS: &str = "id, body, status, scheduled_for, attempts, last_error, sent_at, created_at, updated_at, payload, images, media_options, format, auto_split, account, recurrence, retryable, thread_id";
+
 impl Schedule {
     pub fn new(body: &str, time: &str) -> Self {
         let tweet_body = match TweetBody::from_str(body) {
@@ -104,30 +216,125 @@ impl Schedule {
                 })
                 .timestamp();
         }
-        let db = Database::new(SCHEDULE_TABLE_NAME);
-        let connection = db.open_connection();
-
         Self {
             tweet_body,
             send_time,
-            connection,
+            ..Default::default()
         }
     }
 
+    /// A tweet that repeats by `recurrence`, first sent at its next occurrence.
+    pub fn recurring(body: &str, recurrence: Recurrence) -> Self {
+        let Some(send_time) = recurrence.next_after(Timestamp::now()) else {
+            gracefully_exit(&format!("'{recurrence}' never matches a date."));
+        };
+        Self::new(body, &send_time.to_string()).repeat(recurrence)
+    }
+
+    /// Schedules the next occurrence of `recurrence` every time the tweet is sent.
+    pub fn repeat(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = Some(recurrence);
+        self
+    }
+
+    pub fn recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    /// When the tweet is first sent.
+    pub fn send_time(&self) -> Timestamp {
+        self.send_time
+    }
+
+    /// Posts `payload` instead of plain text, keeping its reply target, quote and poll.
+    pub fn payload(mut self, payload: TweetBody) -> Self {
+        self.tweet_body = payload;
+        self
+    }
+
+    /// Images for the first tweet. Their paths are read when the tweet is sent.
+    pub fn images(mut self, images: Vec<Image>, options: MediaOptions) -> Self {
+        self.images = images;
+        self.media_options = options;
+        self
+    }
+
+    pub fn format(mut self, format: ThreadFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn auto_split(mut self, auto_split: bool) -> Self {
+        self.auto_split = auto_split;
+        self
+    }
+
+    pub fn account(mut self, account: usize) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    /// Fails if the tweet is too long or an image cannot be posted, so it is not saved to fail later.
+    pub fn check(&self) -> Result<(), TwitterError> {
+        let tweet = build_tweet(
+            self.tweet_body.clone(),
+            self.send_time,
+            self.format.clone(),
+            self.auto_split,
+        );
+        tweet.check_length()?;
+        tweet.check_media(self.images.clone(), &self.media_options)
+    }
+
     pub fn save(self) -> bool {
         let query = format!(
             "
             INSERT INTO {SCHEDULE_TABLE_NAME} (
                 body,
-                scheduled_for
-            ) VALUES (?1, ?2);
+                scheduled_for,
+                payload,
+                images,
+                media_options,
+                format,
+                auto_split,
+                account,
+                recurrence
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9);
             ",
         );
 
-        if let Err(err) = self
-            .connection
-            .execute(&query, (self.tweet_body.text, self.send_time.to_string()))
-        {
+        let encoded = serde_json::to_string(&self.tweet_body).and_then(|payload| {
+            let images = serde_json::to_string(&self.images)?;
+            let media_options = serde_json::to_string(&self.media_options)?;
+            let format = self
+                .format
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?;
+            Ok((payload, images, media_options, format))
+        });
+        let (payload, images, media_options, format) = match encoded {
+            Ok(encoded) => encoded,
+            Err(err) => {
+                eprintln!("Failed to save scheduled tweet: {err}");
+                return false;
+            }
+        };
+
+        if let Err(err) = self.connection.execute(
+            &query,
+            (
+                self.tweet_body.text.unwrap_or_default(),
+                self.send_time.to_string(),
+                payload,
+                images,
+                media_options,
+                format,
+                self.auto_split,
+                self.account.map(|account| account as i64),
+                self.recurrence.map(|recurrence| recurrence.to_string()),
+            ),
+        ) {
             eprintln!("Failed to save scheduled tweet: {err}");
             return false;
         }
@@ -136,7 +343,7 @@ impl Schedule {
     }
 
     pub fn all(&self) -> Vec<ScheduledTweet> {
-        let query = format!("SELECT * from {SCHEDULE_TABLE_NAME}");
+        let query = format!("SELECT {COLUMNS} from {SCHEDULE_TABLE_NAME}");
         self.query_tweets(&query)
     }
 
@@ -153,21 +360,96 @@ impl Schedule {
 
     pub(crate) fn due(&self) -> Vec<ScheduledTweet> {
         let query = format!(
-            "SELECT * from {SCHEDULE_TABLE_NAME} WHERE datetime('now') > datetime(scheduled_for) AND status = 'pending'"
+            "SELECT {COLUMNS} from {SCHEDULE_TABLE_NAME} WHERE datetime('now') > datetime(scheduled_for) AND status = 'pending'"
+        );
+        self.query_tweets(&query)
+    }
+
+    /// Failed tweets that are sent again once their backoff is over.
+    pub(crate) fn retries(&self, policy: &ScheduleRetry) -> Vec<ScheduledTweet> {
+        let query = format!(
+            "SELECT {COLUMNS} from {SCHEDULE_TABLE_NAME} WHERE status = 'failed' AND retryable = 1 AND attempts < {}",
+            policy.max_attempts
         );
         self.query_tweets(&query)
     }
 
+    /// Failed tweets whose backoff is over.
+    pub(crate) fn due_retries(&self, policy: &ScheduleRetry) -> Vec<ScheduledTweet> {
+        let now = Timestamp::now();
+        self.retries(policy)
+            .into_iter()
+            .filter(|tweet| policy.retry_at(tweet).is_some_and(|at| at <= now))
+            .collect()
+    }
+
+    pub(crate) fn get(&self, id: u32) -> Option<ScheduledTweet> {
+        let query = format!("SELECT {COLUMNS} from {SCHEDULE_TABLE_NAME} WHERE id = {id}");
+        self.query_tweets(&query).pop()
+    }
+
+    pub(crate) fn pending(&self) -> Vec<ScheduledTweet> {
+        let query = format!("SELECT {COLUMNS} from {SCHEDULE_TABLE_NAME} WHERE status = 'pending'");
+        self.query_tweets(&query)
+    }
+
+    /// Changes whenever another connection, e.g. `schedule new` in another process, commits
+    /// to the database.
+    pub(crate) fn data_version(&self) -> Option<i64> {
+        self.connection
+            .query_row("PRAGMA data_version", [], |row| row.get(0))
+            .ok()
+    }
+
     pub(crate) fn failed(&self) -> Vec<ScheduledTweet> {
-        let query = format!("SELECT * from {SCHEDULE_TABLE_NAME} WHERE status = 'failed'");
+        let query = format!("SELECT {COLUMNS} from {SCHEDULE_TABLE_NAME} WHERE status = 'failed'");
         self.query_tweets(&query)
     }
 
     pub(crate) fn sent(&self) -> Vec<ScheduledTweet> {
-        let query = format!("SELECT * from {SCHEDULE_TABLE_NAME} WHERE status = 'sent'");
+        let query = format!("SELECT {COLUMNS} from {SCHEDULE_TABLE_NAME} WHERE status = 'sent'");
         self.query_tweets(&query)
     }
 
+    /// Adds the next occurrence of a recurring tweet, a copy of it that is due by its rule.
+    /// Returns when it is sent, or `None` for tweets that do not repeat.
+    pub(crate) fn schedule_next(&self, tweet: &ScheduledTweet) -> Option<Timestamp> {
+        let recurrence = match tweet.recurrence.as_deref()?.parse::<Recurrence>() {
+            Ok(recurrence) => recurrence,
+            Err(err) => {
+                eprintln!("Failed to repeat scheduled tweet {}: {err}", tweet.id);
+                return None;
+            }
+        };
+        // Occurrences missed while nothing ran are skipped, not sent in a burst.
+        let after = tweet
+            .scheduled_for
+            .parse::<Timestamp>()
+            .unwrap_or_default()
+            .max(Timestamp::now());
+        let send_time = recurrence.next_after(after)?;
+
+        let query = format!(
+            "
+            INSERT INTO {SCHEDULE_TABLE_NAME} (
+                body, scheduled_for, payload, images, media_options, format, auto_split, account, recurrence
+            )
+            SELECT body, ?2, payload, images, media_options, format, auto_split, account, recurrence
+            FROM {SCHEDULE_TABLE_NAME} WHERE id = ?1
+            "
+        );
+        match self
+            .connection
+            .execute(&query, (tweet.id, send_time.to_string()))
+        {
+            Ok(_) => Some(send_time),
+            Err(err) => {
+                eprintln!("Failed to repeat scheduled tweet {}: {err}", tweet.id);
+                None
+            }
+        }
+    }
+
     pub(crate) fn mark_sent(&self, id: u32) {
         let query = format!(
             "UPDATE {SCHEDULE_TABLE_NAME}
@@ -182,17 +464,30 @@ impl Schedule {
         }
     }
 
-    pub(crate) fn mark_failed(&self, id: u32, error_message: &str) {
+    /// Records a failed attempt. A `retryable` tweet is sent again by the retry policy, from
+    /// the part `thread_id` stopped at when it is a thread that is part way posted.
+    pub(crate) fn mark_failed(
+        &self,
+        id: u32,
+        error_message: &str,
+        retryable: bool,
+        thread_id: Option<i64>,
+    ) {
         let query = format!(
             "UPDATE {SCHEDULE_TABLE_NAME}
              SET status = 'failed',
                  attempts = attempts + 1,
+                 retryable = ?3,
+                 thread_id = ?4,
                  last_error = ?1,
                  sent_at = NULL,
                  updated_at = CURRENT_TIMESTAMP
              WHERE id = ?2"
         );
-        if let Err(err) = self.connection.execute(&query, (error_message, id)) {
+        if let Err(err) = self
+            .connection
+            .execute(&query, (error_message, id, retryable, thread_id))
+        {
             eprintln!("Failed to mark scheduled tweet {} as failed: {err}", id);
         }
     }
@@ -217,6 +512,17 @@ impl Schedule {
                 sent_at: row.get(6)?,
                 created_at: row.get(7)?,
                 updated_at: row.get(8)?,
+                payload: json_column(row, 9)?,
+                images: json_column::<Option<_>>(row, 10)?.unwrap_or_default(),
+                media_options: json_column::<Option<_>>(row, 11)?.unwrap_or_default(),
+                format: json_column(row, 12)?,
+                auto_split: row.get(13)?,
+                account: row
+                    .get::<_, Option<i64>>(14)?
+                    .map(|account| account as usize),
+                recurrence: row.get(15)?,
+                retryable: row.get(16)?,
+                thread_id: row.get(17)?,
             })
         }) {
             Ok(rows) => rows,
@@ -238,6 +544,13 @@ impl Schedule {
     }
 }
 
+/// Decodes a column holding JSON. `T` is an `Option` for columns that may be NULL.
+fn json_column<T: DeserializeOwned>(row: &Row<'_>, index: usize) -> rusqlite::Result<T> {
+    let value: Option<String> = row.get(index)?;
+    serde_json::from_str(value.as_deref().unwrap_or("null"))
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, err.into()))
+}
+
 #[cfg(test)]
 fn set_test_data_dir_override(path: PathBuf) {
     let lock = test_data_dir_lock();
@@ -256,12 +569,25 @@ fn test_data_dir_lock() -> &'static std::sync::Mutex<Option<PathBuf>> {
 
 #[cfg(test)]
 mod test {
-    use std::{env, fs};
+    use std::{env, fs, str::FromStr};
 
     use parse_datetime::parse_datetime;
-
-    use crate::schedule::Schedule;
+    use rusqlite::Connection;
+
+    use crate::{
+        constants::{CREATE_SCHEDULES_TABLE, SCHEDULE_TABLE_NAME},
+        database::migrate,
+        schedule::Schedule,
+        twitter::{
+            media::Image,
+            preflight::MediaOptions,
+            progress::Threads,
+            text::{Numbering, ThreadFormat},
+            tweet::{Reply, TweetBody},
+        },
+    };
     use serial_test::serial;
+    use std::path::PathBuf;
 
     fn setup_test_data_dir() {
         let base = env::temp_dir().join("twitter-cli-tests");
@@ -311,7 +637,7 @@ mod test {
             .unwrap()
             .id;
 
-        schedule.mark_failed(failed_id, "network error");
+        schedule.mark_failed(failed_id, "network error", true, None);
         schedule.mark_sent(sent_id);
 
         let failed = schedule.failed();
@@ -336,14 +662,155 @@ mod test {
             .unwrap()
             .id;
 
-        schedule.mark_failed(tweet_id, "timeout");
+        schedule.mark_failed(tweet_id, "timeout", true, None);
         let failed = schedule.failed();
         let row = failed.iter().find(|tweet| tweet.id == tweet_id).unwrap();
 
         assert_eq!(row.attempts, 1);
+        assert!(row.retryable);
         assert_eq!(row.last_error.as_deref(), Some("timeout"));
     }
 
+    #[test]
+    fn schedule_keeps_the_whole_tweet() {
+        let path = env::temp_dir().join("twitter-cli-tests/schedule-payload.sqlite3");
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        let _ = fs::remove_file(&path);
+        // A row saved before the payload columns existed.
+        let old = Connection::open(&path).unwrap();
+        old.execute(CREATE_SCHEDULES_TABLE, []).unwrap();
+        old.execute(
+            "INSERT INTO scheduled_tweets (body, scheduled_for) VALUES ('old tweet', '2099-01-01')",
+            [],
+        )
+        .unwrap();
+
+        let connection = || {
+            let connection = Connection::open(&path).unwrap();
+            migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+            connection
+        };
+        let mut schedule = Schedule::new("new tweet", "2099-01-01 09:00")
+            .payload(TweetBody {
+                text: Some("new tweet".to_string()),
+                reply: Some(Reply {
+                    in_reply_to_tweet_id: "42".to_string(),
+                }),
+                ..Default::default()
+            })
+            .images(
+                vec![Image {
+                    path: PathBuf::from("/tmp/chart.png"),
+                    alt: Some("A chart".to_string()),
+                }],
+                MediaOptions {
+                    downscale: true,
+                    ..Default::default()
+                },
+            )
+            .format(ThreadFormat {
+                numbering: Numbering::Prefix,
+                ..Default::default()
+            })
+            .auto_split(true)
+            .account(1);
+        schedule.connection = connection();
+        assert!(schedule.save());
+
+        let schedule = Schedule {
+            connection: connection(),
+            ..Schedule::new("", "2099-01-01 09:00")
+        };
+        let all = schedule.all();
+        let old = &all[0];
+        assert!(old.payload.is_none());
+        assert!(old.images.is_empty());
+        assert_eq!(old.account, None);
+        assert_eq!(old.tweet().parts(), vec!["old tweet"]);
+
+        let new = &all[1];
+        let payload = new.payload.as_ref().unwrap();
+        assert_eq!(payload.reply.as_ref().unwrap().in_reply_to_tweet_id, "42");
+        assert_eq!(new.images[0].alt.as_deref(), Some("A chart"));
+        assert!(new.media_options.downscale);
+        assert_eq!(new.format.as_ref().unwrap().numbering, Numbering::Prefix);
+        assert!(new.auto_split);
+        assert_eq!(new.account, Some(1));
+    }
+
+    #[test]
+    fn schedule_next_copies_recurring_tweets() {
+        let path = env::temp_dir().join("twitter-cli-tests/schedule-recurring.sqlite3");
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        let _ = fs::remove_file(&path);
+        let connection = || {
+            let connection = Connection::open(&path).unwrap();
+            migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+            connection
+        };
+
+        let mut once = Schedule::new("once", "2099-01-01 09:00");
+        once.connection = connection();
+        assert!(once.save());
+        let mut weekly = Schedule::new("Week {week}", "2099-01-01 09:00")
+            .repeat("every monday 09:00".parse().unwrap())
+            .account(1);
+        weekly.connection = connection();
+        assert!(weekly.save());
+
+        let schedule = Schedule {
+            connection: connection(),
+            ..Schedule::new("", "2099-01-01 09:00")
+        };
+        let all = schedule.all();
+        assert_eq!(schedule.schedule_next(&all[0]), None);
+        let next = schedule.schedule_next(&all[1]).unwrap();
+
+        let all = schedule.all();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2].scheduled_for, next.to_string());
+        assert!(next > all[1].scheduled_for.parse().unwrap());
+        assert_eq!(all[2].recurrence.as_deref(), Some("every monday 09:00"));
+        assert_eq!(all[2].account, Some(1));
+    }
+
+    #[test]
+    fn schedule_resumes_threads_that_failed_part_way() {
+        let threads = Threads::in_memory();
+        let payload = TweetBody::from_str("one\n---\ntwo").unwrap();
+        let parts = vec!["one".to_string(), "two".to_string()];
+        let thread_id = threads.start(&payload, &parts, &[None, None], 0).unwrap();
+        threads.posted(thread_id, "100").unwrap();
+
+        let path = env::temp_dir().join("twitter-cli-tests/schedule-resume.sqlite3");
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        let _ = fs::remove_file(&path);
+        let connection = || {
+            let connection = Connection::open(&path).unwrap();
+            migrate(&connection, SCHEDULE_TABLE_NAME).unwrap();
+            connection
+        };
+        let mut thread = Schedule::new("one\n---\ntwo", "2099-01-01 09:00");
+        thread.connection = connection();
+        assert!(thread.save());
+
+        let schedule = Schedule {
+            connection: connection(),
+            ..Schedule::new("", "2099-01-01 09:00")
+        };
+        let id = schedule.all()[0].id;
+        schedule.mark_failed(id, "error[server_error]", true, Some(thread_id));
+
+        let failed = schedule.get(id).unwrap();
+        assert_eq!(failed.thread_id, Some(thread_id));
+        let tweet = failed.next_attempt_from(&threads).unwrap();
+        assert_eq!(tweet.unfinished_thread(), Some(thread_id));
+
+        // Finished by `tweet --resume` in the meantime: a retry fails rather than post it again.
+        threads.finished(thread_id);
+        assert!(failed.next_attempt_from(&threads).is_err());
+    }
+
     #[test]
     fn roll_forward_when_past() {
         let got = Schedule::new("body", "2026-01-01 09:00").send_time;
@@ -0,0 +1,339 @@
+use std::{fmt::Display, str::FromStr};
+
+use jiff::{Timestamp, ToSpan, civil::Date, tz::TimeZone};
+
+/// How far ahead an occurrence is searched for. A rule such as `0 9 29 2 *` repeats every
+/// four years at most (leap days), so anything without a match by then never matches.
+const SEARCH_DAYS: i32 = 366 * 8;
+
+const WEEKDAYS: [&str; 7] = [
+    "sunday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+];
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// When a scheduled tweet repeats: a cron expression (`minute hour day month weekday`,
+/// e.g. `0 9 * * 1`) or a rule like `every monday 09:00`, `every weekday 8:30` or
+/// `every day at 17:00`. Times are in the local time zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    rule: String,
+    /// Bit sets of the values each field matches.
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day of month and the weekday were left as `*`. As in cron, a day matches
+    /// either field when both are restricted.
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Recurrence {
+    /// The first occurrence strictly after `after`.
+    pub fn next_after(&self, after: Timestamp) -> Option<Timestamp> {
+        self.next_after_in(after, &TimeZone::system())
+    }
+
+    pub fn next_after_in(&self, after: Timestamp, zone: &TimeZone) -> Option<Timestamp> {
+        let start = after.to_zoned(zone.clone()).date();
+        for offset in 0..SEARCH_DAYS {
+            let date = start.checked_add(offset.days()).ok()?;
+            if !self.matches_day(date) {
+                continue;
+            }
+            for hour in bits(self.hours) {
+                for minute in bits(self.minutes) {
+                    let Ok(time) = date
+                        .at(hour as i8, minute as i8, 0, 0)
+                        .to_zoned(zone.clone())
+                    else {
+                        continue;
+                    };
+                    if time.timestamp() > after {
+                        return Some(time.timestamp());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, date: Date) -> bool {
+        let day = has(self.days, date.day() as u32);
+        let weekday = has(self.weekdays, date.weekday().to_sunday_zero_offset() as u32);
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        };
+        day_matches && has(self.months, date.month() as u32)
+    }
+
+    fn parse_every(rule: &str, rest: &str) -> Result<Self, String> {
+        let mut words: Vec<&str> = rest
+            .split_whitespace()
+            .filter(|word| *word != "at")
+            .collect();
+        let Some(time) = words.pop() else {
+            return Err(format!(
+                "'{rule}' is missing a time, e.g. every monday 09:00"
+            ));
+        };
+        let (hour, minute) = time
+            .split_once(':')
+            .and_then(|(hour, minute)| {
+                Some((hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?))
+            })
+            .filter(|(hour, minute)| *hour < 24 && *minute < 60)
+            .ok_or_else(|| format!("Invalid time '{time}' in '{rule}', expected HH:MM"))?;
+
+        let days = match words.as_slice() {
+            [] | ["day"] => None,
+            ["weekday"] => Some(weekday_bits([1, 2, 3, 4, 5])),
+            ["weekend"] => Some(weekday_bits([0, 6])),
+            days => {
+                let mut set = 0;
+                for day in days.iter().flat_map(|days| days.split(',')) {
+                    let day = day.trim_end_matches('s');
+                    let Some(index) = WEEKDAYS
+                        .iter()
+                        .position(|name| !day.is_empty() && (*name == day || name[..3] == *day))
+                    else {
+                        return Err(format!("Unknown day '{day}' in '{rule}'"));
+                    };
+                    set |= 1 << index;
+                }
+                Some(set)
+            }
+        };
+
+        Ok(Self {
+            rule: rule.to_string(),
+            minutes: 1 << minute,
+            hours: 1 << hour,
+            days: range_bits(1, 31),
+            months: range_bits(1, 12),
+            weekdays: days.unwrap_or(range_bits(0, 6)),
+            any_day: true,
+            any_weekday: days.is_none(),
+        })
+    }
+
+    fn parse_cron(rule: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = rule.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            return Err(format!(
+                "'{rule}' is not a recurrence. Use five cron fields (minute hour day month weekday) or a rule like 'every monday 09:00'."
+            ));
+        };
+        let mut weekday_set = field(weekdays, 0, 7, &WEEKDAYS)?;
+        // Both 0 and 7 are Sunday.
+        if has(weekday_set, 7) {
+            weekday_set = (weekday_set | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            rule: rule.to_string(),
+            minutes: field(minutes, 0, 59, &[])?,
+            hours: field(hours, 0, 23, &[])?,
+            days: field(days, 1, 31, &[])?,
+            months: field(months, 1, 12, &MONTHS)?,
+            weekdays: weekday_set,
+            any_day: days.starts_with('*'),
+            any_weekday: weekdays.starts_with('*'),
+        })
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rule = s.trim().to_lowercase();
+        let recurrence = match rule.strip_prefix("every ") {
+            Some(rest) => Self::parse_every(s.trim(), rest)?,
+            None => Self::parse_cron(s.trim())?,
+        };
+        if recurrence
+            .next_after_in(Timestamp::now(), &TimeZone::UTC)
+            .is_none()
+        {
+            return Err(format!("'{}' never matches a date.", s.trim()));
+        }
+        Ok(recurrence)
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rule)
+    }
+}
+
+/// Fills `{date}`, `{week}` and `{year}` in `text`.
+pub fn fill_template(text: &str, date: Date) -> String {
+    text.replace("{date}", &date.to_string())
+        .replace("{week}", &date.iso_week_date().week().to_string())
+        .replace("{year}", &date.year().to_string())
+}
+
+/// Parses one cron field, e.g. `*`, `*/15`, `1-5`, `mon,wed` or `0-30/10`.
+fn field(spec: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let text = text.to_lowercase();
+        let value = match names
+            .iter()
+            .position(|name| *name == text || name[..3] == text)
+        {
+            // Month names start at 1, weekday names at 0.
+            Some(index) => index as u32 + min.min(1),
+            None => text
+                .parse()
+                .map_err(|_| format!("Invalid value '{text}' in cron field '{spec}'"))?,
+        };
+        if value < min || value > max {
+            return Err(format!(
+                "{value} is out of range {min}-{max} in cron field '{spec}'"
+            ));
+        }
+        Ok(value)
+    };
+
+    let mut set = 0;
+    for item in spec.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid step '{step}' in cron field '{spec}'"))?,
+            ),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if start > end {
+            return Err(format!("Invalid range '{range}' in cron field '{spec}'"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+fn weekday_bits<const N: usize>(days: [u32; N]) -> u64 {
+    days.iter().fold(0, |set, day| set | 1 << day)
+}
+
+fn range_bits(start: u32, end: u32) -> u64 {
+    (start..=end).fold(0, |set, value| set | 1 << value)
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn bits(set: u64) -> impl Iterator<Item = u32> {
+    (0..64).filter(move |value| has(set, *value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next(rule: &str, after: &str) -> String {
+        let after: Timestamp = after.parse().unwrap();
+        rule.parse::<Recurrence>()
+            .unwrap()
+            .next_after_in(after, &TimeZone::UTC)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_every_rules() {
+        // 2026-10-18 is a Sunday.
+        assert_eq!(
+            next("every monday 09:00", "2026-10-18T12:00:00Z"),
+            "2026-10-19T09:00:00Z"
+        );
+        assert_eq!(
+            next("every day at 9:30", "2026-10-18T09:30:00Z"),
+            "2026-10-19T09:30:00Z"
+        );
+        assert_eq!(
+            next("every weekday 08:00", "2026-10-16T09:00:00Z"),
+            "2026-10-19T08:00:00Z"
+        );
+        assert_eq!(
+            next("Every Tue,Thu 17:00", "2026-10-18T00:00:00Z"),
+            "2026-10-20T17:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_cron_rules() {
+        assert_eq!(
+            next("0 9 * * 1", "2026-10-19T09:00:00Z"),
+            "2026-10-26T09:00:00Z"
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-10-18T12:07:00Z"),
+            "2026-10-18T12:15:00Z"
+        );
+        assert_eq!(
+            next("0 0 29 feb *", "2026-10-18T00:00:00Z"),
+            "2028-02-29T00:00:00Z"
+        );
+        // A day matches either field when both are set: the 1st or any Sunday.
+        assert_eq!(
+            next("0 12 1 * 7", "2026-10-19T00:00:00Z"),
+            "2026-10-25T12:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        for rule in [
+            "every monday",
+            "every funday 09:00",
+            "every day 25:00",
+            "0 9 * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "0 9 31 2 *",
+        ] {
+            assert!(rule.parse::<Recurrence>().is_err(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn test_fill_template() {
+        assert_eq!(
+            fill_template(
+                "Week {week} of {year}, {date}",
+                jiff::civil::date(2026, 10, 19)
+            ),
+            "Week 43 of 2026, 2026-10-19"
+        );
+    }
+}
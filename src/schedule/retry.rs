@@ -0,0 +1,118 @@
+use jiff::{SignedDuration, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::{ScheduleStatus, ScheduledTweet, sqlite_time};
+
+/// How scheduled tweets that failed for a transient reason are sent again. Configured under
+/// `[schedule_retry]` in `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScheduleRetry {
+    /// Total attempts per scheduled tweet, including the first one. 1 turns retries off.
+    pub max_attempts: u32,
+    /// Wait after the first failure. It doubles with every further failure.
+    pub retry_delay_secs: u64,
+    /// Upper bound for a single wait.
+    pub max_retry_delay_secs: u64,
+}
+
+impl Default for ScheduleRetry {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            retry_delay_secs: 60,
+            max_retry_delay_secs: 3_600,
+        }
+    }
+}
+
+impl ScheduleRetry {
+    /// Whether a tweet that has failed `attempts` times is tried again.
+    pub fn allows(&self, attempts: u32) -> bool {
+        attempts < self.max_attempts
+    }
+
+    /// The wait after failure number `attempts` (1-based).
+    pub fn backoff(&self, attempts: u32) -> SignedDuration {
+        let exponent = attempts.saturating_sub(1).min(32);
+        let delay = self
+            .retry_delay_secs
+            .saturating_mul(1_u64 << exponent)
+            .min(self.max_retry_delay_secs);
+        SignedDuration::from_secs(delay.min(i64::MAX as u64) as i64)
+    }
+
+    /// When a failed tweet is sent again, or `None` when it is not retried. A time that
+    /// cannot be read counts as long past.
+    pub fn retry_at(&self, tweet: &ScheduledTweet) -> Option<Timestamp> {
+        if !matches!(tweet.status, ScheduleStatus::Failed)
+            || !tweet.retryable
+            || !self.allows(tweet.attempts)
+        {
+            return None;
+        }
+        let failed_at = sqlite_time(&tweet.updated_at).unwrap_or_default();
+        Some(
+            failed_at
+                .checked_add(self.backoff(tweet.attempts))
+                .unwrap_or(failed_at),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(attempts: u32, retryable: bool) -> ScheduledTweet {
+        ScheduledTweet {
+            id: 1,
+            body: "tweet".to_string(),
+            status: ScheduleStatus::Failed,
+            scheduled_for: "2026-10-18T09:00:00Z".to_string(),
+            attempts,
+            retryable,
+            last_error: Some("error[server_error]: 503".to_string()),
+            sent_at: None,
+            created_at: "2026-10-18 08:00:00".to_string(),
+            updated_at: "2026-10-18 09:00:00".to_string(),
+            payload: None,
+            images: vec![],
+            media_options: Default::default(),
+            format: None,
+            auto_split: false,
+            account: None,
+            recurrence: None,
+            thread_id: None,
+        }
+    }
+
+    #[test]
+    fn test_retries_back_off_from_the_last_failure() {
+        let policy = ScheduleRetry {
+            max_attempts: 4,
+            retry_delay_secs: 60,
+            max_retry_delay_secs: 150,
+        };
+
+        let at = |attempts| {
+            policy
+                .retry_at(&failed(attempts, true))
+                .map(|at| at.to_string())
+        };
+        assert_eq!(at(1).as_deref(), Some("2026-10-18T09:01:00Z"));
+        assert_eq!(at(2).as_deref(), Some("2026-10-18T09:02:00Z"));
+        assert_eq!(at(3).as_deref(), Some("2026-10-18T09:02:30Z"));
+        assert_eq!(at(4), None);
+    }
+
+    #[test]
+    fn test_only_transient_failures_are_retried() {
+        let policy = ScheduleRetry::default();
+        assert!(policy.retry_at(&failed(1, false)).is_none());
+
+        let mut pending = failed(0, true);
+        pending.status = ScheduleStatus::Pending;
+        assert!(policy.retry_at(&pending).is_none());
+    }
+}
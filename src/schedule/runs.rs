@@ -0,0 +1,128 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::{constants::SCHEDULE_RUNS_TABLE_NAME, database::Database};
+
+/// When `schedule run` last ran, so `schedule status` can tell whether something calls it.
+#[derive(Debug, Serialize)]
+pub struct LastRun {
+    pub ran_at: String,
+    /// The last run in which no tweet failed.
+    pub succeeded_at: Option<String>,
+    /// Why a tweet failed in the last run.
+    pub last_error: Option<String>,
+}
+
+pub struct ScheduleRuns {
+    connection: Connection,
+}
+
+impl Default for ScheduleRuns {
+    fn default() -> Self {
+        let db = Database::new(SCHEDULE_RUNS_TABLE_NAME);
+        Self {
+            connection: db.open_connection(),
+        }
+    }
+}
+
+impl ScheduleRuns {
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        let connection = Connection::open_in_memory().unwrap();
+        crate::database::migrate(&connection, SCHEDULE_RUNS_TABLE_NAME).unwrap();
+        Self { connection }
+    }
+
+    /// Records a finished run. `error` is why a tweet failed, if one did.
+    pub fn record(&self, error: Option<&str>) {
+        let query = format!(
+            "
+            INSERT INTO {SCHEDULE_RUNS_TABLE_NAME} (id, ran_at, succeeded_at, last_error)
+            VALUES (1, CURRENT_TIMESTAMP, CASE WHEN ?1 IS NULL THEN CURRENT_TIMESTAMP END, ?1)
+            ON CONFLICT(id) DO UPDATE SET
+                ran_at = excluded.ran_at,
+                succeeded_at = COALESCE(excluded.succeeded_at, succeeded_at),
+                last_error = excluded.last_error;
+            "
+        );
+        if let Err(err) = self.connection.execute(&query, [error]) {
+            eprintln!("Failed to record the scheduler run: {err}");
+        }
+    }
+
+    /// Records that the daemon is still running, without touching the outcome of its last
+    /// send.
+    pub fn heartbeat(&self) {
+        let query = format!(
+            "
+            INSERT INTO {SCHEDULE_RUNS_TABLE_NAME} (id, ran_at) VALUES (1, CURRENT_TIMESTAMP)
+            ON CONFLICT(id) DO UPDATE SET ran_at = excluded.ran_at;
+            "
+        );
+        if let Err(err) = self.connection.execute(&query, []) {
+            eprintln!("Failed to record the scheduler run: {err}");
+        }
+    }
+
+    pub fn last(&self) -> Option<LastRun> {
+        let query =
+            format!("SELECT ran_at, succeeded_at, last_error FROM {SCHEDULE_RUNS_TABLE_NAME}");
+        self.connection
+            .query_row(&query, [], |row| {
+                Ok(LastRun {
+                    ran_at: row.get(0)?,
+                    succeeded_at: row.get(1)?,
+                    last_error: row.get(2)?,
+                })
+            })
+            .optional()
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to read the last scheduler run: {err}");
+                None
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrate;
+
+    #[test]
+    fn test_last_success_survives_a_failed_run() {
+        let connection = Connection::open_in_memory().unwrap();
+        migrate(&connection, SCHEDULE_RUNS_TABLE_NAME).unwrap();
+        let runs = ScheduleRuns { connection };
+        assert!(runs.last().is_none());
+
+        runs.record(None);
+        let succeeded_at = runs.last().unwrap().succeeded_at;
+        assert!(succeeded_at.is_some());
+
+        runs.record(Some("error[local]: boom"));
+        let last = runs.last().unwrap();
+        assert_eq!(last.succeeded_at, succeeded_at);
+        assert_eq!(last.last_error.as_deref(), Some("error[local]: boom"));
+    }
+
+    #[test]
+    fn test_heartbeat_keeps_the_last_send() {
+        let runs = ScheduleRuns::in_memory();
+        runs.heartbeat();
+        let last = runs.last().unwrap();
+        assert!(last.succeeded_at.is_none() && last.last_error.is_none());
+
+        runs.record(Some("error[local]: boom"));
+        runs.connection
+            .execute(
+                &format!("UPDATE {SCHEDULE_RUNS_TABLE_NAME} SET ran_at = '2000-01-01 00:00:00'"),
+                [],
+            )
+            .unwrap();
+        runs.heartbeat();
+        let last = runs.last().unwrap();
+        assert_ne!(last.ran_at, "2000-01-01 00:00:00");
+        assert_eq!(last.last_error.as_deref(), Some("error[local]: boom"));
+    }
+}
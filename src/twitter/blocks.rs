@@ -2,58 +2,52 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     auth::oauth2::TokenManager,
-    twitter::Response,
+    twitter::{
+        Response,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::{get_current_user_id, oauth_post_header},
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateBlockData {
     pub blocking: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateBlockResponse {
     pub data: CreateBlockData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateBlockError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteBlockData {
     pub blocking: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteBlockResponse {
     pub data: DeleteBlockData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteBlockError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BlockedUsersMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BlockedUser {
     pub id: String,
     pub name: String,
     pub username: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BlockedUsersResponse {
     #[serde(default)]
     pub data: Vec<BlockedUser>,
@@ -61,11 +55,6 @@ pub struct BlockedUsersResponse {
     pub meta: Option<BlockedUsersMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct BlockedUsersError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct CreateBlock {
     user_id: String,
@@ -90,8 +79,8 @@ struct CreateBlockBody<'a> {
 }
 
 impl BlockedUsers {
-    pub fn current_user() -> Result<Self, BlockedUsersError> {
-        let user_id = get_current_user_id().map_err(|message| BlockedUsersError { message })?;
+    pub fn current_user() -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             max_results: 10,
@@ -103,137 +92,89 @@ impl BlockedUsers {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/blocking", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/blocking", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<BlockedUsersResponse>, BlockedUsersError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<BlockedUsersResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let user_fields = "name,username";
         let access_token = TokenManager::default().get_token();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("user.fields", user_fields)
             .header(curl_rest::Header::Authorization(
                 format!("Bearer {}", access_token).into(),
             ))
-            .send(url.as_str())
-            .map_err(|err| BlockedUsersError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: BlockedUsersResponse =
-                serde_json::from_slice(&response.body).map_err(|err| BlockedUsersError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(BlockedUsersError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl CreateBlock {
-    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, CreateBlockError> {
-        let user_id = get_current_user_id().map_err(|message| CreateBlockError { message })?;
+    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             target_user_id: target_user_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/blocking", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/blocking", self.user_id))
     }
 
-    pub fn send(&self) -> Result<Response<CreateBlockResponse>, CreateBlockError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<CreateBlockResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateBlockBody {
             target_user_id: self.target_user_id.as_str(),
         })
-        .map_err(|err| CreateBlockError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .idempotent()
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| CreateBlockError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: CreateBlockResponse =
-                serde_json::from_slice(&response.body).map_err(|err| CreateBlockError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(CreateBlockError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl DeleteBlock {
-    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, DeleteBlockError> {
-        let source_user_id =
-            get_current_user_id().map_err(|message| DeleteBlockError { message })?;
+    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let source_user_id = get_current_user_id()?;
         Ok(Self {
             source_user_id,
             target_user_id: target_user_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/blocking/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/users/{}/blocking/{}",
             self.source_user_id, self.target_user_id
-        )
+        ))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteBlockResponse>, DeleteBlockError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteBlockResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| DeleteBlockError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: DeleteBlockResponse =
-                serde_json::from_slice(&response.body).map_err(|err| DeleteBlockError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(DeleteBlockError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -256,6 +197,9 @@ impl std::fmt::Display for BlockedUsersResponse {
     }
 }
 
+impl_page!(BlockedUsersResponse);
+impl_paginated!(BlockedUsers => BlockedUsersResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,7 +212,10 @@ mod tests {
             max_results: 10,
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/42/blocking");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/42/blocking"
+        );
     }
 
     #[test]
@@ -283,7 +230,10 @@ mod tests {
             target_user_id: "99".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/42/blocking");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/42/blocking"
+        );
     }
 
     #[test]
@@ -293,6 +243,9 @@ mod tests {
             target_user_id: "99".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/42/blocking/99");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/42/blocking/99"
+        );
     }
 }
@@ -5,21 +5,25 @@ use serde::Serialize;
 
 use crate::auth::oauth2::TokenManager;
 use crate::{
-    twitter::{AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS},
+    twitter::{
+        AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::get_current_user_id,
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BookmarksMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BookmarksResponse {
     #[serde(default)]
     pub data: Vec<TweetData>,
@@ -29,60 +33,45 @@ pub struct BookmarksResponse {
     pub meta: Option<BookmarksMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct BookmarksError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct Bookmarks {
     user_id: String,
     max_results: u8,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateBookmarkData {
     pub bookmarked: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateBookmarkResponse {
     pub data: CreateBookmarkData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateBookmarkError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct CreateBookmark {
     user_id: String,
     tweet_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteBookmarkData {
     pub bookmarked: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteBookmarkResponse {
     pub data: DeleteBookmarkData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteBookmarkError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct DeleteBookmark {
     user_id: String,
     tweet_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BookmarkFolder {
     pub id: String,
     pub name: String,
@@ -90,17 +79,16 @@ pub struct BookmarkFolder {
     pub tweet_count: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BookmarkFoldersMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct BookmarkFoldersResponse {
     #[serde(default)]
     pub data: Vec<BookmarkFolder>,
@@ -108,11 +96,6 @@ pub struct BookmarkFoldersResponse {
     pub meta: Option<BookmarkFoldersMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct BookmarkFoldersError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct BookmarkFolders {
     user_id: String,
@@ -132,8 +115,8 @@ struct CreateBookmarkBody<'a> {
 }
 
 impl Bookmarks {
-    pub fn current_user() -> Result<Self, BookmarksError> {
-        let user_id = get_current_user_id().map_err(|message| BookmarksError { message })?;
+    pub fn current_user() -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             max_results: 10,
@@ -145,12 +128,15 @@ impl Bookmarks {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/bookmarks", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/bookmarks", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<BookmarksResponse>, BookmarksError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<BookmarksResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results;
         let max_results_query = max_results.to_string();
         let tweet_fields = TWEET_FIELDS.to_string();
@@ -160,135 +146,87 @@ impl Bookmarks {
         let token_manager = TokenManager::new();
         let access_token = token_manager.get_token();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results_query.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("tweet.fields", tweet_fields.as_str())
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
             .header(curl_rest::Header::Authorization(
                 format!("Bearer {}", access_token).into(),
             ))
-            .send(url.as_str())
-            .map_err(|err| BookmarksError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let bookmarks_data: BookmarksResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| BookmarksError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: bookmarks_data,
-            })
-        } else {
-            Err(BookmarksError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl CreateBookmark {
-    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, CreateBookmarkError> {
-        let user_id = get_current_user_id().map_err(|message| CreateBookmarkError { message })?;
+    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             tweet_id: tweet_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/bookmarks", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/bookmarks", self.user_id))
     }
 
-    pub fn send(&self) -> Result<Response<CreateBookmarkResponse>, CreateBookmarkError> {
-        let url = self.url();
+    pub fn send(&self) -> Result<Response<CreateBookmarkResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateBookmarkBody {
             tweet_id: self.tweet_id.as_str(),
         })
-        .map_err(|err| CreateBookmarkError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
         let token_manager = TokenManager::new();
         let access_token = token_manager.get_token();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
+            .idempotent()
             .header(curl_rest::Header::Authorization(
                 format!("Bearer {}", access_token).into(),
             ))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| CreateBookmarkError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let bookmark_data: CreateBookmarkResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| CreateBookmarkError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: bookmark_data,
-            })
-        } else {
-            Err(CreateBookmarkError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl DeleteBookmark {
-    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, DeleteBookmarkError> {
-        let user_id = get_current_user_id().map_err(|message| DeleteBookmarkError { message })?;
+    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             tweet_id: tweet_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/bookmarks/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/users/{}/bookmarks/{}",
             self.user_id, self.tweet_id
-        )
+        ))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteBookmarkResponse>, DeleteBookmarkError> {
-        let url = self.url();
+    pub fn send(&self) -> Result<Response<DeleteBookmarkResponse>, TwitterError> {
+        let url = self.url(api());
         let token_manager = TokenManager::new();
         let access_token = token_manager.get_token();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
             .header(curl_rest::Header::Authorization(
                 format!("Bearer {}", access_token).into(),
             ))
-            .send(url.as_str())
-            .map_err(|err| DeleteBookmarkError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let bookmark_data: DeleteBookmarkResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| DeleteBookmarkError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: bookmark_data,
-            })
-        } else {
-            Err(DeleteBookmarkError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -311,8 +249,8 @@ impl Display for BookmarkFoldersResponse {
 }
 
 impl BookmarkFolders {
-    pub fn current_user() -> Result<Self, BookmarkFoldersError> {
-        let user_id = get_current_user_id().map_err(|message| BookmarkFoldersError { message })?;
+    pub fn current_user() -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             max_results: 10,
@@ -324,50 +262,35 @@ impl BookmarkFolders {
         self
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/bookmarks/folders",
-            self.user_id
-        )
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/bookmarks/folders", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<BookmarkFoldersResponse>, BookmarkFoldersError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<BookmarkFoldersResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let token_manager = TokenManager::new();
         let access_token = token_manager.get_token();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .header(curl_rest::Header::Authorization(
                 format!("Bearer {}", access_token).into(),
             ))
-            .send(url.as_str())
-            .map_err(|err| BookmarkFoldersError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let folder_data: BookmarkFoldersResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| BookmarkFoldersError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: folder_data,
-            })
-        } else {
-            Err(BookmarkFoldersError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl BookmarkFolderTweets {
-    pub fn current_user(folder_id: impl Into<String>) -> Result<Self, BookmarksError> {
-        let user_id = get_current_user_id().map_err(|message| BookmarksError { message })?;
+    pub fn current_user(folder_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             folder_id: folder_id.into(),
@@ -380,15 +303,18 @@ impl BookmarkFolderTweets {
         self
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/bookmarks/folders/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/users/{}/bookmarks/folders/{}",
             self.user_id, self.folder_id
-        )
+        ))
     }
 
-    pub fn fetch(&self) -> Result<Response<BookmarksResponse>, BookmarksError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<BookmarksResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results;
         let max_results_query = max_results.to_string();
         let tweet_fields = TWEET_FIELDS.to_string();
@@ -397,37 +323,28 @@ impl BookmarkFolderTweets {
         let token_manager = TokenManager::new();
         let access_token = token_manager.get_token();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results_query.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("tweet.fields", tweet_fields.as_str())
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
             .header(curl_rest::Header::Authorization(
                 format!("Bearer {}", access_token).into(),
             ))
-            .send(url.as_str())
-            .map_err(|err| BookmarksError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let bookmarks_data: BookmarksResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| BookmarksError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: bookmarks_data,
-            })
-        } else {
-            Err(BookmarksError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
+impl_page!(BookmarksResponse, includes);
+impl_page!(BookmarkFoldersResponse);
+impl_paginated!(Bookmarks => BookmarksResponse);
+impl_paginated!(BookmarkFolders => BookmarkFoldersResponse);
+impl_paginated!(BookmarkFolderTweets => BookmarksResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -439,7 +356,10 @@ mod tests {
             max_results: 10,
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/bookmarks");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/bookmarks"
+        );
     }
 
     #[test]
@@ -449,7 +369,10 @@ mod tests {
             tweet_id: "456".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/bookmarks");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/bookmarks"
+        );
     }
 
     #[test]
@@ -460,7 +383,7 @@ mod tests {
         };
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/users/123/bookmarks/456"
         );
     }
@@ -473,7 +396,7 @@ mod tests {
         };
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/users/123/bookmarks/folders"
         );
     }
@@ -487,7 +410,7 @@ mod tests {
         };
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/users/123/bookmarks/folders/456"
         );
     }
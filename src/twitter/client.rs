@@ -0,0 +1,566 @@
+use std::{
+    borrow::Cow,
+    env, fs,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use curl_rest::{Header, Method, QueryParam};
+
+use crate::{
+    config::Config,
+    twitter::{
+        error::TwitterError,
+        rate_limit::{self, RateLimit},
+        retry::{RetryPolicy, Transient},
+    },
+    utils::get_config_file,
+};
+
+pub const DEFAULT_API_BASE_URL: &str = "https://api.x.com";
+pub const API_BASE_URL_ENV: &str = "TWITTER_API_BASE_URL";
+const USER_AGENT: &str = "twitter-cli";
+
+/// Custom headers whose value is hidden when a request is printed by `--dry-run`.
+const SECRET_HEADER_WORDS: [&str; 5] = ["auth", "token", "key", "secret", "cookie"];
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Makes every request print what it would send instead of sending it.
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::Relaxed);
+}
+
+pub fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Shared context for every X API call.
+///
+/// The base URL is resolved once per process from `TWITTER_API_BASE_URL`, then
+/// `api_base_url` in the config file, and finally falls back to `https://api.x.com`.
+/// The retry policy comes from the `[retry]` table of the config file.
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    retry: RetryPolicy,
+    /// Whether the rate limits of responses are stored for `twitter limits`. Only the
+    /// shared [`api`] client stores them.
+    record_rate_limits: bool,
+}
+
+/// A request builder that mirrors `curl_rest::Client` but is sent through the shared client.
+pub struct Request<'a> {
+    method: Method,
+    retry: RetryPolicy,
+    record_rate_limits: bool,
+    idempotent: bool,
+    authorization: Option<Box<dyn Fn() -> String + 'a>>,
+    headers: Vec<Header<'a>>,
+    query: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    body: Option<RequestBody<'a>>,
+}
+
+enum RequestBody<'a> {
+    Json(Cow<'a, str>),
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl Default for ApiClient {
+    fn default() -> Self {
+        Self::new(DEFAULT_API_BASE_URL)
+    }
+}
+
+impl ApiClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let base_url: String = base_url.into();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            retry: RetryPolicy::default(),
+            record_rate_limits: false,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    fn from_environment() -> Self {
+        let config = fs::read_to_string(get_config_file())
+            .ok()
+            .and_then(|data| toml::from_str::<Config>(&data).ok())
+            .unwrap_or_default();
+
+        let base_url = env::var(API_BASE_URL_ENV)
+            .ok()
+            .or(config.api_base_url)
+            .filter(|base_url| !base_url.trim().is_empty());
+
+        let client = match base_url {
+            Some(base_url) => Self::new(base_url.trim()),
+            None => Self::default(),
+        };
+
+        Self {
+            record_rate_limits: true,
+            ..client.with_retry(config.retry.unwrap_or_default())
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_str()
+    }
+
+    /// Joins an API path such as `/2/users/me` onto the base URL.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    pub fn request<'a>(&self) -> Request<'a> {
+        Request {
+            method: Method::Get,
+            retry: self.retry.clone(),
+            record_rate_limits: self.record_rate_limits,
+            idempotent: true,
+            authorization: None,
+            headers: vec![],
+            query: vec![],
+            body: None,
+        }
+    }
+
+    pub fn get<'a>(&self) -> Request<'a> {
+        self.request().method(Method::Get)
+    }
+
+    pub fn post<'a>(&self) -> Request<'a> {
+        self.request().method(Method::Post)
+    }
+
+    pub fn put<'a>(&self) -> Request<'a> {
+        self.request().method(Method::Put)
+    }
+
+    pub fn delete<'a>(&self) -> Request<'a> {
+        self.request().method(Method::Delete)
+    }
+}
+
+impl<'a> Request<'a> {
+    pub fn method(mut self, method: Method) -> Self {
+        self.idempotent = !matches!(method, Method::Post | Method::Patch);
+        self.method = method;
+        self
+    }
+
+    /// Marks a POST as safe to repeat, e.g. liking a tweet that is already liked.
+    pub fn idempotent(mut self) -> Self {
+        self.idempotent = true;
+        self
+    }
+
+    /// Signs every attempt with a fresh `Authorization` header.
+    ///
+    /// OAuth 1.0a signatures carry a nonce and timestamp, so a request that is sent again
+    /// after waiting out a rate limit has to be signed again.
+    pub fn authorization(mut self, sign: impl Fn() -> String + 'a) -> Self {
+        self.authorization = Some(Box::new(sign));
+        self
+    }
+
+    pub fn header(mut self, header: Header<'a>) -> Self {
+        self.headers.push(header);
+        self
+    }
+
+    pub fn query_param_kv(
+        mut self,
+        key: impl Into<Cow<'a, str>>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds the query parameter only when there is a value, e.g. an optional `pagination_token`.
+    pub fn query_param_opt(
+        self,
+        key: impl Into<Cow<'a, str>>,
+        value: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match value {
+            Some(value) => self.query_param_kv(key, value),
+            None => self,
+        }
+    }
+
+    pub fn body_json(mut self, json: impl Into<Cow<'a, str>>) -> Self {
+        self.body = Some(RequestBody::Json(json.into()));
+        self
+    }
+
+    pub fn body_bytes(mut self, bytes: impl Into<Cow<'a, [u8]>>) -> Self {
+        self.body = Some(RequestBody::Bytes(bytes.into()));
+        self
+    }
+
+    /// Sends the request to `url`, which is expected to come from [`ApiClient::url`].
+    ///
+    /// The `x-rate-limit-*` headers of every response are recorded per endpoint by the
+    /// shared [`api`] client. A 429 waits
+    /// until the window resets and tries again, up to `max_rate_limit_waits` times, unless
+    /// waiting was disabled with `--no-wait`.
+    /// Transient failures are retried according to the client's [`RetryPolicy`]; requests
+    /// that may already have been applied are only retried when they are idempotent.
+    /// Any other status outside `2xx` is returned as [`TwitterError::Api`].
+    ///
+    /// With `--dry-run` the request is printed instead and [`TwitterError::DryRun`] returned.
+    pub fn send(self, url: &str) -> Result<curl_rest::Response, TwitterError> {
+        if dry_run() {
+            println!("{}", self.describe(url));
+            return Err(TwitterError::DryRun);
+        }
+
+        let endpoint = rate_limit::endpoint_key(&format!("{:?}", self.method), url);
+        let mut attempt = 0;
+        let mut rate_limit_waits = 0;
+
+        loop {
+            attempt += 1;
+            let (err, transient) = match self.send_once(url) {
+                Ok(response) => {
+                    let budget = RateLimit::from_headers(endpoint.as_str(), &response.headers);
+                    if self.record_rate_limits
+                        && let Some(budget) = &budget
+                    {
+                        rate_limit::record(budget);
+                    }
+                    let status = response.status.as_u16();
+                    if (200..300).contains(&status) {
+                        return Ok(response);
+                    }
+
+                    let err = TwitterError::from_status(status, &response.body);
+                    if let Some(budget) = budget
+                        && err.is_rate_limited()
+                        && !rate_limit::no_wait()
+                        && rate_limit_waits < self.retry.max_rate_limit_waits
+                    {
+                        rate_limit_waits += 1;
+                        rate_limit::wait_for_reset(&budget);
+                        continue;
+                    }
+
+                    (err, Transient::from_status(status))
+                }
+                Err(err) => {
+                    let transient = Transient::from_transport(&err);
+                    (TwitterError::from(err), transient)
+                }
+            };
+
+            match transient {
+                Some(transient) if self.retry.allows(attempt, transient, self.idempotent) => {
+                    let backoff = self.retry.backoff(attempt);
+                    eprintln!(
+                        "> {endpoint} failed ({}). Retrying in {:.1}s (attempt {}/{}).",
+                        err.kind().as_str(),
+                        backoff.as_secs_f32(),
+                        attempt + 1,
+                        self.retry.max_attempts
+                    );
+                    thread::sleep(backoff);
+                }
+                _ => return Err(err),
+            }
+        }
+    }
+
+    fn send_once(&self, url: &str) -> Result<curl_rest::Response, curl_rest::Error> {
+        let mut headers = self.headers.clone();
+        if let Some(sign) = &self.authorization {
+            headers.push(Header::Authorization(sign().into()));
+        }
+
+        let mut request = curl_rest::Client::with_user_agent(USER_AGENT)
+            .method(self.method.clone())
+            .headers(headers)
+            .query_params(
+                self.query
+                    .iter()
+                    .map(|(key, value)| QueryParam::new(key.as_ref(), value.as_ref())),
+            );
+
+        request = match &self.body {
+            Some(RequestBody::Json(json)) => request.body_json(json.as_ref()),
+            Some(RequestBody::Bytes(bytes)) => request.body_bytes(bytes.as_ref()),
+            None => request,
+        };
+
+        request.send(url)
+    }
+
+    /// What `--dry-run` prints: the method, URL, auth scheme, headers and body, with the
+    /// credentials left out.
+    fn describe(&self, url: &str) -> String {
+        let mut url = url.to_string();
+        for (index, (key, value)) in self.query.iter().enumerate() {
+            url.push(if index == 0 && !url.contains('?') {
+                '?'
+            } else {
+                '&'
+            });
+            url.push_str(&format!("{key}={value}"));
+        }
+
+        let method = format!("{:?}", self.method).to_uppercase();
+        let mut lines = vec![format!("[dry run] {method} {url}")];
+
+        let authorization = self.authorization.as_ref().map(|sign| sign()).or_else(|| {
+            self.headers.iter().find_map(|header| match header {
+                Header::Authorization(value) => Some(value.to_string()),
+                _ => None,
+            })
+        });
+        let auth = match authorization {
+            Some(header) if header.starts_with("OAuth ") => "OAuth 1.0a user context",
+            Some(header) if header.starts_with("Bearer ") => "Bearer token",
+            Some(_) => "custom",
+            None => "none",
+        };
+        lines.push(format!("Auth: {auth} (credentials redacted)"));
+
+        for header in &self.headers {
+            let (name, value) = match header {
+                Header::ContentType(value) => ("Content-Type", value.clone()),
+                Header::Accept(value) => ("Accept", value.clone()),
+                Header::Custom(name, value) => {
+                    let lower = name.to_lowercase();
+                    let value = if SECRET_HEADER_WORDS.iter().any(|word| lower.contains(word)) {
+                        "<redacted>".into()
+                    } else {
+                        value.clone()
+                    };
+                    (name.as_ref(), value)
+                }
+                // Authorization is summed up by the auth scheme above.
+                _ => continue,
+            };
+            lines.push(format!("{name}: {value}"));
+        }
+
+        match &self.body {
+            Some(RequestBody::Json(json)) => {
+                let pretty = serde_json::from_str::<serde_json::Value>(json)
+                    .ok()
+                    .and_then(|value| serde_json::to_string_pretty(&value).ok())
+                    .unwrap_or_else(|| json.to_string());
+                lines.push(pretty);
+            }
+            Some(RequestBody::Bytes(bytes)) => match std::str::from_utf8(bytes) {
+                Ok(text) if !text.contains('\0') => lines.push(text.trim_end().to_string()),
+                _ => lines.push(format!("<{} bytes>", bytes.len())),
+            },
+            None => {}
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Returns the process-wide API client.
+pub fn api() -> &'static ApiClient {
+    static CLIENT: OnceLock<ApiClient> = OnceLock::new();
+    CLIENT.get_or_init(ApiClient::from_environment)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::{Arc, atomic::AtomicUsize},
+        thread::JoinHandle,
+    };
+
+    use super::*;
+    use crate::twitter::error::ErrorKind;
+
+    /// Reads one request from `stream` and answers it with `status` and extra `headers`.
+    fn answer(stream: TcpStream, status: u16, headers: &str) {
+        let mut reader = BufReader::new(stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                content_length = length.trim().parse().unwrap();
+            }
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        let body = match status {
+            200..300 => r#"{"data":{}}"#,
+            429 => r#"{"title":"Too Many Requests","status":429}"#,
+            _ => r#"{"title":"Service Unavailable","status":503}"#,
+        };
+        let response = format!(
+            "HTTP/1.1 {status} Status\r\nContent-Type: application/json\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        reader.get_mut().write_all(response.as_bytes()).unwrap();
+    }
+
+    fn test_client(base_url: String) -> ApiClient {
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            base_delay_ms: 1,
+            max_delay_ms: 5,
+            retry_non_idempotent: false,
+            max_rate_limit_waits: 1,
+        };
+        ApiClient::new(base_url).with_retry(retry)
+    }
+
+    /// Answers one connection per canned status and returns how many requests it served.
+    fn fake_server(statuses: Vec<u16>) -> (ApiClient, JoinHandle<usize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut served = 0;
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                answer(stream, status, "");
+                served += 1;
+            }
+            served
+        });
+
+        (test_client(base_url), handle)
+    }
+
+    #[test]
+    fn test_url_joins_path_onto_base_url() {
+        let client = ApiClient::new("http://127.0.0.1:8080/");
+
+        assert_eq!(
+            client.url("/2/users/me"),
+            "http://127.0.0.1:8080/2/users/me"
+        );
+        assert_eq!(client.url("2/tweets"), "http://127.0.0.1:8080/2/tweets");
+    }
+
+    #[test]
+    fn test_default_base_url_is_production_host() {
+        let client = ApiClient::default();
+
+        assert_eq!(client.base_url(), DEFAULT_API_BASE_URL);
+        assert_eq!(client.url("/2/tweets"), "https://api.x.com/2/tweets");
+    }
+
+    #[test]
+    fn test_get_is_retried_after_server_errors() {
+        let (client, server) = fake_server(vec![503, 502, 200]);
+
+        let response = client.get().send(&client.url("/2/users/me"));
+
+        assert!(response.is_ok());
+        assert_eq!(server.join().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_retries_stop_after_max_attempts() {
+        let (client, server) = fake_server(vec![503, 503, 503]);
+
+        let err = client
+            .delete()
+            .send(&client.url("/2/tweets/1"))
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::ServerError);
+        assert_eq!(server.join().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_rate_limit_waits_run_out() {
+        // A server that is always rate limited, with a window that reset long ago.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = test_client(format!("http://{}", listener.local_addr().unwrap()));
+        let served = Arc::new(AtomicUsize::new(0));
+        let counter = served.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                counter.fetch_add(1, Ordering::Relaxed);
+                answer(
+                    stream.unwrap(),
+                    429,
+                    "x-rate-limit-limit: 1\r\nx-rate-limit-remaining: 0\r\nx-rate-limit-reset: 0\r\n",
+                );
+            }
+        });
+
+        let err = client.get().send(&client.url("/2/users/me")).unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::RateLimited);
+        assert_eq!(served.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_post_is_not_retried_when_it_may_have_been_applied() {
+        let (client, server) = fake_server(vec![503]);
+
+        let err = client
+            .post()
+            .body_json(r#"{"text":"hello"}"#)
+            .send(&client.url("/2/tweets"))
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::ServerError);
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_idempotent_post_is_retried() {
+        let (client, server) = fake_server(vec![500, 200]);
+
+        let response = client
+            .post()
+            .idempotent()
+            .body_json(r#"{"tweet_id":"1"}"#)
+            .send(&client.url("/2/users/1/likes"));
+
+        assert!(response.is_ok());
+        assert_eq!(server.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_dry_run_description_redacts_credentials() {
+        let client = ApiClient::new("https://api.x.com");
+        let request = client
+            .post()
+            .authorization(|| "OAuth oauth_token=\"secret\"".to_string())
+            .header(Header::Custom("X-Api-Key".into(), "secret".into()))
+            .query_param_kv("dry", "1")
+            .body_json(r#"{"text":"hello"}"#);
+
+        let description = request.describe(&client.url("/2/tweets"));
+
+        assert!(!description.contains("secret"));
+        assert_eq!(
+            description,
+            "[dry run] POST https://api.x.com/2/tweets?dry=1\n\
+             Auth: OAuth 1.0a user context (credentials redacted)\n\
+             X-Api-Key: <redacted>\n\
+             {\n  \"text\": \"hello\"\n}"
+        );
+    }
+}
@@ -1,78 +1,69 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    twitter::Response,
+    twitter::{
+        Response,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::{get_current_user_id, oauth_get_header, oauth_post_header},
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SendConversationMessageData {
     pub dm_conversation_id: String,
     pub dm_event_id: String,
     pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SendConversationMessageResponse {
     pub data: SendConversationMessageData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SendConversationMessageError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateConversationData {
     pub dm_conversation_id: String,
     pub dm_event_id: String,
     pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateConversationResponse {
     pub data: CreateConversationData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateConversationError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SendWithParticipantMessageData {
     pub dm_conversation_id: String,
     pub dm_event_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SendWithParticipantMessageResponse {
     pub data: SendWithParticipantMessageData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SendWithParticipantMessageError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ConversationDmEvent {
     pub dm_conversation_id: String,
     pub dm_event_id: String,
     pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ConversationDmEventsMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ConversationDmEventsResponse {
     #[serde(default)]
     pub data: Vec<ConversationDmEvent>,
@@ -80,29 +71,23 @@ pub struct ConversationDmEventsResponse {
     pub meta: Option<ConversationDmEventsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ConversationDmEventsError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UserDmEvent {
     pub dm_conversation_id: String,
     pub dm_event_id: String,
     pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UserDmEventsMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UserDmEventsResponse {
     #[serde(default)]
     pub data: Vec<UserDmEvent>,
@@ -110,29 +95,23 @@ pub struct UserDmEventsResponse {
     pub meta: Option<UserDmEventsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct UserDmEventsError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ParticipantDmEvent {
     pub dm_conversation_id: String,
     pub dm_event_id: String,
     pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ParticipantDmEventsMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ParticipantDmEventsResponse {
     #[serde(default)]
     pub data: Vec<ParticipantDmEvent>,
@@ -140,11 +119,6 @@ pub struct ParticipantDmEventsResponse {
     pub meta: Option<ParticipantDmEventsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ParticipantDmEventsError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct SendConversationMessage {
     conversation_id: String,
@@ -201,48 +175,27 @@ impl SendConversationMessage {
         }
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/dm_conversations/{}/messages",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/dm_conversations/{}/messages",
             self.conversation_id
-        )
+        ))
     }
 
-    pub fn send(
-        &self,
-    ) -> Result<Response<SendConversationMessageResponse>, SendConversationMessageError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<SendConversationMessageResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&SendConversationMessageBody {
             text: self.text.as_str(),
         })
-        .map_err(|err| SendConversationMessageError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| SendConversationMessageError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: SendConversationMessageResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| SendConversationMessageError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(SendConversationMessageError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -259,49 +212,39 @@ impl ConversationDmEvents {
         self
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/dm_conversations/{}/dm_events",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/dm_conversations/{}/dm_events",
             self.conversation_id
-        )
+        ))
     }
 
-    pub fn fetch(
+    pub fn fetch_page(
         &self,
-    ) -> Result<Response<ConversationDmEventsResponse>, ConversationDmEventsError> {
-        let url = self.url();
+        pagination_token: Option<&str>,
+    ) -> Result<Response<ConversationDmEventsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
-        let auth_header = oauth_get_header(url.as_str(), &());
+        let mut auth_params = vec![("max_results", &max_results as &dyn Display)];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| ConversationDmEventsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: ConversationDmEventsResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| ConversationDmEventsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(ConversationDmEventsError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .query_param_opt("pagination_token", pagination_token)
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl UserDmEvents {
-    pub fn current_user() -> Result<Self, UserDmEventsError> {
-        let user_id = get_current_user_id().map_err(|message| UserDmEventsError { message })?;
+    pub fn current_user() -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             max_results: 10,
@@ -313,38 +256,30 @@ impl UserDmEvents {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/dm_events", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/dm_events", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<UserDmEventsResponse>, UserDmEventsError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<UserDmEventsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
-        let auth_header = oauth_get_header(url.as_str(), &());
+        let mut auth_params = vec![("max_results", &max_results as &dyn Display)];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| UserDmEventsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: UserDmEventsResponse =
-                serde_json::from_slice(&response.body).map_err(|err| UserDmEventsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(UserDmEventsError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .query_param_opt("pagination_token", pagination_token)
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -361,41 +296,33 @@ impl ParticipantDmEvents {
         self
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/dm_conversations/with/{}/dm_events",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/dm_conversations/with/{}/dm_events",
             self.participant_id
-        )
+        ))
     }
 
-    pub fn fetch(&self) -> Result<Response<ParticipantDmEventsResponse>, ParticipantDmEventsError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<ParticipantDmEventsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
-        let auth_header = oauth_get_header(url.as_str(), &());
+        let mut auth_params = vec![("max_results", &max_results as &dyn Display)];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| ParticipantDmEventsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: ParticipantDmEventsResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| ParticipantDmEventsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(ParticipantDmEventsError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .query_param_opt("pagination_token", pagination_token)
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -407,50 +334,27 @@ impl SendWithParticipantMessage {
         }
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/dm_conversations/with/{}/messages",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/dm_conversations/with/{}/messages",
             self.participant_id
-        )
+        ))
     }
 
-    pub fn send(
-        &self,
-    ) -> Result<Response<SendWithParticipantMessageResponse>, SendWithParticipantMessageError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<SendWithParticipantMessageResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&SendConversationMessageBody {
             text: self.text.as_str(),
         })
-        .map_err(|err| SendWithParticipantMessageError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| SendWithParticipantMessageError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: SendWithParticipantMessageResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| {
-                SendWithParticipantMessageError {
-                    message: err.to_string(),
-                }
-            })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(SendWithParticipantMessageError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -462,45 +366,26 @@ impl CreateConversation {
         }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/dm_conversations"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/dm_conversations")
     }
 
-    pub fn send(&self) -> Result<Response<CreateConversationResponse>, CreateConversationError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url, &());
+    pub fn send(&self) -> Result<Response<CreateConversationResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateConversationBody {
             conversation_type: "GroupDM",
             participant_ids: self.participant_ids.as_slice(),
             text: self.text.as_str(),
         })
-        .map_err(|err| CreateConversationError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url)
-            .map_err(|err| CreateConversationError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: CreateConversationResponse =
-                serde_json::from_slice(&response.body).map_err(|err| CreateConversationError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(CreateConversationError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -591,6 +476,13 @@ impl std::fmt::Display for ParticipantDmEventsResponse {
     }
 }
 
+impl_page!(ConversationDmEventsResponse);
+impl_page!(UserDmEventsResponse);
+impl_page!(ParticipantDmEventsResponse);
+impl_paginated!(ConversationDmEvents => ConversationDmEventsResponse);
+impl_paginated!(UserDmEvents => UserDmEventsResponse);
+impl_paginated!(ParticipantDmEvents => ParticipantDmEventsResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -600,7 +492,7 @@ mod tests {
         let endpoint = SendConversationMessage::new("123", "hello");
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/dm_conversations/123/messages"
         );
     }
@@ -609,7 +501,10 @@ mod tests {
     fn test_create_conversation_uses_collection_url() {
         let endpoint = CreateConversation::new(vec!["1".to_string(), "2".to_string()], "hello");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/dm_conversations");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/dm_conversations"
+        );
     }
 
     #[test]
@@ -617,7 +512,7 @@ mod tests {
         let endpoint = SendWithParticipantMessage::new("123", "hello");
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/dm_conversations/with/123/messages"
         );
     }
@@ -627,7 +522,7 @@ mod tests {
         let endpoint = ConversationDmEvents::new("123");
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/dm_conversations/123/dm_events"
         );
     }
@@ -639,7 +534,10 @@ mod tests {
             max_results: 10,
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/dm_events");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/dm_events"
+        );
     }
 
     #[test]
@@ -647,7 +545,7 @@ mod tests {
         let endpoint = ParticipantDmEvents::new("123");
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/dm_conversations/with/123/dm_events"
         );
     }
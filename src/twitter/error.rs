@@ -0,0 +1,299 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Every failure an endpoint can produce.
+#[derive(Debug)]
+pub enum TwitterError {
+    /// The API answered with a non-success status.
+    Api {
+        status: u16,
+        problem: Option<Box<Problem>>,
+        body: String,
+    },
+    /// The request never completed: DNS, TLS, connection resets, timeouts.
+    Transport(String),
+    /// The API answered with a success status but the body did not match the expected shape.
+    Decode(String),
+    /// The request could not be prepared locally, e.g. an unreadable file or a missing cache.
+    Local(String),
+    /// The request was printed instead of sent because of `--dry-run`.
+    DryRun,
+}
+
+/// The problem document X returns alongside error statuses.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Problem {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub detail: Option<String>,
+    #[serde(default, rename = "type")]
+    pub problem_type: Option<String>,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub errors: Vec<ProblemError>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProblemError {
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub detail: Option<String>,
+    #[serde(default, rename = "type")]
+    pub problem_type: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidRequest,
+    Unauthorized,
+    Forbidden,
+    DuplicateContent,
+    NotFound,
+    RateLimited,
+    ServerError,
+    OtherStatus,
+    Transport,
+    Decode,
+    Local,
+    DryRun,
+}
+
+impl ErrorKind {
+    /// A stable identifier scripts can match on.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::InvalidRequest => "invalid_request",
+            ErrorKind::Unauthorized => "unauthorized",
+            ErrorKind::Forbidden => "forbidden",
+            ErrorKind::DuplicateContent => "duplicate_content",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::ServerError => "server_error",
+            ErrorKind::OtherStatus => "api_error",
+            ErrorKind::Transport => "transport",
+            ErrorKind::Decode => "decode",
+            ErrorKind::Local => "local",
+            ErrorKind::DryRun => "dry_run",
+        }
+    }
+
+    /// The process exit code used by the CLI for this kind of failure.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Local => 1,
+            ErrorKind::InvalidRequest | ErrorKind::OtherStatus => 2,
+            ErrorKind::Unauthorized => 3,
+            ErrorKind::Forbidden => 4,
+            ErrorKind::DuplicateContent => 5,
+            ErrorKind::NotFound => 6,
+            ErrorKind::RateLimited => 7,
+            ErrorKind::ServerError => 8,
+            ErrorKind::Transport => 9,
+            ErrorKind::Decode => 10,
+            ErrorKind::DryRun => 0,
+        }
+    }
+}
+
+impl TwitterError {
+    /// Builds an API error from a non-success status and its raw body.
+    pub fn from_status(status: u16, body: &[u8]) -> Self {
+        let problem = serde_json::from_slice::<Problem>(body)
+            .ok()
+            .filter(|problem| problem.title.is_some() || !problem.errors.is_empty())
+            .map(Box::new);
+        Self::Api {
+            status,
+            problem,
+            body: String::from_utf8_lossy(body).to_string(),
+        }
+    }
+
+    pub fn local(err: impl Display) -> Self {
+        Self::Local(err.to_string())
+    }
+
+    pub fn decode(err: impl Display) -> Self {
+        Self::Decode(err.to_string())
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            TwitterError::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub fn problem(&self) -> Option<&Problem> {
+        match self {
+            TwitterError::Api { problem, .. } => problem.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TwitterError::Api { status, .. } => match status {
+                400 => ErrorKind::InvalidRequest,
+                401 => ErrorKind::Unauthorized,
+                403 if self.mentions("duplicate content") => ErrorKind::DuplicateContent,
+                403 => ErrorKind::Forbidden,
+                404 => ErrorKind::NotFound,
+                429 => ErrorKind::RateLimited,
+                500..=599 => ErrorKind::ServerError,
+                _ => ErrorKind::OtherStatus,
+            },
+            TwitterError::Transport(_) => ErrorKind::Transport,
+            TwitterError::Decode(_) => ErrorKind::Decode,
+            TwitterError::Local(_) => ErrorKind::Local,
+            TwitterError::DryRun => ErrorKind::DryRun,
+        }
+    }
+
+    pub fn is_duplicate(&self) -> bool {
+        self.kind() == ErrorKind::DuplicateContent
+    }
+
+    /// Whether sending the same request later may work: a rate limit, a 5xx or a network
+    /// error. A rejection such as duplicate content fails the same way every time.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.kind(),
+            ErrorKind::RateLimited | ErrorKind::ServerError | ErrorKind::Transport
+        )
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.kind() == ErrorKind::RateLimited
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.kind() == ErrorKind::DryRun
+    }
+
+    pub fn is_unauthorized(&self) -> bool {
+        self.kind() == ErrorKind::Unauthorized
+    }
+
+    fn mentions(&self, needle: &str) -> bool {
+        let Some(problem) = self.problem() else {
+            return false;
+        };
+
+        problem
+            .detail
+            .iter()
+            .chain(problem.errors.iter().filter_map(|err| err.message.as_ref()))
+            .chain(problem.errors.iter().filter_map(|err| err.detail.as_ref()))
+            .any(|text| text.to_lowercase().contains(needle))
+    }
+}
+
+impl Display for TwitterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = self.kind().as_str();
+        match self {
+            TwitterError::Api {
+                status,
+                problem: Some(problem),
+                ..
+            } => {
+                let title = problem.title.as_deref().unwrap_or("Request failed");
+                write!(f, "error[{kind}]: {title} ({status})")?;
+                if let Some(detail) = &problem.detail {
+                    write!(f, ": {detail}")?;
+                }
+                for err in &problem.errors {
+                    if let Some(message) = err.message.as_ref().or(err.detail.as_ref()) {
+                        write!(f, "\n  - {message}")?;
+                    }
+                }
+                Ok(())
+            }
+            TwitterError::Api { status, body, .. } => {
+                write!(f, "error[{kind}]: HTTP {status}")?;
+                if !body.trim().is_empty() {
+                    write!(f, ": {}", body.trim())?;
+                }
+                Ok(())
+            }
+            TwitterError::Transport(message) => {
+                write!(f, "error[{kind}]: Request failed: {message}")
+            }
+            TwitterError::Decode(message) => {
+                write!(f, "error[{kind}]: Unexpected response body: {message}")
+            }
+            TwitterError::Local(message) => write!(f, "error[{kind}]: {message}"),
+            TwitterError::DryRun => write!(f, "error[{kind}]: Not sent because of --dry-run"),
+        }
+    }
+}
+
+impl std::error::Error for TwitterError {}
+
+impl From<curl_rest::Error> for TwitterError {
+    fn from(err: curl_rest::Error) -> Self {
+        Self::Transport(err.to_string())
+    }
+}
+
+impl From<curl::Error> for TwitterError {
+    fn from(err: curl::Error) -> Self {
+        Self::Transport(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_content_is_told_apart_from_forbidden() {
+        let body = br#"{"detail":"You are not allowed to create a Tweet with duplicate content.","type":"about:blank","title":"Forbidden","status":403}"#;
+        let err = TwitterError::from_status(403, body);
+
+        assert_eq!(err.kind(), ErrorKind::DuplicateContent);
+        assert_eq!(
+            err.to_string(),
+            "error[duplicate_content]: Forbidden (403): You are not allowed to create a Tweet with duplicate content."
+        );
+
+        let forbidden = TwitterError::from_status(403, br#"{"title":"Forbidden","status":403}"#);
+        assert_eq!(forbidden.kind(), ErrorKind::Forbidden);
+
+        assert!(!err.is_transient());
+        assert!(TwitterError::from_status(503, b"").is_transient());
+    }
+
+    #[test]
+    fn test_problem_errors_are_listed() {
+        let body = br#"{"errors":[{"parameters":{"ids":["x"]},"message":"The `ids` query parameter value [x] is not valid"}],"title":"Invalid Request","detail":"One or more parameters to your request was invalid.","type":"https://api.twitter.com/2/problems/invalid-request"}"#;
+        let err = TwitterError::from_status(400, body);
+
+        assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+        assert_eq!(
+            err.to_string(),
+            "error[invalid_request]: Invalid Request (400): One or more parameters to your request was invalid.\n  - The `ids` query parameter value [x] is not valid"
+        );
+    }
+
+    #[test]
+    fn test_unparsed_body_is_kept() {
+        let err = TwitterError::from_status(429, b"Too Many Requests");
+
+        assert!(err.is_rate_limited());
+        assert!(err.problem().is_none());
+        assert_eq!(
+            err.to_string(),
+            "error[rate_limited]: HTTP 429: Too Many Requests"
+        );
+    }
+}
@@ -1,22 +1,26 @@
 use crate::{
-    twitter::{Response, UserData},
+    twitter::{
+        Response, UserData,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::{get_current_user_id, oauth_get_header, oauth_post_header},
 };
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Display;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct FollowingMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct FollowingResponse {
     #[serde(default)]
     pub data: Vec<UserData>,
@@ -24,22 +28,16 @@ pub struct FollowingResponse {
     pub meta: Option<FollowingMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct FollowingError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct FollowersMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct FollowersResponse {
     #[serde(default)]
     pub data: Vec<UserData>,
@@ -47,11 +45,6 @@ pub struct FollowersResponse {
     pub meta: Option<FollowersMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct FollowersError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct Following {
     user_id: String,
@@ -64,43 +57,33 @@ pub struct Followers {
     max_results: u8,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateFollowData {
     pub following: bool,
     pub pending_follow: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateFollowResponse {
     pub data: CreateFollowData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateFollowError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct CreateFollow {
     user_id: String,
     target_user_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteFollowData {
     pub following: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteFollowResponse {
     pub data: DeleteFollowData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteFollowError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct DeleteFollow {
     source_user_id: String,
@@ -144,44 +127,35 @@ impl Following {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/following", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/following", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<FollowingResponse>, FollowingError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<FollowingResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let user_fields = "name,username".to_string();
-        let auth_params = oauth::ParameterList::new([
+        let mut auth_params = vec![
             ("max_results", &max_results as &dyn Display),
             ("user.fields", &user_fields as &dyn Display),
-        ]);
-        let auth_header = oauth_get_header(url.as_str(), &auth_params);
+        ];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("user.fields", user_fields.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| FollowingError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let user_data: FollowingResponse =
-                serde_json::from_slice(&response.body).map_err(|err| FollowingError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: user_data,
-            })
-        } else {
-            Err(FollowingError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -217,142 +191,102 @@ impl Followers {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/followers", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/followers", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<FollowersResponse>, FollowersError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<FollowersResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let user_fields = "name,username".to_string();
-        let auth_params = oauth::ParameterList::new([
+        let mut auth_params = vec![
             ("max_results", &max_results as &dyn Display),
             ("user.fields", &user_fields as &dyn Display),
-        ]);
-        let auth_header = oauth_get_header(url.as_str(), &auth_params);
+        ];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("user.fields", user_fields.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| FollowersError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let user_data: FollowersResponse =
-                serde_json::from_slice(&response.body).map_err(|err| FollowersError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: user_data,
-            })
-        } else {
-            Err(FollowersError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl CreateFollow {
-    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, CreateFollowError> {
-        let user_id = get_current_user_id().map_err(|message| CreateFollowError { message })?;
+    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             target_user_id: target_user_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/following", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/following", self.user_id))
     }
 
-    pub fn send(&self) -> Result<Response<CreateFollowResponse>, CreateFollowError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<CreateFollowResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateFollowBody {
             target_user_id: self.target_user_id.as_str(),
         })
-        .map_err(|err| CreateFollowError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .idempotent()
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| CreateFollowError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let follow_data: CreateFollowResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| CreateFollowError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: follow_data,
-            })
-        } else {
-            Err(CreateFollowError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl DeleteFollow {
-    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, DeleteFollowError> {
-        let source_user_id =
-            get_current_user_id().map_err(|message| DeleteFollowError { message })?;
+    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let source_user_id = get_current_user_id()?;
         Ok(Self {
             source_user_id,
             target_user_id: target_user_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/following/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/users/{}/following/{}",
             self.source_user_id, self.target_user_id
-        )
+        ))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteFollowResponse>, DeleteFollowError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteFollowResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| DeleteFollowError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let follow_data: DeleteFollowResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| DeleteFollowError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: follow_data,
-            })
-        } else {
-            Err(DeleteFollowError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
+impl_page!(FollowingResponse);
+impl_page!(FollowersResponse);
+impl_paginated!(Following => FollowingResponse);
+impl_paginated!(Followers => FollowersResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,14 +295,20 @@ mod tests {
     fn test_following_url_uses_user_id() {
         let endpoint = Following::new("123");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/following");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/following"
+        );
     }
 
     #[test]
     fn test_followers_url_uses_user_id() {
         let endpoint = Followers::new("123");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/followers");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/followers"
+        );
     }
 
     #[test]
@@ -378,7 +318,10 @@ mod tests {
             target_user_id: "456".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/following");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/following"
+        );
     }
 
     #[test]
@@ -389,7 +332,7 @@ mod tests {
         };
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/users/123/following/456"
         );
     }
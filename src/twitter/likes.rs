@@ -1,22 +1,26 @@
 use std::fmt::Display;
 
 use crate::{
-    twitter::{AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS},
+    twitter::{
+        AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::{get_current_user_id, oauth_get_header, oauth_post_header},
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct LikesMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct LikesResponse {
     pub data: Vec<TweetData>,
     #[serde(default)]
@@ -25,59 +29,43 @@ pub struct LikesResponse {
     pub meta: Option<LikesMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct LikesError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateLikeData {
     pub liked: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateLikeResponse {
     pub data: CreateLikeData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateLikeError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteLikeData {
     pub liked: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteLikeResponse {
     pub data: DeleteLikeData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteLikeError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct LikingUsersMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LikingUser {
     pub id: String,
     pub name: String,
     pub username: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct LikingUsersResponse {
     #[serde(default)]
     pub data: Vec<LikingUser>,
@@ -85,11 +73,6 @@ pub struct LikingUsersResponse {
     pub meta: Option<LikingUsersMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct LikingUsersError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct Likes {
     user_id: String,
@@ -132,50 +115,42 @@ impl Likes {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/liked_tweets", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/liked_tweets", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<LikesResponse>, LikesError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<LikesResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results;
         let tweet_fields = TWEET_FIELDS.to_string();
         let user_fields = USER_FIELDS.to_string();
         let expansions = AUTHOR_EXPANSION.to_string();
-        let auth_params = oauth::ParameterList::new([
+        let mut auth_params = vec![
             ("max_results", &max_results as &dyn Display),
             ("tweet.fields", &tweet_fields as &dyn Display),
             ("user.fields", &user_fields as &dyn Display),
             ("expansions", &expansions as &dyn Display),
-        ]);
-        let auth_header = oauth_get_header(url.as_str(), &auth_params);
+        ];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
         let max_results_query = max_results.to_string();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results_query.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("tweet.fields", tweet_fields.as_str())
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| LikesError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let likes_data: LikesResponse =
-                serde_json::from_slice(&response.body).map_err(|err| LikesError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: likes_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(LikesError { message: err_data })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -192,130 +167,94 @@ impl LikingUsers {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/tweets/{}/liking_users", self.tweet_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/tweets/{}/liking_users", self.tweet_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<LikingUsersResponse>, LikingUsersError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<LikingUsersResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
-        let auth_header = oauth_get_header(url.as_str(), &());
+        let user_fields = "name,username";
+        let mut auth_params = vec![
+            ("max_results", &max_results as &dyn Display),
+            ("user.fields", &user_fields as &dyn Display),
+        ];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
-            .query_param_kv("user.fields", "name,username")
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| LikingUsersError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: LikingUsersResponse =
-                serde_json::from_slice(&response.body).map_err(|err| LikingUsersError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(LikingUsersError { message: err_data })
-        }
+            .query_param_opt("pagination_token", pagination_token)
+            .query_param_kv("user.fields", user_fields)
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl CreateLike {
-    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, CreateLikeError> {
-        let user_id = get_current_user_id().map_err(|message| CreateLikeError { message })?;
+    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             tweet_id: tweet_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/likes", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/likes", self.user_id))
     }
 
-    pub fn send(&self) -> Result<Response<CreateLikeResponse>, CreateLikeError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<CreateLikeResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateLikeBody {
             tweet_id: self.tweet_id.as_str(),
         })
-        .map_err(|err| CreateLikeError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .idempotent()
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| CreateLikeError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: CreateLikeResponse =
-                serde_json::from_slice(&response.body).map_err(|err| CreateLikeError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(CreateLikeError { message: err_data })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl DeleteLike {
-    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, DeleteLikeError> {
-        let user_id = get_current_user_id().map_err(|message| DeleteLikeError { message })?;
+    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             tweet_id: tweet_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/likes/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/users/{}/likes/{}",
             self.user_id, self.tweet_id
-        )
+        ))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteLikeResponse>, DeleteLikeError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteLikeResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| DeleteLikeError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: DeleteLikeResponse =
-                serde_json::from_slice(&response.body).map_err(|err| DeleteLikeError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(DeleteLikeError { message: err_data })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -338,6 +277,11 @@ impl std::fmt::Display for LikingUsersResponse {
     }
 }
 
+impl_page!(LikesResponse, includes);
+impl_page!(LikingUsersResponse);
+impl_paginated!(Likes => LikesResponse);
+impl_paginated!(LikingUsers => LikingUsersResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -349,7 +293,10 @@ mod tests {
             tweet_id: "456".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/likes");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/likes"
+        );
     }
 
     #[test]
@@ -359,7 +306,10 @@ mod tests {
             tweet_id: "456".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/likes/456");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/likes/456"
+        );
     }
 
     #[test]
@@ -370,7 +320,7 @@ mod tests {
         };
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/tweets/456/liking_users"
         );
     }
@@ -3,7 +3,12 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    twitter::{AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS},
+    twitter::{
+        AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::{bearer_auth_header, get_current_user_id, oauth_post_header, oauth_put_header},
 };
 
@@ -11,20 +16,20 @@ const LIST_FIELDS: &str = "id,name,owner_id,private,description,follower_count,m
 const LIST_EXPANSIONS: &str = "owner_id";
 const OWNER_USER_FIELDS: &str = "name,username";
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ListUser {
     pub id: String,
     pub name: String,
     pub username: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ListIncludes {
     #[serde(default)]
     pub users: Option<Vec<ListUser>>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ListData {
     pub id: String,
     pub name: String,
@@ -40,17 +45,16 @@ pub struct ListData {
     pub member_count: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ListMembershipsMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ListMembershipsResponse {
     #[serde(default)]
     pub data: Vec<ListData>,
@@ -60,44 +64,28 @@ pub struct ListMembershipsResponse {
     pub meta: Option<ListMembershipsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ListMembershipsError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ListLookupResponse {
     pub data: ListData,
     #[serde(default)]
     pub includes: Option<ListIncludes>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ListLookupError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateListResponse {
     pub data: ListData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateListError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct OwnedListsMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct OwnedListsResponse {
     #[serde(default)]
     pub data: Vec<ListData>,
@@ -107,22 +95,16 @@ pub struct OwnedListsResponse {
     pub meta: Option<OwnedListsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct OwnedListsError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ListTweetsMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ListTweetsResponse {
     #[serde(default)]
     pub data: Vec<TweetData>,
@@ -132,22 +114,16 @@ pub struct ListTweetsResponse {
     pub meta: Option<ListTweetsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ListTweetsError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ListMembersMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ListMembersResponse {
     #[serde(default)]
     pub data: Vec<ListUser>,
@@ -155,11 +131,6 @@ pub struct ListMembersResponse {
     pub meta: Option<ListMembersMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ListMembersError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct ListMemberships {
     user_id: String,
@@ -196,71 +167,51 @@ pub struct ListTweets {
     max_results: u8,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateListMemberData {
     pub is_member: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateListMemberResponse {
     pub data: CreateListMemberData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateListMemberError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct CreateListMember {
     list_id: String,
     user_id: String,
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteListMemberData {
     pub is_member: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteListData {
     pub deleted: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteListResponse {
     pub data: DeleteListData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteListError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteListMemberResponse {
     pub data: DeleteListMemberData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteListMemberError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UpdateListData {
     pub updated: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UpdateListResponse {
     pub data: UpdateListData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct UpdateListError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct DeleteListMember {
     list_id: String,
@@ -304,8 +255,8 @@ struct CreateListMemberBody<'a> {
     user_id: &'a str,
 }
 impl ListMemberships {
-    pub fn current_user() -> Result<Self, ListMembershipsError> {
-        let user_id = get_current_user_id().map_err(|message| ListMembershipsError { message })?;
+    pub fn current_user() -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             max_results: 10,
@@ -317,50 +268,35 @@ impl ListMemberships {
         self
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/list_memberships",
-            self.user_id
-        )
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/list_memberships", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<ListMembershipsResponse>, ListMembershipsError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<ListMembershipsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("list.fields", LIST_FIELDS)
             .query_param_kv("expansions", LIST_EXPANSIONS)
             .query_param_kv("user.fields", OWNER_USER_FIELDS)
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url.as_str())
-            .map_err(|err| ListMembershipsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let lists_data: ListMembershipsResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| ListMembershipsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: lists_data,
-            })
-        } else {
-            Err(ListMembershipsError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl OwnedLists {
-    pub fn current_user() -> Result<Self, OwnedListsError> {
-        let user_id = get_current_user_id().map_err(|message| OwnedListsError { message })?;
+    pub fn current_user() -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             max_results: 10,
@@ -372,41 +308,29 @@ impl OwnedLists {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/owned_lists", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/owned_lists", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<OwnedListsResponse>, OwnedListsError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<OwnedListsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("list.fields", LIST_FIELDS)
             .query_param_kv("expansions", LIST_EXPANSIONS)
             .query_param_kv("user.fields", OWNER_USER_FIELDS)
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url.as_str())
-            .map_err(|err| OwnedListsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let lists_data: OwnedListsResponse =
-                serde_json::from_slice(&response.body).map_err(|err| OwnedListsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: lists_data,
-            })
-        } else {
-            Err(OwnedListsError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -417,39 +341,23 @@ impl ListLookup {
         }
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/lists/{}", self.list_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/lists/{}", self.list_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<ListLookupResponse>, ListLookupError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<ListLookupResponse>, TwitterError> {
+        let url = self.url(api());
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("list.fields", LIST_FIELDS)
             .query_param_kv("expansions", LIST_EXPANSIONS)
             .query_param_kv("user.fields", OWNER_USER_FIELDS)
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url.as_str())
-            .map_err(|err| ListLookupError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let list_data: ListLookupResponse =
-                serde_json::from_slice(&response.body).map_err(|err| ListLookupError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: list_data,
-            })
-        } else {
-            Err(ListLookupError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -472,45 +380,26 @@ impl CreateList {
         self
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/lists"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/lists")
     }
 
-    pub fn send(&self) -> Result<Response<CreateListResponse>, CreateListError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url, &());
+    pub fn send(&self) -> Result<Response<CreateListResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateListBody {
             name: self.name.as_str(),
             description: self.description.as_deref(),
             private: self.private,
         })
-        .map_err(|err| CreateListError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url)
-            .map_err(|err| CreateListError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: CreateListResponse =
-                serde_json::from_slice(&response.body).map_err(|err| CreateListError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(CreateListError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -522,43 +411,25 @@ impl CreateListMember {
         }
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/lists/{}/members", self.list_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/lists/{}/members", self.list_id))
     }
 
-    pub fn send(&self) -> Result<Response<CreateListMemberResponse>, CreateListMemberError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<CreateListMemberResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateListMemberBody {
             user_id: self.user_id.as_str(),
         })
-        .map_err(|err| CreateListMemberError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .idempotent()
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| CreateListMemberError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: CreateListMemberResponse =
-                serde_json::from_slice(&response.body).map_err(|err| CreateListMemberError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(CreateListMemberError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -575,39 +446,27 @@ impl ListMembers {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/lists/{}/members", self.list_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/lists/{}/members", self.list_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<ListMembersResponse>, ListMembersError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<ListMembersResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("user.fields", OWNER_USER_FIELDS)
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url.as_str())
-            .map_err(|err| ListMembersError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let members_data: ListMembersResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| ListMembersError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: members_data,
-            })
-        } else {
-            Err(ListMembersError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -624,41 +483,29 @@ impl ListTweets {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/lists/{}/tweets", self.list_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/lists/{}/tweets", self.list_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<ListTweetsResponse>, ListTweetsError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<ListTweetsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("tweet.fields", TWEET_FIELDS)
             .query_param_kv("user.fields", USER_FIELDS)
             .query_param_kv("expansions", AUTHOR_EXPANSION)
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url.as_str())
-            .map_err(|err| ListTweetsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweets_data: ListTweetsResponse =
-                serde_json::from_slice(&response.body).map_err(|err| ListTweetsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweets_data,
-            })
-        } else {
-            Err(ListTweetsError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -670,44 +517,27 @@ impl DeleteListMember {
         }
     }
 
-    pub fn for_current_user(list_id: impl Into<String>) -> Result<Self, DeleteListMemberError> {
-        let user_id = get_current_user_id().map_err(|message| DeleteListMemberError { message })?;
+    pub fn for_current_user(list_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self::new(list_id, user_id))
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/lists/{}/members/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/lists/{}/members/{}",
             self.list_id, self.user_id
-        )
+        ))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteListMemberResponse>, DeleteListMemberError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteListMemberResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| DeleteListMemberError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: DeleteListMemberResponse =
-                serde_json::from_slice(&response.body).map_err(|err| DeleteListMemberError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(DeleteListMemberError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -740,45 +570,26 @@ impl UpdateList {
         self.name.is_some() || self.description.is_some() || self.private.is_some()
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/lists/{}", self.list_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/lists/{}", self.list_id))
     }
 
-    pub fn send(&self) -> Result<Response<UpdateListResponse>, UpdateListError> {
-        let url = self.url();
-        let auth_header = oauth_put_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<UpdateListResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&UpdateListBody {
             name: self.name.as_deref(),
             description: self.description.as_deref(),
             private: self.private,
         })
-        .map_err(|err| UpdateListError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .put()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .authorization(|| oauth_put_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| UpdateListError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: UpdateListResponse =
-                serde_json::from_slice(&response.body).map_err(|err| UpdateListError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(UpdateListError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -789,36 +600,19 @@ impl DeleteList {
         }
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/lists/{}", self.list_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/lists/{}", self.list_id))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteListResponse>, DeleteListError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteListResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| DeleteListError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: DeleteListResponse =
-                serde_json::from_slice(&response.body).map_err(|err| DeleteListError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(DeleteListError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -925,6 +719,15 @@ impl Display for ListMembersResponse {
     }
 }
 
+impl_page!(ListMembershipsResponse, includes);
+impl_page!(OwnedListsResponse, includes);
+impl_page!(ListTweetsResponse, includes);
+impl_page!(ListMembersResponse);
+impl_paginated!(ListMemberships => ListMembershipsResponse);
+impl_paginated!(OwnedLists => OwnedListsResponse);
+impl_paginated!(ListTweets => ListTweetsResponse);
+impl_paginated!(ListMembers => ListMembersResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -936,28 +739,40 @@ mod tests {
             user_id: "456".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/lists/123/members/456");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/lists/123/members/456"
+        );
     }
 
     #[test]
     fn test_create_list_url_is_lists_collection() {
         let endpoint = CreateList::new("cli-builders");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/lists");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/lists"
+        );
     }
 
     #[test]
     fn test_update_list_url_uses_list_id() {
         let endpoint = UpdateList::new("123");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/lists/123");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/lists/123"
+        );
     }
 
     #[test]
     fn test_list_lookup_url_uses_list_id() {
         let endpoint = ListLookup::new("123");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/lists/123");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/lists/123"
+        );
     }
 
     #[test]
@@ -987,7 +802,10 @@ mod tests {
             max_results: 10,
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/owned_lists");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/owned_lists"
+        );
     }
 
     #[test]
@@ -996,7 +814,10 @@ mod tests {
             list_id: "123".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/lists/123");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/lists/123"
+        );
     }
 
     #[test]
@@ -1057,7 +878,10 @@ mod tests {
     fn test_create_list_member_url_uses_list_id() {
         let endpoint = CreateListMember::new("123", "456");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/lists/123/members");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/lists/123/members"
+        );
     }
 
     #[test]
@@ -1067,6 +891,9 @@ mod tests {
             max_results: 10,
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/lists/123/tweets");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/lists/123/tweets"
+        );
     }
 }
@@ -0,0 +1,267 @@
+use clap::ValueEnum;
+use pulldown_cmark::{Event, LinkType, Parser, Tag, TagEnd};
+
+/// How bold and italic text looks once its Markdown markers are gone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Emphasis {
+    /// Plain text.
+    #[default]
+    Strip,
+    /// Unicode sans-serif bold and italic letters. Screen readers may spell them out.
+    Unicode,
+}
+
+/// Renders Markdown as tweet text, returning one section per tweet. Headings and horizontal
+/// rules start a new section, list items become `•` lines, links become `text (url)` and
+/// images become `![alt](path)` lines that attach them to their tweet. Code is kept as written.
+pub fn render(markdown: &str, emphasis: Emphasis) -> Vec<String> {
+    let mut renderer = Renderer {
+        emphasis,
+        sections: vec![],
+        current: String::new(),
+        lists: vec![],
+        item_start: false,
+        strong: 0,
+        italic: 0,
+        links: vec![],
+    };
+    for event in Parser::new(markdown) {
+        renderer.event(event);
+    }
+    renderer.break_section();
+    renderer.sections
+}
+
+struct Renderer {
+    emphasis: Emphasis,
+    sections: Vec<String>,
+    current: String,
+    /// Open lists, innermost last, with the next number of ordered ones.
+    lists: Vec<Option<u64>>,
+    /// Set right after a list marker, so the item's first paragraph stays on its line.
+    item_start: bool,
+    strong: usize,
+    italic: usize,
+    /// Open links and images: where their text starts in `current`, and their destination.
+    links: Vec<(usize, String, bool)>,
+}
+
+impl Renderer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => self.push(&format!("`{code}`")),
+            Event::SoftBreak => self.push(" "),
+            Event::HardBreak => self.push("\n"),
+            Event::Rule => self.break_section(),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { .. } => {
+                self.break_section();
+                self.strong += 1;
+            }
+            Tag::Paragraph | Tag::CodeBlock(_) | Tag::BlockQuote(_) => self.block(),
+            Tag::List(first_number) => {
+                if self.lists.is_empty() {
+                    self.block();
+                }
+                self.lists.push(first_number);
+            }
+            Tag::Item => {
+                self.new_line();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.current.push_str(&"  ".repeat(depth));
+                self.current.push_str(&marker);
+                self.item_start = true;
+            }
+            Tag::Strong => self.strong += 1,
+            Tag::Emphasis => self.italic += 1,
+            Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            } => {
+                let bare = matches!(link_type, LinkType::Autolink | LinkType::Email);
+                self.links
+                    .push((self.current.len(), dest_url.to_string(), bare));
+            }
+            Tag::Image { dest_url, .. } => {
+                self.new_line();
+                self.links
+                    .push((self.current.len(), dest_url.to_string(), false));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => self.strong -= 1,
+            TagEnd::List(_) => {
+                self.lists.pop();
+            }
+            TagEnd::CodeBlock => self.current.truncate(self.current.trim_end().len()),
+            TagEnd::Strong => self.strong -= 1,
+            TagEnd::Emphasis => self.italic -= 1,
+            TagEnd::Link => {
+                let Some((start, url, bare)) = self.links.pop() else {
+                    return;
+                };
+                let text = &self.current[start..];
+                if bare || text.is_empty() || same_url(text, &url) {
+                    self.current.truncate(start);
+                    self.current.push_str(&url);
+                } else {
+                    self.current.push_str(&format!(" ({url})"));
+                }
+            }
+            TagEnd::Image => {
+                let Some((start, path, _)) = self.links.pop() else {
+                    return;
+                };
+                let alt: String = self.current.drain(start..).collect();
+                self.current
+                    .push_str(&format!("![{}]({path})\n", alt.trim()));
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let styled = match (self.emphasis, self.strong > 0, self.italic > 0) {
+            (Emphasis::Unicode, true, true) => text.chars().map(bold_italic).collect(),
+            (Emphasis::Unicode, true, false) => text.chars().map(bold).collect(),
+            (Emphasis::Unicode, false, true) => text.chars().map(italic).collect(),
+            _ => text.to_string(),
+        };
+        self.push(&styled);
+    }
+
+    fn push(&mut self, text: &str) {
+        self.item_start = false;
+        self.current.push_str(text);
+    }
+
+    /// Leaves a blank line before the next block, or a line break inside a list.
+    fn block(&mut self) {
+        if self.item_start || self.current.trim().is_empty() {
+            return;
+        }
+        if self.lists.is_empty() {
+            self.current.truncate(self.current.trim_end().len());
+            self.current.push_str("\n\n");
+        } else {
+            self.new_line();
+        }
+    }
+
+    fn new_line(&mut self) {
+        if !self.current.is_empty() && !self.current.ends_with('\n') {
+            self.current.push('\n');
+        }
+    }
+
+    /// Ends the current tweet.
+    fn break_section(&mut self) {
+        let section = self.current.trim();
+        if !section.is_empty() {
+            self.sections.push(section.to_string());
+        }
+        self.current.clear();
+    }
+}
+
+/// Whether a link's text is just its url, with or without the scheme.
+fn same_url(text: &str, url: &str) -> bool {
+    let strip = |value: &str| {
+        value
+            .trim_start_matches("mailto:")
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .to_string()
+    };
+    strip(text) == strip(url)
+}
+
+/// Maps ASCII letters (and digits, for bold) into a Mathematical Alphanumeric Symbols block.
+fn shift(c: char, upper: u32, lower: u32, digit: Option<u32>) -> char {
+    let base = match c {
+        'A'..='Z' => upper + (c as u32 - 'A' as u32),
+        'a'..='z' => lower + (c as u32 - 'a' as u32),
+        '0'..='9' => match digit {
+            Some(digit) => digit + (c as u32 - '0' as u32),
+            None => return c,
+        },
+        _ => return c,
+    };
+    char::from_u32(base).unwrap_or(c)
+}
+
+fn bold(c: char) -> char {
+    shift(c, 0x1D5D4, 0x1D5EE, Some(0x1D7EC))
+}
+
+fn italic(c: char) -> char {
+    shift(c, 0x1D608, 0x1D622, None)
+}
+
+fn bold_italic(c: char) -> char {
+    shift(c, 0x1D63C, 0x1D656, Some(0x1D7EC))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headings_start_new_tweets() {
+        let markdown = "Intro paragraph\nwrapped here.\n\n# Part two\n\nBody *with* **emphasis**.\n\n---\n\nLast";
+
+        assert_eq!(
+            render(markdown, Emphasis::Strip),
+            vec![
+                "Intro paragraph wrapped here.",
+                "Part two\n\nBody with emphasis.",
+                "Last"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lists_links_images_and_code() {
+        let markdown = "Changes:\n\n- Faster `split`\n- Docs at [the site](https://example.com)\n  1. nested\n- See <https://x.com>\n\n![A chart](chart.png)\n";
+
+        assert_eq!(
+            render(markdown, Emphasis::Strip),
+            vec![
+                "Changes:\n\n• Faster `split`\n• Docs at the site (https://example.com)\n  1. nested\n• See https://x.com\n\n![A chart](chart.png)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unicode_emphasis() {
+        assert_eq!(
+            render("**Bold 1** and *it*", Emphasis::Unicode),
+            vec!["𝗕𝗼𝗹𝗱 𝟭 and 𝘪𝘵"]
+        );
+        assert_eq!(
+            render("[example.com](https://example.com/)", Emphasis::Strip),
+            vec!["https://example.com/"]
+        );
+    }
+}
@@ -1,11 +1,30 @@
 use std::{
-    io::Read,
+    fs::File,
+    io::{self, Read},
     path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
 };
 
-use serde::Deserialize;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
 
-use crate::utils::oauth_post_header;
+use crate::{
+    twitter::{
+        Response,
+        client::api,
+        error::TwitterError,
+        preflight::{self, MediaOptions, PreparedMedia},
+        retry::random_u64,
+    },
+    utils::{oauth_get_header, oauth_post_header},
+};
+
+/// Size of every APPEND segment. The API accepts at most 5 MB per segment.
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// How long to wait for the API to process a video before giving up.
+const MAX_PROCESSING_WAIT: Duration = Duration::from_secs(10 * 60);
 
 #[derive(Debug, Deserialize)]
 struct MediaUploadResponse {
@@ -15,103 +34,602 @@ struct MediaUploadResponse {
 #[derive(Debug, Deserialize)]
 struct Data {
     id: String,
+    #[serde(default)]
+    processing_info: Option<ProcessingInfo>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct UploadMediaError {
-    pub message: String,
+/// Server-side processing of a video or GIF, reported by FINALIZE and STATUS.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct ProcessingInfo {
+    state: ProcessingState,
+    #[serde(default)]
+    check_after_secs: Option<u64>,
+    #[serde(default)]
+    progress_percent: Option<u8>,
+    #[serde(default)]
+    error: Option<ProcessingError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ProcessingState {
+    Pending,
+    InProgress,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct ProcessingError {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// The `media_category` an upload is made for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaCategory {
+    TweetImage,
+    TweetGif,
+    TweetVideo,
+}
+
+impl MediaCategory {
+    pub fn from_media_type(media_type: &str) -> Result<Self, TwitterError> {
+        match media_type {
+            "image/gif" => Ok(MediaCategory::TweetGif),
+            media_type if media_type.starts_with("video/") => Ok(MediaCategory::TweetVideo),
+            media_type if media_type.starts_with("image/") => Ok(MediaCategory::TweetImage),
+            media_type => Err(TwitterError::local(format!(
+                "Unsupported media type: {media_type}"
+            ))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaCategory::TweetImage => "tweet_image",
+            MediaCategory::TweetGif => "tweet_gif",
+            MediaCategory::TweetVideo => "tweet_video",
+        }
+    }
+
+    /// Videos and GIFs go through the chunked INIT/APPEND/FINALIZE upload.
+    pub fn is_chunked(&self) -> bool {
+        !matches!(self, MediaCategory::TweetImage)
+    }
 }
 
-pub fn upload(path: PathBuf) -> Result<String, UploadMediaError> {
-    let upload_url = "https://api.x.com/2/media/upload";
-    println!("> Uploading image to Twitter.");
+#[derive(Serialize)]
+struct MetadataBody<'a> {
+    id: &'a str,
+    metadata: Metadata<'a>,
+}
+
+#[derive(Serialize)]
+struct Metadata<'a> {
+    alt_text: AltText<'a>,
+}
+
+#[derive(Serialize)]
+struct AltText<'a> {
+    text: &'a str,
+}
+
+/// The most images a single tweet can carry.
+pub const MAX_IMAGES: usize = 4;
+
+/// The longest alt text the API accepts, in characters.
+pub const MAX_ALT_TEXT_CHARS: usize = 1000;
+
+/// An image to attach, with the alt text read by screen readers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Image {
+    pub path: PathBuf,
+    pub alt: Option<String>,
+}
+
+/// Uploads `images` in order and sets their alt text. Returns the media ids in the same order.
+/// Every file is checked before the first upload, so a bad file costs no API call.
+pub fn upload_images(
+    images: Vec<Image>,
+    options: &MediaOptions,
+) -> Result<Vec<String>, TwitterError> {
+    let prepared = prepare_images(&images, options)?;
+    upload_prepared_images(images, prepared)
+}
+
+/// Uploads the images of every tweet in a thread, returning their media ids tweet by tweet.
+/// Every file of every tweet is checked before the first upload.
+pub fn upload_thread_images(
+    tweets: Vec<Vec<Image>>,
+    options: &MediaOptions,
+) -> Result<Vec<Vec<String>>, TwitterError> {
+    let prepared = tweets
+        .iter()
+        .map(|images| prepare_images(images, options))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    tweets
+        .into_iter()
+        .zip(prepared)
+        .map(|(images, prepared)| upload_prepared_images(images, prepared))
+        .collect()
+}
+
+/// Checks the images of every tweet in a thread without uploading anything, for tweets
+/// that are posted later.
+pub fn check_thread_images(
+    tweets: &[Vec<Image>],
+    options: &MediaOptions,
+) -> Result<(), TwitterError> {
+    tweets
+        .iter()
+        .try_for_each(|images| prepare_images(images, options).map(|_| ()))
+}
+
+/// Checks the images of one tweet and prepares every file for upload.
+fn prepare_images(
+    images: &[Image],
+    options: &MediaOptions,
+) -> Result<Vec<PreparedMedia>, TwitterError> {
+    if images.len() > MAX_IMAGES {
+        return Err(TwitterError::local(format!(
+            "A tweet can carry at most {MAX_IMAGES} images, got {}.",
+            images.len()
+        )));
+    }
+
+    if let Some(image) = images.iter().find(|image| {
+        image
+            .alt
+            .as_ref()
+            .is_some_and(|alt| alt.chars().count() > MAX_ALT_TEXT_CHARS)
+    }) {
+        return Err(TwitterError::local(format!(
+            "The alt text for {:?} is longer than {MAX_ALT_TEXT_CHARS} characters.",
+            image.path
+        )));
+    }
+
+    let prepared = images
+        .iter()
+        .map(|image| preflight::prepare(&image.path, options))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if prepared.len() > 1
+        && let Some(media) = prepared.iter().find(|media| media.category.is_chunked())
+    {
+        return Err(TwitterError::local(format!(
+            "{:?} is a video or GIF, which has to be the only media in a tweet.",
+            media.path
+        )));
+    }
 
-    let auth_header = oauth_post_header(upload_url, &());
-    let file_kind = infer::get_from_path(&path);
+    Ok(prepared)
+}
 
-    let media_type = match file_kind {
-        Ok(kind) => kind.unwrap().mime_type().to_string(),
-        Err(_) => {
-            return Err(UploadMediaError {
-                message: "Could not get the file type.".to_string(),
-            });
+fn upload_prepared_images(
+    images: Vec<Image>,
+    prepared: Vec<PreparedMedia>,
+) -> Result<Vec<String>, TwitterError> {
+    let mut media_ids = Vec::with_capacity(images.len());
+    for (index, (image, media)) in images.into_iter().zip(prepared).enumerate() {
+        let media_id = match upload_prepared(media) {
+            // Nothing was uploaded, so the tweet printed next refers to a placeholder.
+            Err(err) if err.is_dry_run() => format!("<media {}>", index + 1),
+            media_id => media_id?,
+        };
+        if let Some(alt) = image.alt.as_deref() {
+            set_alt_text(&media_id, alt).or_else(ignore_dry_run)?;
         }
+        media_ids.push(media_id);
+    }
+
+    Ok(media_ids)
+}
+
+fn ignore_dry_run(err: TwitterError) -> Result<(), TwitterError> {
+    if err.is_dry_run() { Ok(()) } else { Err(err) }
+}
+
+/// Sets the alt text of an uploaded image through the media metadata endpoint.
+pub fn set_alt_text(media_id: &str, alt_text: &str) -> Result<(), TwitterError> {
+    let url = api().url("/2/media/metadata");
+    let body = serde_json::to_string(&MetadataBody {
+        id: media_id,
+        metadata: Metadata {
+            alt_text: AltText { text: alt_text },
+        },
+    })
+    .map_err(TwitterError::local)?;
+
+    api()
+        .post()
+        .idempotent()
+        .authorization(|| oauth_post_header(url.as_str(), &()))
+        .body_json(body)
+        .send(url.as_str())?;
+
+    Ok(())
+}
+
+pub fn upload(path: PathBuf, options: &MediaOptions) -> Result<String, TwitterError> {
+    upload_prepared(preflight::prepare(&path, options)?)
+}
+
+fn upload_prepared(media: PreparedMedia) -> Result<String, TwitterError> {
+    let PreparedMedia {
+        path,
+        media_type,
+        category,
+        bytes,
+    } = media;
+    let Some(bytes) = bytes else {
+        return upload_chunked(&path, &media_type, category);
     };
 
+    let upload_url = api().url("/2/media/upload");
+
+    eprintln!("> Uploading {} to Twitter.", path.display());
+
     let file_name = path
         .file_name()
         .and_then(|name| name.to_str())
         .unwrap_or("image.bin")
         .to_string();
 
-    let boundary = format!("----twitter-cli-{}", std::process::id());
-    let body = build_multipart_body(&boundary, &file_name, &media_type, &path)?;
-    let content_type = format!("multipart/form-data; boundary={boundary}");
+    let form = build_multipart_body(&file_name, &media_type, &bytes);
+    let response = post_form(&upload_url, form, false)?;
+    let media_upload_res: Response<MediaUploadResponse> = Response::decode(response)?;
+
+    eprintln!("> Image uploaded to Twitter.");
+
+    Ok(media_upload_res.content.data.id)
+}
+
+/// Uploads a video or GIF in segments: INIT, one APPEND per chunk, FINALIZE, then STATUS
+/// until the API has processed it. The file is read from disk one chunk at a time.
+fn upload_chunked(
+    path: &Path,
+    media_type: &str,
+    category: MediaCategory,
+) -> Result<String, TwitterError> {
+    let upload_url = api().url("/2/media/upload");
+    let mut file = File::open(path).map_err(TwitterError::local)?;
+    let total_bytes = file.metadata().map_err(TwitterError::local)?.len();
+
+    eprintln!("> Uploading {} to Twitter.", path.display());
+
+    let init = Multipart::new()
+        .text("command", "INIT")
+        .text("media_type", media_type)
+        .text("total_bytes", &total_bytes.to_string())
+        .text("media_category", category.as_str());
+    let init: Response<MediaUploadResponse> =
+        Response::decode(post_form(&upload_url, init, false)?)?;
+    let media_id = init.content.data.id;
+
+    let progress = ProgressBar::new(total_bytes);
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes}",
+            )
+            .unwrap()
+            .progress_chars("##-"),
+    );
+
+    let mut chunk = vec![0; CHUNK_SIZE];
+    let mut segment_index = 0;
+    loop {
+        let read = read_chunk(&mut file, &mut chunk).inspect_err(|_| progress.abandon())?;
+        if read == 0 {
+            break;
+        }
+
+        let append = Multipart::new()
+            .text("command", "APPEND")
+            .text("media_id", &media_id)
+            .text("segment_index", &segment_index.to_string())
+            .file("media", "blob", "application/octet-stream", &chunk[..read]);
+        post_form(&upload_url, append, true).inspect_err(|_| progress.abandon())?;
+
+        progress.inc(read as u64);
+        segment_index += 1;
+    }
+    progress.finish_and_clear();
+
+    let finalize = Multipart::new()
+        .text("command", "FINALIZE")
+        .text("media_id", &media_id);
+    let finalize: Response<MediaUploadResponse> =
+        Response::decode(post_form(&upload_url, finalize, false)?)?;
+
+    wait_for_processing(finalize.content.data.processing_info, || status(&media_id))?;
 
-    let response = curl_rest::Client::default()
+    eprintln!("> Media uploaded to Twitter.");
+
+    Ok(media_id)
+}
+
+/// Fills `chunk` from `file`, returning fewer bytes only at the end of the file.
+fn read_chunk(file: &mut impl Read, chunk: &mut [u8]) -> Result<usize, TwitterError> {
+    let mut filled = 0;
+    while filled < chunk.len() {
+        match file.read(&mut chunk[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(TwitterError::local(err)),
+        }
+    }
+
+    Ok(filled)
+}
+
+fn status(media_id: &str) -> Result<Option<ProcessingInfo>, TwitterError> {
+    let url = api().url("/2/media/upload");
+    let auth_params = oauth::ParameterList::new([("command", "STATUS"), ("media_id", media_id)]);
+
+    let response = api()
+        .get()
+        .query_param_kv("command", "STATUS")
+        .query_param_kv("media_id", media_id)
+        .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+        .send(url.as_str())?;
+
+    let status: Response<MediaUploadResponse> = Response::decode(response)?;
+    Ok(status.content.data.processing_info)
+}
+
+/// Polls `check` as often as the API asks until processing has succeeded or failed.
+fn wait_for_processing(
+    mut processing_info: Option<ProcessingInfo>,
+    mut check: impl FnMut() -> Result<Option<ProcessingInfo>, TwitterError>,
+) -> Result<(), TwitterError> {
+    let started = Instant::now();
+
+    while let Some(info) = processing_info {
+        match info.state {
+            ProcessingState::Succeeded => break,
+            ProcessingState::Failed => {
+                let reason = info
+                    .error
+                    .and_then(|error| error.message.or(error.name))
+                    .unwrap_or_else(|| "no reason given".to_string());
+                return Err(TwitterError::local(format!(
+                    "Media processing failed: {reason}"
+                )));
+            }
+            ProcessingState::Pending | ProcessingState::InProgress => {
+                if started.elapsed() > MAX_PROCESSING_WAIT {
+                    return Err(TwitterError::local(
+                        "Gave up waiting for the media to finish processing.",
+                    ));
+                }
+
+                let wait = Duration::from_secs(info.check_after_secs.unwrap_or(1));
+                eprintln!(
+                    "> Processing media ({}%). Checking again in {}s.",
+                    info.progress_percent.unwrap_or(0),
+                    wait.as_secs()
+                );
+                thread::sleep(wait);
+                processing_info = check()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn post_form(
+    url: &str,
+    form: Multipart,
+    idempotent: bool,
+) -> Result<curl_rest::Response, TwitterError> {
+    let content_type = form.content_type();
+    let mut request = api()
         .post()
-        .header(curl_rest::Header::Authorization(auth_header.into()))
+        .authorization(|| oauth_post_header(url, &()))
         .header(curl_rest::Header::ContentType(content_type.into()))
-        .body(curl_rest::Body::Bytes(body.into()))
-        .send(upload_url)
-        .map_err(|err| UploadMediaError {
-            message: err.to_string(),
-        })?;
-
-    if (200..300).contains(&response.status.as_u16()) {
-        let media_upload_res: MediaUploadResponse = serde_json::from_slice(&response.body)
-            .map_err(|err| UploadMediaError {
-                message: err.to_string(),
-            })?;
-
-        println!("> Image uploaded to Twitter. The image ID will be added to the first tweet.");
-
-        Ok(media_upload_res.data.id)
-    } else {
-        Err(UploadMediaError {
-            message: "Please provive a valid image file. Videos are not supported".to_string(),
-        })
+        .body_bytes(form.finish());
+    if idempotent {
+        request = request.idempotent();
     }
+
+    request.send(url)
 }
 
-fn build_multipart_body(
-    boundary: &str,
-    file_name: &str,
-    media_type: &str,
-    file_path: &Path,
-) -> Result<Vec<u8>, UploadMediaError> {
-    let file_size = std::fs::metadata(file_path)
-        .map(|meta| meta.len() as usize)
-        .unwrap_or(0);
-    let mut body = Vec::with_capacity(file_size + 512);
-
-    let write_text_part = |body: &mut Vec<u8>, name: &str, value: &str| {
-        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
-        body.extend_from_slice(
+/// A `multipart/form-data` body. Its fields are not part of the OAuth signature.
+struct Multipart {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl Multipart {
+    /// Starts a form with a random boundary, so no file can contain it by chance or by design.
+    fn new() -> Self {
+        Self {
+            boundary: format!("----twitter-cli-{:016x}{:016x}", random_u64(), random_u64()),
+            body: vec![],
+        }
+    }
+
+    fn text(mut self, name: &str, value: &str) -> Self {
+        self.body
+            .extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body.extend_from_slice(
             format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes(),
         );
-        body.extend_from_slice(value.as_bytes());
-        body.extend_from_slice(b"\r\n");
-    };
-
-    write_text_part(&mut body, "media_category", "tweet_image");
-    write_text_part(&mut body, "media_type", media_type);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
 
-    body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
-    body.extend_from_slice(
-        format!("Content-Disposition: form-data; name=\"media\"; filename=\"{file_name}\"\r\n")
+    fn file(mut self, name: &str, file_name: &str, media_type: &str, data: &[u8]) -> Self {
+        self.body.reserve(data.len() + 256);
+        self.body
+            .extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body.extend_from_slice(
+            format!(
+                "Content-Disposition: form-data; name=\"{name}\"; filename=\"{file_name}\"\r\n"
+            )
             .as_bytes(),
-    );
-    body.extend_from_slice(format!("Content-Type: {media_type}\r\n\r\n").as_bytes());
-    let mut file = std::fs::File::open(file_path).map_err(|err| UploadMediaError {
-        message: err.to_string(),
-    })?;
-    file.read_to_end(&mut body)
-        .map_err(|err| UploadMediaError {
-            message: err.to_string(),
-        })?;
-    body.extend_from_slice(b"\r\n");
-    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
-
-    Ok(body)
+        );
+        self.body
+            .extend_from_slice(format!("Content-Type: {media_type}\r\n\r\n").as_bytes());
+        self.body.extend_from_slice(data);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self.body
+    }
+}
+
+fn build_multipart_body(file_name: &str, media_type: &str, data: &[u8]) -> Multipart {
+    Multipart::new()
+        .text("media_category", MediaCategory::TweetImage.as_str())
+        .text("media_type", media_type)
+        .file("media", file_name, media_type, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_images_rejects_too_many_images_before_uploading() {
+        let images = vec![
+            Image {
+                path: PathBuf::from("missing.png"),
+                alt: None,
+            };
+            MAX_IMAGES + 1
+        ];
+
+        let err = upload_images(images, &MediaOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("at most 4 images"));
+    }
+
+    #[test]
+    fn test_upload_images_rejects_long_alt_text_before_uploading() {
+        let images = vec![Image {
+            path: PathBuf::from("missing.png"),
+            alt: Some("a".repeat(MAX_ALT_TEXT_CHARS + 1)),
+        }];
+
+        let err = upload_images(images, &MediaOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("alt text"));
+    }
+
+    #[test]
+    fn test_alt_text_metadata_body() {
+        let body = MetadataBody {
+            id: "1",
+            metadata: Metadata {
+                alt_text: AltText { text: "A cat" },
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_string(&body).unwrap(),
+            r#"{"id":"1","metadata":{"alt_text":{"text":"A cat"}}}"#
+        );
+    }
+
+    #[test]
+    fn test_media_category_from_media_type() {
+        let category = |media_type| MediaCategory::from_media_type(media_type).unwrap();
+
+        assert_eq!(category("image/png"), MediaCategory::TweetImage);
+        assert_eq!(category("image/gif"), MediaCategory::TweetGif);
+        assert_eq!(category("video/mp4"), MediaCategory::TweetVideo);
+        assert_eq!(category("video/quicktime").as_str(), "tweet_video");
+        assert!(!MediaCategory::TweetImage.is_chunked());
+        assert!(MediaCategory::from_media_type("application/pdf").is_err());
+    }
+
+    #[test]
+    fn test_read_chunk_fills_the_buffer_until_the_end_of_the_file() {
+        let mut file = io::Cursor::new(vec![7_u8; 10]);
+        let mut chunk = vec![0; 4];
+
+        let reads: Vec<usize> = (0..4)
+            .map(|_| read_chunk(&mut file, &mut chunk).unwrap())
+            .collect();
+        assert_eq!(reads, vec![4, 4, 2, 0]);
+    }
+
+    fn processing(state: ProcessingState) -> Option<ProcessingInfo> {
+        Some(ProcessingInfo {
+            state,
+            check_after_secs: Some(0),
+            progress_percent: None,
+            error: None,
+        })
+    }
+
+    #[test]
+    fn test_wait_for_processing_polls_until_succeeded() {
+        let mut states = vec![
+            processing(ProcessingState::Succeeded),
+            processing(ProcessingState::InProgress),
+        ];
+        let mut checks = 0;
+
+        wait_for_processing(processing(ProcessingState::Pending), || {
+            checks += 1;
+            Ok(states.pop().unwrap())
+        })
+        .unwrap();
+        assert_eq!(checks, 2);
+
+        wait_for_processing(None, || unreachable!("images are not processed")).unwrap();
+    }
+
+    #[test]
+    fn test_wait_for_processing_reports_failures() {
+        let info: ProcessingInfo = serde_json::from_str(
+            r#"{"state":"failed","error":{"code":1,"name":"InvalidMedia","message":"Unsupported video codec"}}"#,
+        )
+        .unwrap();
+
+        let err = wait_for_processing(Some(info), || unreachable!()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "error[local]: Media processing failed: Unsupported video codec"
+        );
+    }
+
+    #[test]
+    fn test_multipart_body() {
+        let form = Multipart::new().text("command", "APPEND").file(
+            "media",
+            "blob",
+            "application/octet-stream",
+            b"abc",
+        );
+        let boundary = form.boundary.clone();
+        assert_ne!(boundary, Multipart::new().boundary);
+
+        assert_eq!(
+            String::from_utf8(form.finish()).unwrap(),
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"command\"\r\n\r\nAPPEND\r\n\
+                 --{boundary}\r\nContent-Disposition: form-data; name=\"media\"; filename=\"blob\"\r\n\
+                 Content-Type: application/octet-stream\r\n\r\nabc\r\n--{boundary}--\r\n"
+            )
+        );
+    }
 }
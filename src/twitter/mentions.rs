@@ -1,22 +1,26 @@
 use std::fmt::Display;
 
 use crate::{
-    twitter::{AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS},
+    twitter::{
+        AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetData, USER_FIELDS,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::oauth_get_header,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MentionsMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MentionsResponse {
     pub data: Vec<TweetData>,
     #[serde(default)]
@@ -25,11 +29,6 @@ pub struct MentionsResponse {
     pub meta: Option<MentionsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct MentionsError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct Mentions {
     user_id: String,
@@ -49,49 +48,44 @@ impl Mentions {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/mentions", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/mentions", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<MentionsResponse>, MentionsError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<MentionsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results;
         let tweet_fields = TWEET_FIELDS.to_string();
         let user_fields = USER_FIELDS.to_string();
         let expansions = AUTHOR_EXPANSION.to_string();
-        let auth_params = oauth::ParameterList::new([
+        let mut auth_params = vec![
             ("max_results", &max_results as &dyn Display),
             ("tweet.fields", &tweet_fields as &dyn Display),
             ("user.fields", &user_fields as &dyn Display),
             ("expansions", &expansions as &dyn Display),
-        ]);
-        let auth_header = oauth_get_header(url.as_str(), &auth_params);
+        ];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
         let max_results_query = max_results.to_string();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results_query.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("tweet.fields", tweet_fields.as_str())
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| MentionsError {
-                message: err.to_string(),
-            })?;
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
 
-        if (200..300).contains(&response.status.as_u16()) {
-            let mentions_data: MentionsResponse =
-                serde_json::from_slice(&response.body).map_err(|err| MentionsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: mentions_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(MentionsError { message: err_data })
-        }
+        Response::decode(response)
     }
 }
+
+impl_page!(MentionsResponse, includes);
+impl_paginated!(Mentions => MentionsResponse);
@@ -1,18 +1,30 @@
 use std::fmt::Display;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::twitter::error::TwitterError;
 
 pub(crate) mod blocks;
 pub(crate) mod bookmarks;
+pub mod client;
 pub(crate) mod dms;
+pub mod error;
 pub(crate) mod follows;
 pub(crate) mod likes;
 pub(crate) mod lists;
+pub mod markdown;
 pub mod media;
 pub(crate) mod mentions;
 pub(crate) mod mutes;
+pub mod pagination;
+pub mod preflight;
+pub mod progress;
+pub mod rate_limit;
+pub mod raw;
+pub mod retry;
 pub(crate) mod retweets;
 pub(crate) mod streams;
+pub mod text;
 pub(crate) mod timeline;
 pub mod tweet;
 pub(crate) mod tweets;
@@ -21,6 +33,8 @@ pub mod user;
 const TWEET_FIELDS: &str = "author_id,created_at";
 const USER_FIELDS: &str = "name,username";
 const AUTHOR_EXPANSION: &str = "author_id";
+const POLL_FIELDS: &str = "duration_minutes,end_datetime,voting_status";
+const AUTHOR_AND_POLL_EXPANSIONS: &str = "author_id,attachments.poll_ids";
 
 pub struct Response<T> {
     #[allow(dead_code)]
@@ -28,6 +42,17 @@ pub struct Response<T> {
     pub content: T,
 }
 
+impl<T: DeserializeOwned> Response<T> {
+    /// Decodes the JSON body of a successful API response.
+    pub(crate) fn decode(response: curl_rest::Response) -> Result<Self, TwitterError> {
+        let content = serde_json::from_slice(&response.body).map_err(TwitterError::decode)?;
+        Ok(Self {
+            status: response.status.as_u16(),
+            content,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TweetCreateResponse {
     pub data: TweetData,
@@ -49,7 +74,13 @@ impl Display for TweetCreateResponse {
             write!(f, "\nCreated at: {}", created_at)?;
         }
 
-        write!(f, "\nTweet body: {}", self.data.text)
+        write!(f, "\nTweet body: {}", self.data.text)?;
+
+        if let Some(poll) = self.poll() {
+            write!(f, "\n{poll}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -59,6 +90,12 @@ impl TweetCreateResponse {
         let users = self.includes.as_ref()?.users.as_ref()?;
         users.iter().find(|user| user.id == author_id)
     }
+
+    fn poll(&self) -> Option<&PollData> {
+        let poll_id = self.data.attachments.as_ref()?.poll_ids.first()?;
+        let polls = self.includes.as_ref()?.polls.as_ref()?;
+        polls.iter().find(|poll| &poll.id == poll_id)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -71,12 +108,78 @@ pub struct TweetData {
     pub author_id: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Attachments>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attachments {
+    #[serde(default)]
+    pub poll_ids: Vec<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Includes {
     #[serde(default)]
     pub users: Option<Vec<UserData>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polls: Option<Vec<PollData>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PollData {
+    pub id: String,
+    pub options: Vec<PollOption>,
+    #[serde(default)]
+    pub voting_status: Option<String>,
+    #[serde(default)]
+    pub end_datetime: Option<String>,
+    #[serde(default)]
+    pub duration_minutes: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PollOption {
+    pub position: u32,
+    pub label: String,
+    pub votes: u64,
+}
+
+impl Display for PollData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total: u64 = self.options.iter().map(|option| option.votes).sum();
+        write!(f, "Poll")?;
+        match (&self.voting_status, &self.end_datetime) {
+            (Some(status), Some(end)) => write!(f, " ({status}, ends {end})")?,
+            (Some(status), None) => write!(f, " ({status})")?,
+            (None, Some(end)) => write!(f, " (ends {end})")?,
+            (None, None) => {}
+        }
+        write!(f, ": {total} votes")?;
+
+        let width = self
+            .options
+            .iter()
+            .map(|option| option.label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut options: Vec<&PollOption> = self.options.iter().collect();
+        options.sort_by_key(|option| option.position);
+        for option in options {
+            let percent = if total == 0 {
+                0.0
+            } else {
+                option.votes as f64 * 100.0 / total as f64
+            };
+            write!(
+                f,
+                "\n  {:<width$}  {:>5.1}%  ({})",
+                option.label, percent, option.votes
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -99,6 +202,7 @@ mod tests {
                 id: "1".to_string(),
                 author_id: Some("42".to_string()),
                 created_at: Some("2026-03-06T10:00:00.000Z".to_string()),
+                attachments: None,
             },
             includes: Some(Includes {
                 users: Some(vec![UserData {
@@ -106,6 +210,7 @@ mod tests {
                     name: "Jane Doe".to_string(),
                     username: "janedoe".to_string(),
                 }]),
+                polls: None,
             }),
         };
 
@@ -124,6 +229,7 @@ mod tests {
                 id: "1".to_string(),
                 author_id: Some("42".to_string()),
                 created_at: None,
+                attachments: None,
             },
             includes: None,
         };
@@ -133,4 +239,31 @@ mod tests {
             "Tweet Id: 1\nAuthor Id: 42\nTweet body: Hello, world"
         );
     }
+
+    #[test]
+    fn test_tweet_display_with_poll_results() {
+        let response: TweetCreateResponse = serde_json::from_str(
+            r#"{
+                "data": {"id": "1", "text": "Lunch?", "attachments": {"poll_ids": ["9"]}},
+                "includes": {"polls": [{
+                    "id": "9",
+                    "voting_status": "closed",
+                    "end_datetime": "2026-03-07T10:00:00.000Z",
+                    "options": [
+                        {"position": 2, "label": "Tacos", "votes": 1},
+                        {"position": 1, "label": "Pizza", "votes": 3}
+                    ]
+                }]}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            response.to_string(),
+            "Tweet Id: 1\nTweet body: Lunch?\n\
+             Poll (closed, ends 2026-03-07T10:00:00.000Z): 4 votes\n  \
+             Pizza   75.0%  (3)\n  \
+             Tacos   25.0%  (1)"
+        );
+    }
 }
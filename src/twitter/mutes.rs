@@ -1,58 +1,54 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    twitter::Response,
+    twitter::{
+        Response,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::{get_current_user_id, oauth_get_header, oauth_post_header},
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateMuteData {
     pub muting: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateMuteResponse {
     pub data: CreateMuteData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateMuteError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteMuteData {
     pub muting: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteMuteResponse {
     pub data: DeleteMuteData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteMuteError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MutedUsersMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MutedUser {
     pub id: String,
     pub name: String,
     pub username: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MutedUsersResponse {
     #[serde(default)]
     pub data: Vec<MutedUser>,
@@ -60,11 +56,6 @@ pub struct MutedUsersResponse {
     pub meta: Option<MutedUsersMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct MutedUsersError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct CreateMute {
     user_id: String,
@@ -89,8 +80,8 @@ struct CreateMuteBody<'a> {
 }
 
 impl MutedUsers {
-    pub fn current_user() -> Result<Self, MutedUsersError> {
-        let user_id = get_current_user_id().map_err(|message| MutedUsersError { message })?;
+    pub fn current_user() -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             max_results: 10,
@@ -102,134 +93,94 @@ impl MutedUsers {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/muting", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/muting", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<MutedUsersResponse>, MutedUsersError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<MutedUsersResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
-        let auth_header = oauth_get_header(url.as_str(), &());
+        let user_fields = "name,username";
+        let mut auth_params = vec![
+            ("max_results", &max_results as &dyn Display),
+            ("user.fields", &user_fields as &dyn Display),
+        ];
+        if let Some(pagination_token) = &pagination_token {
+            auth_params.push(("pagination_token", pagination_token as &dyn Display));
+        }
+        let auth_params = oauth::ParameterList::new(auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
-            .query_param_kv("user.fields", "name,username")
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| MutedUsersError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: MutedUsersResponse =
-                serde_json::from_slice(&response.body).map_err(|err| MutedUsersError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(MutedUsersError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .query_param_opt("pagination_token", pagination_token)
+            .query_param_kv("user.fields", user_fields)
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl CreateMute {
-    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, CreateMuteError> {
-        let user_id = get_current_user_id().map_err(|message| CreateMuteError { message })?;
+    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             target_user_id: target_user_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/muting", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/muting", self.user_id))
     }
 
-    pub fn send(&self) -> Result<Response<CreateMuteResponse>, CreateMuteError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<CreateMuteResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateMuteBody {
             target_user_id: self.target_user_id.as_str(),
         })
-        .map_err(|err| CreateMuteError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .idempotent()
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| CreateMuteError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: CreateMuteResponse =
-                serde_json::from_slice(&response.body).map_err(|err| CreateMuteError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(CreateMuteError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl DeleteMute {
-    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, DeleteMuteError> {
-        let source_user_id =
-            get_current_user_id().map_err(|message| DeleteMuteError { message })?;
+    pub fn for_current_user(target_user_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let source_user_id = get_current_user_id()?;
         Ok(Self {
             source_user_id,
             target_user_id: target_user_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/muting/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/users/{}/muting/{}",
             self.source_user_id, self.target_user_id
-        )
+        ))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteMuteResponse>, DeleteMuteError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteMuteResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| DeleteMuteError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: DeleteMuteResponse =
-                serde_json::from_slice(&response.body).map_err(|err| DeleteMuteError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(DeleteMuteError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -252,6 +203,9 @@ impl std::fmt::Display for MutedUsersResponse {
     }
 }
 
+impl_page!(MutedUsersResponse);
+impl_paginated!(MutedUsers => MutedUsersResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,7 +217,10 @@ mod tests {
             max_results: 10,
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/42/muting");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/42/muting"
+        );
     }
 
     #[test]
@@ -273,7 +230,10 @@ mod tests {
             target_user_id: "99".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/42/muting");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/42/muting"
+        );
     }
 
     #[test]
@@ -283,6 +243,9 @@ mod tests {
             target_user_id: "99".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/42/muting/99");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/42/muting/99"
+        );
     }
 }
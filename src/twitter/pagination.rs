@@ -0,0 +1,339 @@
+use serde::de::DeserializeOwned;
+
+use crate::twitter::{Response, error::TwitterError};
+
+/// The largest `max_results` any list endpoint accepts.
+const MAX_PAGE_SIZE: u8 = 100;
+
+/// A response from a list endpoint, which points at the next page through `meta.next_token`.
+pub trait Page: DeserializeOwned {
+    fn next_token(&self) -> Option<&str>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn truncate(&mut self, len: usize);
+
+    /// Appends the items of the following page. `meta` then describes the last page fetched.
+    fn extend(&mut self, next: Self);
+}
+
+/// An endpoint whose results are split across pages.
+pub trait Paginated {
+    type Page: Page;
+
+    /// Fetches the page that `pagination_token` points at, or the first page.
+    fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<Self::Page>, TwitterError>;
+
+    /// Fetches the first page.
+    fn fetch(&self) -> Result<Response<Self::Page>, TwitterError> {
+        self.fetch_page(None)
+    }
+
+    /// Iterates over the pages, starting at `pagination_token`.
+    fn pages(&self, pagination_token: Option<String>) -> Pages<'_, Self>
+    where
+        Self: Sized,
+    {
+        Pages {
+            endpoint: self,
+            next_token: pagination_token,
+            done: false,
+        }
+    }
+}
+
+/// Iterator over the pages of a [`Paginated`] endpoint. It stops after the first error.
+pub struct Pages<'e, E> {
+    endpoint: &'e E,
+    next_token: Option<String>,
+    done: bool,
+}
+
+impl<E: Paginated> Iterator for Pages<'_, E> {
+    type Item = Result<Response<E::Page>, TwitterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let page = self.endpoint.fetch_page(self.next_token.as_deref());
+        match &page {
+            Ok(page) => {
+                self.next_token = page.content.next_token().map(str::to_string);
+                self.done = self.next_token.is_none();
+            }
+            Err(_) => self.done = true,
+        }
+
+        Some(page)
+    }
+}
+
+/// How many pages a command follows.
+#[derive(Debug, Clone, Default)]
+pub struct Pagination {
+    /// Follow `next_token` until the last page.
+    pub all: bool,
+    /// Follow `next_token` until this many items have been fetched.
+    pub limit: Option<usize>,
+    /// Start at this page instead of the first one.
+    pub pagination_token: Option<String>,
+}
+
+impl Pagination {
+    fn follows_pages(&self) -> bool {
+        self.all || self.limit.is_some()
+    }
+
+    /// The `max_results` to request, so that following pages takes as few calls as possible.
+    pub fn page_size(&self, requested: u8) -> u8 {
+        match self.limit {
+            _ if self.all => MAX_PAGE_SIZE,
+            Some(limit) => requested.max(limit.min(MAX_PAGE_SIZE as usize) as u8),
+            None => requested,
+        }
+    }
+
+    /// Fetches one page, or every page allowed by `all`/`limit`, merged into one response.
+    pub fn collect<E: Paginated>(&self, endpoint: &E) -> Result<Response<E::Page>, TwitterError> {
+        let mut pages = endpoint.pages(self.pagination_token.clone());
+        let mut merged = match pages.next() {
+            Some(page) => page?,
+            None => unreachable!("the first page is always fetched"),
+        };
+
+        if self.follows_pages() {
+            while self.limit.is_none_or(|limit| merged.content.len() < limit) {
+                let Some(page) = pages.next() else {
+                    break;
+                };
+                let page = page?;
+                merged.status = page.status;
+                merged.content.extend(page.content);
+            }
+        }
+
+        if let Some(limit) = self.limit
+            && merged.content.len() > limit
+        {
+            merged.content.truncate(limit);
+        } else if let Some(next_token) = merged.content.next_token() {
+            eprintln!("> More results are available with --pagination-token {next_token}");
+        }
+
+        Ok(merged)
+    }
+}
+
+/// Implements [`Page`] for a response with `data: Vec<_>`, an optional `meta.next_token` and,
+/// when `includes` is given, expansions that are merged across pages.
+macro_rules! impl_page {
+    ($response:ty) => {
+        impl $crate::twitter::pagination::Page for $response {
+            fn next_token(&self) -> Option<&str> {
+                self.meta.as_ref()?.next_token.as_deref()
+            }
+
+            fn len(&self) -> usize {
+                self.data.len()
+            }
+
+            fn truncate(&mut self, len: usize) {
+                self.data.truncate(len);
+            }
+
+            fn extend(&mut self, next: Self) {
+                self.data.extend(next.data);
+                self.meta = next.meta;
+            }
+        }
+    };
+    ($response:ty, includes) => {
+        impl $crate::twitter::pagination::Page for $response {
+            fn next_token(&self) -> Option<&str> {
+                self.meta.as_ref()?.next_token.as_deref()
+            }
+
+            fn len(&self) -> usize {
+                self.data.len()
+            }
+
+            fn truncate(&mut self, len: usize) {
+                self.data.truncate(len);
+            }
+
+            fn extend(&mut self, next: Self) {
+                self.data.extend(next.data);
+                self.meta = next.meta;
+                self.includes = match (self.includes.take(), next.includes) {
+                    (Some(mut includes), Some(next)) => {
+                        // Expanded users are keyed by id; keep the first copy of each.
+                        let users = includes.users.get_or_insert_with(Vec::new);
+                        for user in next.users.unwrap_or_default() {
+                            if !users.iter().any(|known| known.id == user.id) {
+                                users.push(user);
+                            }
+                        }
+                        Some(includes)
+                    }
+                    (includes, next) => includes.or(next),
+                };
+            }
+        }
+    };
+}
+
+/// Implements [`Paginated`] by forwarding to the endpoint's own `fetch_page`.
+macro_rules! impl_paginated {
+    ($endpoint:ty => $response:ty) => {
+        impl $crate::twitter::pagination::Paginated for $endpoint {
+            type Page = $response;
+
+            fn fetch_page(
+                &self,
+                pagination_token: Option<&str>,
+            ) -> Result<$crate::twitter::Response<$response>, $crate::twitter::error::TwitterError>
+            {
+                <$endpoint>::fetch_page(self, pagination_token)
+            }
+        }
+    };
+}
+
+pub(crate) use {impl_page, impl_paginated};
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct NumbersMeta {
+        next_token: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct NumbersResponse {
+        data: Vec<u32>,
+        meta: Option<NumbersMeta>,
+    }
+
+    impl_page!(NumbersResponse);
+
+    /// Serves `pages` in order and remembers the tokens it was asked for.
+    struct Numbers {
+        pages: Vec<Vec<u32>>,
+        requested: RefCell<Vec<Option<String>>>,
+    }
+
+    impl Paginated for Numbers {
+        type Page = NumbersResponse;
+
+        fn fetch_page(
+            &self,
+            pagination_token: Option<&str>,
+        ) -> Result<Response<NumbersResponse>, TwitterError> {
+            self.requested
+                .borrow_mut()
+                .push(pagination_token.map(str::to_string));
+            let index: usize = pagination_token.map_or(0, |token| token.parse().unwrap());
+            let next_token = (index + 1 < self.pages.len()).then(|| (index + 1).to_string());
+            Ok(Response {
+                status: 200,
+                content: NumbersResponse {
+                    data: self.pages[index].clone(),
+                    meta: Some(NumbersMeta { next_token }),
+                },
+            })
+        }
+    }
+
+    fn numbers() -> Numbers {
+        Numbers {
+            pages: vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]],
+            requested: RefCell::new(vec![]),
+        }
+    }
+
+    #[test]
+    fn test_single_page_by_default() {
+        let endpoint = numbers();
+        let page = Pagination::default().collect(&endpoint).unwrap();
+
+        assert_eq!(page.content.data, vec![1, 2, 3]);
+        assert_eq!(page.content.next_token(), Some("1"));
+        assert_eq!(endpoint.requested.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_all_follows_every_next_token() {
+        let endpoint = numbers();
+        let pagination = Pagination {
+            all: true,
+            ..Default::default()
+        };
+        let page = pagination.collect(&endpoint).unwrap();
+
+        assert_eq!(page.content.data, vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(page.content.next_token(), None);
+        assert_eq!(
+            *endpoint.requested.borrow(),
+            vec![None, Some("1".to_string()), Some("2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_limit_stops_once_enough_items_are_fetched() {
+        let endpoint = numbers();
+        let pagination = Pagination {
+            limit: Some(4),
+            ..Default::default()
+        };
+        let page = pagination.collect(&endpoint).unwrap();
+
+        assert_eq!(page.content.data, vec![1, 2, 3, 4]);
+        assert_eq!(endpoint.requested.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_pagination_token_starts_midway() {
+        let endpoint = numbers();
+        let pagination = Pagination {
+            all: true,
+            pagination_token: Some("1".to_string()),
+            ..Default::default()
+        };
+        let page = pagination.collect(&endpoint).unwrap();
+
+        assert_eq!(page.content.data, vec![4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_page_size() {
+        let all = Pagination {
+            all: true,
+            ..Default::default()
+        };
+        let limit = |limit| Pagination {
+            limit: Some(limit),
+            ..Default::default()
+        };
+
+        assert_eq!(Pagination::default().page_size(10), 10);
+        assert_eq!(all.page_size(10), 100);
+        assert_eq!(limit(3).page_size(10), 10);
+        assert_eq!(limit(40).page_size(10), 40);
+        assert_eq!(limit(1000).page_size(10), 100);
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use image::{
+    DynamicImage, ImageDecoder, ImageReader, Limits,
+    codecs::{jpeg::JpegEncoder, png::PngEncoder},
+    imageops::FilterType,
+    metadata::Orientation,
+};
+use img_parts::{
+    Bytes, DynImage, ImageEXIF,
+    jpeg::{Jpeg, markers},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::twitter::{error::TwitterError, media::MediaCategory};
+
+/// Size limits for each kind of media, from the X media upload documentation.
+pub const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+pub const MAX_GIF_BYTES: u64 = 15 * 1024 * 1024;
+pub const MAX_VIDEO_BYTES: u64 = 512 * 1024 * 1024;
+
+/// The longest edge, in pixels, the API accepts for an image.
+pub const MAX_IMAGE_DIMENSION: u32 = 8192;
+
+/// The longest edge an image is shrunk to when it has to be downscaled.
+const DOWNSCALE_DIMENSION: u32 = 4096;
+
+/// Quality used when a JPEG has to be re-encoded.
+const JPEG_QUALITY: u8 = 85;
+
+const SUPPORTED_MEDIA_TYPES: [&str; 6] = [
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+    "image/gif",
+    "video/mp4",
+    "video/quicktime",
+];
+
+/// What happens to media before it is uploaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MediaOptions {
+    /// Upload images as they are, with their EXIF metadata (camera, GPS position, ...).
+    pub keep_metadata: bool,
+    /// Re-encode JPEG and PNG images that are too large instead of rejecting them.
+    pub downscale: bool,
+}
+
+/// A file that passed pre-flight validation and is ready to upload.
+#[derive(Debug)]
+pub struct PreparedMedia {
+    pub path: PathBuf,
+    pub media_type: String,
+    pub category: MediaCategory,
+    /// The cleaned image to upload. Videos and GIFs are streamed from `path` instead.
+    pub bytes: Option<Vec<u8>>,
+}
+
+/// Checks `path` against the API limits and strips or shrinks images as `options` ask,
+/// without making any API call.
+pub fn prepare(path: &Path, options: &MediaOptions) -> Result<PreparedMedia, TwitterError> {
+    let media_type = detect_media_type(path)?;
+    let category = MediaCategory::from_media_type(&media_type)?;
+    let size = fs::metadata(path)
+        .map_err(|err| TwitterError::local(format!("Failed to read {path:?}: {err}")))?
+        .len();
+
+    if category.is_chunked() {
+        let limit = match category {
+            MediaCategory::TweetGif => MAX_GIF_BYTES,
+            _ => MAX_VIDEO_BYTES,
+        };
+        check_size(path, size, limit, category)?;
+        return Ok(PreparedMedia {
+            path: path.to_path_buf(),
+            media_type,
+            category,
+            bytes: None,
+        });
+    }
+
+    let original = fs::read(path)
+        .map_err(|err| TwitterError::local(format!("Failed to read {path:?}: {err}")))?;
+    let bytes = prepare_image(path, &media_type, original, options)?;
+    check_size(path, bytes.len() as u64, MAX_IMAGE_BYTES, category)?;
+
+    Ok(PreparedMedia {
+        path: path.to_path_buf(),
+        media_type,
+        category,
+        bytes: Some(bytes),
+    })
+}
+
+/// Detects the media type from the file contents rather than its extension.
+pub fn detect_media_type(path: &Path) -> Result<String, TwitterError> {
+    let kind = infer::get_from_path(path)
+        .map_err(|err| TwitterError::local(format!("Failed to read {path:?}: {err}")))?;
+    let unsupported = |found: &str| {
+        TwitterError::local(format!(
+            "{path:?} is {found}, which cannot be posted. Supported types: {}.",
+            SUPPORTED_MEDIA_TYPES.join(", ")
+        ))
+    };
+
+    match kind {
+        Some(kind) if SUPPORTED_MEDIA_TYPES.contains(&kind.mime_type()) => {
+            Ok(kind.mime_type().to_string())
+        }
+        Some(kind) => Err(unsupported(kind.mime_type())),
+        None => Err(unsupported("an unknown file type")),
+    }
+}
+
+fn check_size(
+    path: &Path,
+    size: u64,
+    limit: u64,
+    category: MediaCategory,
+) -> Result<(), TwitterError> {
+    if size <= limit {
+        return Ok(());
+    }
+
+    let hint = match category {
+        MediaCategory::TweetImage => " Pass --downscale to shrink JPEG and PNG images.",
+        _ => "",
+    };
+    Err(TwitterError::local(format!(
+        "{path:?} is {}, over the {} limit for {}.{hint}",
+        megabytes(size),
+        megabytes(limit),
+        category.as_str().trim_start_matches("tweet_")
+    )))
+}
+
+fn megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Strips metadata and, when allowed, shrinks an image that is over the limits. The byte
+/// limit is checked afterwards, since stripping metadata alone may be enough.
+fn prepare_image(
+    path: &Path,
+    media_type: &str,
+    bytes: Vec<u8>,
+    options: &MediaOptions,
+) -> Result<Vec<u8>, TwitterError> {
+    let can_reencode = matches!(media_type, "image/jpeg" | "image/png");
+    let (width, height) = ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_dimensions().ok())
+        .unwrap_or((0, 0));
+    let too_large = width.max(height) > MAX_IMAGE_DIMENSION;
+
+    if options.downscale && can_reencode && (too_large || bytes.len() as u64 > MAX_IMAGE_BYTES) {
+        let image = decode(path, &bytes)?;
+        eprintln!("> Downscaling {} ({width}x{height}).", path.display());
+        return downscale(path, image, media_type);
+    }
+
+    if too_large {
+        let hint = if can_reencode {
+            " Pass --downscale to shrink it."
+        } else {
+            ""
+        };
+        return Err(TwitterError::local(format!(
+            "{path:?} is {width}x{height}, over the {MAX_IMAGE_DIMENSION}px limit.{hint}"
+        )));
+    }
+
+    if options.keep_metadata {
+        return Ok(bytes);
+    }
+
+    // Phones store rotation in EXIF, so dropping it as is would turn the photo sideways.
+    if media_type == "image/jpeg" && orientation(&bytes) != Orientation::NoTransforms {
+        let image = decode(path, &bytes)?;
+        return encode(path, &image, "image/jpeg");
+    }
+
+    Ok(strip_metadata(bytes))
+}
+
+/// Drops EXIF (which holds the GPS position) and, for JPEG, XMP from an image without
+/// re-encoding it.
+pub fn strip_metadata(bytes: Vec<u8>) -> Vec<u8> {
+    let bytes = Bytes::from(bytes);
+    let stripped = match DynImage::from_bytes(bytes.clone()) {
+        Ok(Some(DynImage::Jpeg(mut jpeg))) => {
+            // APP1 carries both EXIF and XMP.
+            jpeg.remove_segments_by_marker(markers::APP1);
+            Some(Jpeg::encoder(jpeg).bytes())
+        }
+        Ok(Some(mut image)) => {
+            image.set_exif(None);
+            Some(image.encoder().bytes())
+        }
+        Ok(None) | Err(_) => None,
+    };
+
+    stripped.unwrap_or(bytes).to_vec()
+}
+
+fn orientation(bytes: &[u8]) -> Orientation {
+    ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_decoder().ok())
+        .and_then(|mut decoder| decoder.orientation().ok())
+        .unwrap_or(Orientation::NoTransforms)
+}
+
+/// Decodes an image with its EXIF orientation applied.
+fn decode(path: &Path, bytes: &[u8]) -> Result<DynamicImage, TwitterError> {
+    let failed =
+        |err: image::ImageError| TwitterError::local(format!("Failed to decode {path:?}: {err}"));
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|err| TwitterError::local(format!("Failed to read {path:?}: {err}")))?;
+    reader.limits(Limits::no_limits());
+    let mut decoder = reader.into_decoder().map_err(failed)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder).map_err(failed)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Shrinks the image until it fits both the pixel and the byte limit.
+fn downscale(path: &Path, image: DynamicImage, media_type: &str) -> Result<Vec<u8>, TwitterError> {
+    let mut edge = image.width().max(image.height()).min(DOWNSCALE_DIMENSION);
+    loop {
+        let resized = image.resize(edge, edge, FilterType::Lanczos3);
+        let bytes = encode(path, &resized, media_type)?;
+        if bytes.len() as u64 <= MAX_IMAGE_BYTES || edge <= 256 {
+            return Ok(bytes);
+        }
+        edge = edge * 3 / 4;
+    }
+}
+
+fn encode(path: &Path, image: &DynamicImage, media_type: &str) -> Result<Vec<u8>, TwitterError> {
+    let mut bytes = vec![];
+    let encoded = if media_type == "image/png" {
+        image.write_with_encoder(PngEncoder::new(&mut bytes))
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))
+    };
+    encoded.map_err(|err| TwitterError::local(format!("Failed to re-encode {path:?}: {err}")))?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage};
+
+    use super::*;
+
+    /// A minimal EXIF block (little endian TIFF) with one GPS IFD pointer.
+    const EXIF: &[u8] = b"II*\x00\x08\x00\x00\x00\x01\x00\x25\x88\x04\x00\x01\x00\x00\x00\x1a\x00\x00\x00\x00\x00\x00\x00";
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let image =
+            DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([200, 10, 10])));
+        encode(Path::new("test.jpg"), &image, "image/jpeg").unwrap()
+    }
+
+    fn with_exif(bytes: Vec<u8>) -> Vec<u8> {
+        let mut jpeg = Jpeg::from_bytes(Bytes::from(bytes)).unwrap();
+        jpeg.set_exif(Some(Bytes::from_static(EXIF)));
+        jpeg.encoder().bytes().to_vec()
+    }
+
+    fn write(name: &str, bytes: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("twitter-preflight-{}-{name}", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_unknown_file_types_are_rejected_instead_of_panicking() {
+        let path = write("notes.txt", b"just some text");
+        let err = prepare(&path, &MediaOptions::default()).unwrap_err();
+        let _ = fs::remove_file(&path);
+
+        assert!(err.to_string().contains("an unknown file type"));
+    }
+
+    #[test]
+    fn test_exif_is_stripped_by_default() {
+        let original = with_exif(jpeg(8, 8));
+        assert!(
+            Jpeg::from_bytes(Bytes::from(original.clone()))
+                .unwrap()
+                .exif()
+                .is_some()
+        );
+
+        let path = write("gps.jpg", &original);
+        let prepared = prepare(&path, &MediaOptions::default()).unwrap();
+        let kept = prepare(
+            &path,
+            &MediaOptions {
+                keep_metadata: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let _ = fs::remove_file(&path);
+
+        let stripped = Jpeg::from_bytes(Bytes::from(prepared.bytes.unwrap())).unwrap();
+        assert!(stripped.exif().is_none());
+        assert_eq!(kept.bytes.unwrap(), original);
+    }
+
+    #[test]
+    fn test_oversized_images_need_downscale() {
+        let path = write("wide.jpg", &jpeg(MAX_IMAGE_DIMENSION + 8, 4));
+        let rejected = prepare(&path, &MediaOptions::default()).unwrap_err();
+        let downscaled = prepare(
+            &path,
+            &MediaOptions {
+                downscale: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(rejected.to_string().contains("--downscale"));
+        let (width, _) = ImageReader::new(Cursor::new(downscaled.bytes.unwrap()))
+            .with_guessed_format()
+            .unwrap()
+            .into_dimensions()
+            .unwrap();
+        assert_eq!(width, DOWNSCALE_DIMENSION);
+    }
+
+    #[test]
+    fn test_size_limits_depend_on_the_category() {
+        let path = Path::new("clip.mp4");
+
+        assert!(
+            check_size(
+                path,
+                MAX_GIF_BYTES,
+                MAX_VIDEO_BYTES,
+                MediaCategory::TweetVideo
+            )
+            .is_ok()
+        );
+        let err = check_size(
+            path,
+            MAX_GIF_BYTES + 1,
+            MAX_GIF_BYTES,
+            MediaCategory::TweetGif,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "error[local]: \"clip.mp4\" is 15.0 MB, over the 15.0 MB limit for gif."
+        );
+    }
+}
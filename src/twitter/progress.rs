@@ -0,0 +1,205 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::{
+    constants::THREADS_TABLE_NAME,
+    database::Database,
+    twitter::{
+        error::TwitterError,
+        tweet::{Media, TweetBody},
+    },
+};
+
+/// How far a thread got, so a failed part can be retried with `tweet --resume <id>`.
+#[derive(Debug, Serialize)]
+pub struct ThreadProgress {
+    pub id: i64,
+    /// The first tweet as it was built, with its media, reply, quote and poll.
+    pub payload: TweetBody,
+    pub parts: Vec<String>,
+    /// The index of the configured account that posted the live parts.
+    pub account: usize,
+    /// The media uploaded for each part. Empty for threads without inline images.
+    pub part_media: Vec<Option<Media>>,
+    /// The ids of the parts that are live, in order.
+    pub posted_ids: Vec<String>,
+    pub last_error: Option<String>,
+}
+
+impl ThreadProgress {
+    /// The last part that went live, which the next part replies to.
+    pub fn previous_tweet(&self) -> Option<&str> {
+        self.posted_ids.last().map(String::as_str)
+    }
+}
+
+/// Threads that are being posted or stopped part way.
+pub struct Threads {
+    connection: Connection,
+}
+
+impl Default for Threads {
+    fn default() -> Self {
+        let db = Database::new(THREADS_TABLE_NAME);
+        Self {
+            connection: db.open_connection(),
+        }
+    }
+}
+
+impl Threads {
+    /// A private database for tests, so they never touch the user's data directory.
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        let connection = Connection::open_in_memory().unwrap();
+        crate::database::migrate(&connection, THREADS_TABLE_NAME).unwrap();
+        Self { connection }
+    }
+
+    /// Records a thread `account` is about to post before its first part is sent and returns
+    /// its resume handle.
+    pub fn start(
+        &self,
+        payload: &TweetBody,
+        parts: &[String],
+        part_media: &[Option<Media>],
+        account: usize,
+    ) -> Result<i64, TwitterError> {
+        let query = format!(
+            "INSERT INTO {THREADS_TABLE_NAME} (payload, parts, part_media, account) VALUES (?1, ?2, ?3, ?4)"
+        );
+        self.connection
+            .execute(
+                &query,
+                (
+                    to_json(payload)?,
+                    to_json(parts)?,
+                    to_json(part_media)?,
+                    account as i64,
+                ),
+            )
+            .map_err(|err| TwitterError::local(format!("Failed to save thread progress: {err}")))?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<ThreadProgress>, TwitterError> {
+        let query = format!(
+            "SELECT id, payload, parts, posted_ids, last_error, part_media, account FROM {THREADS_TABLE_NAME} WHERE id = ?1"
+        );
+        let row = self
+            .connection
+            .query_row(&query, [id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, i64>(6)?,
+                ))
+            })
+            .optional()
+            .map_err(|err| TwitterError::local(format!("Failed to read thread {id}: {err}")))?;
+
+        let Some((id, payload, parts, posted_ids, last_error, part_media, account)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(ThreadProgress {
+            id,
+            payload: from_json(&payload)?,
+            parts: from_json(&parts)?,
+            account: account as usize,
+            part_media: from_json(&part_media)?,
+            posted_ids: from_json(&posted_ids)?,
+            last_error,
+        }))
+    }
+
+    /// Appends the id of a part that just went live.
+    pub fn posted(&self, id: i64, tweet_id: &str) -> Result<(), TwitterError> {
+        let query = format!(
+            "
+            UPDATE {THREADS_TABLE_NAME}
+            SET posted_ids = json_insert(posted_ids, '$[#]', ?2),
+                last_error = NULL,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?1
+            "
+        );
+        self.connection
+            .execute(&query, (id, tweet_id))
+            .map(|_| ())
+            .map_err(|err| TwitterError::local(format!("Failed to save thread progress: {err}")))
+    }
+
+    pub fn failed(&self, id: i64, err: &TwitterError) {
+        let query = format!(
+            "UPDATE {THREADS_TABLE_NAME} SET last_error = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?1"
+        );
+        if let Err(err) = self.connection.execute(&query, (id, err.to_string())) {
+            eprintln!("Failed to record the thread error: {err}");
+        }
+    }
+
+    /// Forgets a thread once every part is live.
+    pub fn finished(&self, id: i64) {
+        let query = format!("DELETE FROM {THREADS_TABLE_NAME} WHERE id = ?1");
+        if let Err(err) = self.connection.execute(&query, [id]) {
+            eprintln!("Failed to clear thread {id}: {err}");
+        }
+    }
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, TwitterError> {
+    serde_json::to_string(value).map_err(TwitterError::local)
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, TwitterError> {
+    serde_json::from_str(json)
+        .map_err(|err| TwitterError::local(format!("Corrupt thread progress: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_progress_keeps_posted_ids_in_order() {
+        let threads = Threads::in_memory();
+        let mut payload = TweetBody::from_str("one\n---\ntwo\n---\nthree").unwrap();
+        payload.quote_tweet_id = Some("9".to_string());
+        let parts = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+
+        let part_media = vec![
+            None,
+            Some(Media {
+                media_ids: vec!["5".to_string()],
+            }),
+            None,
+        ];
+
+        let id = threads.start(&payload, &parts, &part_media, 1).unwrap();
+        threads.posted(id, "100").unwrap();
+        threads.posted(id, "101").unwrap();
+        threads.failed(id, &TwitterError::local("boom"));
+
+        let progress = threads.get(id).unwrap().unwrap();
+        assert_eq!(progress.parts, parts);
+        assert_eq!(progress.account, 1);
+        assert_eq!(progress.posted_ids, vec!["100", "101"]);
+        assert_eq!(
+            progress.part_media[1].as_ref().unwrap().media_ids,
+            vec!["5"]
+        );
+        assert_eq!(progress.previous_tweet(), Some("101"));
+        assert_eq!(progress.payload.quote_tweet_id.as_deref(), Some("9"));
+        assert_eq!(progress.last_error.as_deref(), Some("error[local]: boom"));
+
+        threads.finished(id);
+        assert!(threads.get(id).unwrap().is_none());
+    }
+}
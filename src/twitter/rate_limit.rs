@@ -0,0 +1,277 @@
+use std::{
+    sync::{
+        Mutex, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use curl_rest::ResponseHeader;
+use jiff::Timestamp;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::{constants::RATE_LIMITS_TABLE_NAME, database::Database};
+
+const LIMIT_HEADER: &str = "x-rate-limit-limit";
+const REMAINING_HEADER: &str = "x-rate-limit-remaining";
+const RESET_HEADER: &str = "x-rate-limit-reset";
+
+/// Extra time slept past the reported reset so the window has really rolled over.
+const RESET_GRACE_SECONDS: i64 = 1;
+
+static NO_WAIT: AtomicBool = AtomicBool::new(false);
+
+/// Makes a 429 fail immediately instead of sleeping until the window resets.
+pub fn set_no_wait(no_wait: bool) {
+    NO_WAIT.store(no_wait, Ordering::Relaxed);
+}
+
+pub(crate) fn no_wait() -> bool {
+    NO_WAIT.load(Ordering::Relaxed)
+}
+
+/// The last known request budget for one endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RateLimit {
+    pub endpoint: String,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Unix timestamp (seconds) at which the window resets.
+    pub reset_at: i64,
+    pub updated_at: String,
+}
+
+impl RateLimit {
+    /// Reads the `x-rate-limit-*` headers, if the response carried all of them.
+    pub fn from_headers(endpoint: impl Into<String>, headers: &[ResponseHeader]) -> Option<Self> {
+        Some(Self {
+            endpoint: endpoint.into(),
+            limit: header_value(headers, LIMIT_HEADER)?.parse().ok()?,
+            remaining: header_value(headers, REMAINING_HEADER)?.parse().ok()?,
+            reset_at: header_value(headers, RESET_HEADER)?.parse().ok()?,
+            updated_at: String::new(),
+        })
+    }
+
+    /// How long to sleep before the window resets, measured from `now`.
+    pub fn wait_from(&self, now: Timestamp) -> Duration {
+        let seconds = self.reset_at - now.as_second() + RESET_GRACE_SECONDS;
+        Duration::from_secs(seconds.max(0) as u64)
+    }
+
+    pub fn reset_time(&self) -> Option<Timestamp> {
+        Timestamp::from_second(self.reset_at).ok()
+    }
+}
+
+/// Local record of the rate-limit headers returned by every endpoint.
+pub struct RateLimits {
+    connection: Connection,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        let db = Database::new(RATE_LIMITS_TABLE_NAME);
+        Self {
+            connection: db.open_connection(),
+        }
+    }
+}
+
+impl RateLimits {
+    pub fn record(&self, rate_limit: &RateLimit) {
+        let query = format!(
+            "
+            INSERT INTO {RATE_LIMITS_TABLE_NAME} (endpoint, rate_limit, remaining, reset_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+            ON CONFLICT(endpoint)
+            DO UPDATE SET
+                rate_limit = excluded.rate_limit,
+                remaining = excluded.remaining,
+                reset_at = excluded.reset_at,
+                updated_at = CURRENT_TIMESTAMP;
+            "
+        );
+        if let Err(err) = self.connection.execute(
+            &query,
+            (
+                rate_limit.endpoint.as_str(),
+                rate_limit.limit,
+                rate_limit.remaining,
+                rate_limit.reset_at,
+            ),
+        ) {
+            eprintln!(
+                "Failed to record the rate limit for {}: {err}",
+                rate_limit.endpoint
+            );
+        }
+    }
+
+    pub fn all(&self) -> Vec<RateLimit> {
+        let query = format!(
+            "SELECT endpoint, rate_limit, remaining, reset_at, updated_at FROM {RATE_LIMITS_TABLE_NAME} ORDER BY endpoint"
+        );
+        let mut stmt = match self.connection.prepare(&query) {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                eprintln!("Failed to prepare rate limit query: {err}");
+                return vec![];
+            }
+        };
+
+        let rows = match stmt.query_map([], |row| {
+            Ok(RateLimit {
+                endpoint: row.get(0)?,
+                limit: row.get(1)?,
+                remaining: row.get(2)?,
+                reset_at: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        }) {
+            Ok(rows) => rows,
+            Err(err) => {
+                eprintln!("Failed to run rate limit query: {err}");
+                return vec![];
+            }
+        };
+
+        rows.filter_map(|row| match row {
+            Ok(rate_limit) => Some(rate_limit),
+            Err(err) => {
+                eprintln!("Failed to decode rate limit row: {err}");
+                None
+            }
+        })
+        .collect()
+    }
+}
+
+/// Stores the budget reported by a response, keyed by [`endpoint_key`].
+pub(crate) fn record(rate_limit: &RateLimit) {
+    // Opened on the first response and kept for the rest of the process.
+    static RATE_LIMITS: OnceLock<Mutex<RateLimits>> = OnceLock::new();
+
+    let rate_limits = RATE_LIMITS.get_or_init(|| Mutex::new(RateLimits::default()));
+    match rate_limits.lock() {
+        Ok(rate_limits) => rate_limits.record(rate_limit),
+        Err(err) => eprintln!(
+            "Failed to record the rate limit for {}: {err}",
+            rate_limit.endpoint
+        ),
+    }
+}
+
+/// Blocks until the window described by `rate_limit` has reset.
+pub(crate) fn wait_for_reset(rate_limit: &RateLimit) {
+    let wait = rate_limit.wait_from(Timestamp::now());
+    eprintln!(
+        "> Rate limited on {}. Waiting {}s for the window to reset.",
+        rate_limit.endpoint,
+        wait.as_secs()
+    );
+    thread::sleep(wait);
+}
+
+/// Groups requests by route so `/2/users/123/likes` and `/2/users/456/likes` share a budget.
+pub fn endpoint_key(method: &str, url: &str) -> String {
+    let path = url.split_once("://").map_or(url, |(_, rest)| {
+        rest.find('/').map_or("/", |start| &rest[start..])
+    });
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    // The first segment is the API version (`/2/...`) and is kept as is.
+    let segments: Vec<&str> = path
+        .split('/')
+        .enumerate()
+        .map(|(index, segment)| {
+            if index > 1
+                && !segment.is_empty()
+                && segment.chars().all(|c| c.is_ascii_digit() || c == '-')
+            {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect();
+
+    format!("{} {}", method.to_uppercase(), segments.join("/"))
+}
+
+fn header_value<'h>(headers: &'h [ResponseHeader], name: &str) -> Option<&'h str> {
+    headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case(name))
+        .map(|header| header.value.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitter::client::ApiClient;
+
+    fn header(name: &str, value: &str) -> ResponseHeader {
+        ResponseHeader {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_endpoint_key_replaces_ids_and_drops_query() {
+        let production = ApiClient::new("https://api.x.com");
+        let local = ApiClient::new("http://127.0.0.1:8080");
+
+        assert_eq!(
+            endpoint_key(
+                "get",
+                &production.url("/2/users/12345/liked_tweets?max_results=10")
+            ),
+            "GET /2/users/:id/liked_tweets"
+        );
+        assert_eq!(
+            endpoint_key("DELETE", &local.url("/2/users/1/following/2")),
+            "DELETE /2/users/:id/following/:id"
+        );
+        assert_eq!(
+            endpoint_key("POST", &production.url("/2/dm_conversations/1-2/messages")),
+            "POST /2/dm_conversations/:id/messages"
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_from_headers() {
+        let headers = vec![
+            header("X-Rate-Limit-Limit", "75"),
+            header("x-rate-limit-remaining", "0"),
+            header("x-rate-limit-reset", "1700000900"),
+        ];
+        let rate_limit = RateLimit::from_headers("GET /2/users/me", &headers).unwrap();
+
+        assert_eq!(rate_limit.limit, 75);
+        assert_eq!(rate_limit.remaining, 0);
+        assert_eq!(rate_limit.reset_at, 1700000900);
+
+        assert!(RateLimit::from_headers("GET /2/users/me", &headers[..2]).is_none());
+    }
+
+    #[test]
+    fn test_wait_until_reset() {
+        let rate_limit = RateLimit {
+            endpoint: "GET /2/users/me".to_string(),
+            limit: 75,
+            remaining: 0,
+            reset_at: 1700000900,
+            updated_at: String::new(),
+        };
+
+        let now = Timestamp::from_second(1700000840).unwrap();
+        assert_eq!(rate_limit.wait_from(now), Duration::from_secs(61));
+
+        let later = Timestamp::from_second(1700001000).unwrap();
+        assert_eq!(rate_limit.wait_from(later), Duration::from_secs(0));
+    }
+}
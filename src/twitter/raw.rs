@@ -0,0 +1,443 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use curl_rest::{Header, Method};
+use serde_json::{Map, Value};
+
+use crate::{
+    auth::oauth2::TokenManager,
+    twitter::{
+        Response,
+        client::{ApiClient, api},
+        error::TwitterError,
+    },
+    utils::{
+        bearer_auth_header, get_current_user_id, oauth_get_header, oauth_header, oauth_post_header,
+        oauth_put_header,
+    },
+};
+
+/// How a raw request is signed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum AuthScheme {
+    /// OAuth 1.0a user context from the current account
+    #[default]
+    #[value(name = "oauth1")]
+    OAuth1,
+    /// The app-only bearer token
+    Bearer,
+    /// The OAuth 2.0 user token, refreshed when it has expired
+    #[value(name = "oauth2")]
+    OAuth2,
+}
+
+/// A request to any endpoint, for the ones the CLI has no command for.
+#[derive(Debug)]
+pub struct RawRequest {
+    method: Method,
+    url: String,
+    auth: AuthScheme,
+    headers: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    fields: Map<String, Value>,
+    input: Option<Vec<u8>>,
+}
+
+impl RawRequest {
+    /// `path` is an API path such as `/2/users/me`, optionally with a query string, or a full
+    /// URL on the API host.
+    pub fn new(method: &str, path: &str) -> Result<Self, TwitterError> {
+        let method = match method.to_uppercase().as_str() {
+            "GET" => Method::Get,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "PATCH" => Method::Patch,
+            "DELETE" => Method::Delete,
+            other => return Err(TwitterError::local(format!("Unsupported method: {other}"))),
+        };
+
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let url = api_url(api(), path)?;
+
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (key.to_string(), value.to_string())
+            })
+            .collect();
+
+        Ok(Self {
+            method,
+            url,
+            auth: AuthScheme::default(),
+            headers: vec![],
+            query,
+            fields: Map::new(),
+            input: None,
+        })
+    }
+
+    pub fn auth(mut self, auth: AuthScheme) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Adds a field. It becomes a query parameter for GET and DELETE, and for requests whose
+    /// body is given with [`Self::input`]. Otherwise it is a field of the JSON body, where
+    /// `reply[in_reply_to_tweet_id]` nests and `media_ids[]` appends to an array.
+    pub fn field(mut self, key: &str, value: Value) -> Result<Self, TwitterError> {
+        if self.sends_fields_as_query() {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            self.query.push((key.to_string(), value));
+        } else {
+            insert_field(&mut self.fields, key, value)?;
+        }
+        Ok(self)
+    }
+
+    /// Sends `body` as is instead of the JSON built from fields.
+    pub fn input(mut self, body: Vec<u8>) -> Self {
+        self.input = Some(body);
+        for (key, value) in std::mem::take(&mut self.fields) {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            self.query.push((key, value));
+        }
+        self
+    }
+
+    fn sends_fields_as_query(&self) -> bool {
+        self.input.is_some() || matches!(self.method, Method::Get | Method::Delete)
+    }
+
+    /// Search and count endpoints page with `next_token`, everything else with `pagination_token`.
+    fn pagination_param(&self) -> &'static str {
+        if self.url.contains("/search/") || self.url.contains("/counts/") {
+            "next_token"
+        } else {
+            "pagination_token"
+        }
+    }
+
+    fn sign(&self, query: &[(String, String)]) -> String {
+        match self.auth {
+            AuthScheme::OAuth1 => {
+                let params: Vec<(&str, &dyn Display)> = query
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value as &dyn Display))
+                    .collect();
+                let params = oauth::ParameterList::new(params);
+                match self.method {
+                    Method::Get => oauth_get_header(self.url.as_str(), &params),
+                    Method::Post => oauth_post_header(self.url.as_str(), &params),
+                    Method::Put => oauth_put_header(self.url.as_str(), &params),
+                    Method::Patch => oauth_header("PATCH", self.url.as_str(), &params),
+                    _ => oauth_header("DELETE", self.url.as_str(), &params),
+                }
+            }
+            AuthScheme::Bearer => bearer_auth_header(),
+            AuthScheme::OAuth2 => format!("Bearer {}", TokenManager::default().get_token()),
+        }
+    }
+
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<Value>, TwitterError> {
+        let mut query = self.query.clone();
+        if let Some(pagination_token) = pagination_token {
+            query.push((
+                self.pagination_param().to_string(),
+                pagination_token.to_string(),
+            ));
+        }
+
+        // The OAuth2 token may be refreshed, so it is fetched once rather than per attempt.
+        let bearer = match self.auth {
+            AuthScheme::OAuth1 => None,
+            _ => Some(self.sign(&query)),
+        };
+
+        let mut request = api().request().method(self.method.clone());
+        request = match bearer {
+            Some(bearer) => request.header(Header::Authorization(bearer.into())),
+            None => request.authorization(|| self.sign(&query)),
+        };
+        for (key, value) in &query {
+            request = request.query_param_kv(key.as_str(), value.as_str());
+        }
+        for (name, value) in &self.headers {
+            request = request.header(parse_header(name, value));
+        }
+        let has_content_type = self
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+        request = match &self.input {
+            Some(input) if has_content_type => request.body_bytes(input.as_slice()),
+            Some(input) => request
+                .header(Header::ContentType("application/json".into()))
+                .body_bytes(input.as_slice()),
+            None if !self.fields.is_empty() => {
+                request.body_json(Value::Object(self.fields.clone()).to_string())
+            }
+            None => request,
+        };
+
+        let response = request.send(self.url.as_str())?;
+        if response.body.is_empty() {
+            return Ok(Response {
+                status: response.status.as_u16(),
+                content: Value::Null,
+            });
+        }
+
+        Response::decode(response)
+    }
+
+    pub fn fetch(&self) -> Result<Response<Value>, TwitterError> {
+        self.fetch_page(None)
+    }
+
+    /// Follows `meta.next_token` until the last page and merges `data` and `includes`.
+    pub fn fetch_all(&self) -> Result<Response<Value>, TwitterError> {
+        let mut merged = self.fetch()?;
+        while let Some(next_token) = next_token(&merged.content) {
+            let page = self.fetch_page(Some(next_token.as_str()))?;
+            merged.status = page.status;
+            merge_page(&mut merged.content, page.content);
+        }
+
+        Ok(merged)
+    }
+}
+
+/// Replaces an `:id` segment with the id of the current user.
+pub fn expand_path(path: &str) -> Result<String, TwitterError> {
+    if !path.split(['/', '?']).any(|segment| segment == ":id") {
+        return Ok(path.to_string());
+    }
+
+    let user_id = get_current_user_id()?;
+    let expanded: Vec<String> = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(":id") {
+            Some(rest) if rest.is_empty() || rest.starts_with('?') => format!("{user_id}{rest}"),
+            _ => segment.to_string(),
+        })
+        .collect();
+    Ok(expanded.join("/"))
+}
+
+fn parse_header<'a>(name: &'a str, value: &'a str) -> Header<'a> {
+    match name.to_lowercase().as_str() {
+        "content-type" => Header::ContentType(value.into()),
+        "accept" => Header::Accept(value.into()),
+        _ => Header::Custom(name.into(), value.into()),
+    }
+}
+
+fn next_token(page: &Value) -> Option<String> {
+    page.pointer("/meta/next_token")?
+        .as_str()
+        .map(str::to_string)
+}
+
+fn merge_page(merged: &mut Value, next: Value) {
+    let (Value::Object(merged), Value::Object(mut next)) = (merged, next) else {
+        return;
+    };
+
+    if let (Some(Value::Array(data)), Some(Value::Array(more))) =
+        (merged.get_mut("data"), next.remove("data"))
+    {
+        data.extend(more);
+    }
+
+    if let Some(Value::Object(more)) = next.remove("includes") {
+        let includes = merged
+            .entry("includes")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(includes) = includes {
+            for (key, value) in more {
+                match (includes.get_mut(&key), value) {
+                    (Some(Value::Array(known)), Value::Array(value)) => known.extend(value),
+                    (_, value) => {
+                        includes.insert(key, value);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(meta) = next.remove("meta") {
+        merged.insert("meta".to_string(), meta);
+    }
+}
+
+/// Inserts `value` at `key`, where `a[b]` is the field `b` of the object `a` and `a[]` appends.
+fn insert_field(
+    fields: &mut Map<String, Value>,
+    key: &str,
+    value: Value,
+) -> Result<(), TwitterError> {
+    let invalid = || TwitterError::local(format!("Invalid field name: {key}"));
+    let (name, rest) = key.split_once('[').unwrap_or((key, ""));
+    if name.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut path = vec![name];
+    let mut rest = rest;
+    while !rest.is_empty() {
+        let (segment, tail) = rest.split_once(']').ok_or_else(invalid)?;
+        path.push(segment);
+        rest = match tail {
+            "" => "",
+            tail => tail.strip_prefix('[').ok_or_else(invalid)?,
+        };
+    }
+
+    let mut target = fields.entry(path[0]).or_insert(Value::Null);
+    for segment in &path[1..] {
+        if segment.is_empty() {
+            if !target.is_array() {
+                *target = Value::Array(vec![]);
+            }
+            let Value::Array(items) = target else {
+                unreachable!()
+            };
+            items.push(Value::Null);
+            let last = items.len() - 1;
+            target = &mut items[last];
+        } else {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            let Value::Object(object) = target else {
+                unreachable!()
+            };
+            target = object.entry(*segment).or_insert(Value::Null);
+        }
+    }
+    *target = value;
+
+    Ok(())
+}
+
+/// The URL of `path` on the API host. A full URL must be on that host too, so credentials
+/// are never sent anywhere else.
+fn api_url(client: &ApiClient, path: &str) -> Result<String, TwitterError> {
+    if !(path.starts_with("http://") || path.starts_with("https://")) {
+        return Ok(client.url(path));
+    }
+
+    let base_url = client.base_url();
+    if path == base_url || path.starts_with(&format!("{base_url}/")) {
+        Ok(path.to_string())
+    } else {
+        Err(TwitterError::local(format!(
+            "{path} is not on the API host {base_url}. Pass a path such as /2/users/me instead."
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_fields_nest_and_append() {
+        let mut fields = Map::new();
+        insert_field(&mut fields, "text", json!("hello")).unwrap();
+        insert_field(&mut fields, "reply[in_reply_to_tweet_id]", json!("1")).unwrap();
+        insert_field(&mut fields, "media[media_ids][]", json!("2")).unwrap();
+        insert_field(&mut fields, "media[media_ids][]", json!("3")).unwrap();
+
+        assert_eq!(
+            Value::Object(fields),
+            json!({
+                "text": "hello",
+                "reply": {"in_reply_to_tweet_id": "1"},
+                "media": {"media_ids": ["2", "3"]}
+            })
+        );
+        assert!(insert_field(&mut Map::new(), "reply[id", json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_get_fields_become_query_parameters() {
+        let request = RawRequest::new("get", "/2/users/1/pinned_lists?expansions=owner_id")
+            .unwrap()
+            .field("max_results", json!(50))
+            .unwrap();
+
+        assert!(request.fields.is_empty());
+        assert_eq!(
+            request.query,
+            vec![
+                ("expansions".to_string(), "owner_id".to_string()),
+                ("max_results".to_string(), "50".to_string())
+            ]
+        );
+        assert!(RawRequest::new("TRACE", "/2/users/me").is_err());
+    }
+
+    #[test]
+    fn test_full_urls_must_be_on_the_api_host() {
+        let client = ApiClient::new("https://api.x.com");
+
+        assert_eq!(
+            api_url(&client, "/2/users/me").unwrap(),
+            "https://api.x.com/2/users/me"
+        );
+        assert_eq!(
+            api_url(&client, "https://api.x.com/2/users/me").unwrap(),
+            "https://api.x.com/2/users/me"
+        );
+        assert!(api_url(&client, "https://attacker.example/x").is_err());
+        assert!(api_url(&client, "https://api.x.com.attacker.example/x").is_err());
+        assert!(api_url(&client, "http://api.x.com/2/users/me").is_err());
+    }
+
+    #[test]
+    fn test_merge_page_appends_data_and_includes() {
+        let mut merged = json!({
+            "data": [{"id": "1"}],
+            "includes": {"users": [{"id": "9"}]},
+            "meta": {"next_token": "a"}
+        });
+        merge_page(
+            &mut merged,
+            json!({
+                "data": [{"id": "2"}],
+                "includes": {"users": [{"id": "8"}]},
+                "meta": {"result_count": 1}
+            }),
+        );
+
+        assert_eq!(
+            merged,
+            json!({
+                "data": [{"id": "1"}, {"id": "2"}],
+                "includes": {"users": [{"id": "9"}, {"id": "8"}]},
+                "meta": {"result_count": 1}
+            })
+        );
+        assert_eq!(next_token(&merged), None);
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// How failed requests are retried. Configured under `[retry]` in `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first one.
+    pub max_attempts: u32,
+    /// Backoff before the first retry. It doubles with every further attempt.
+    pub base_delay_ms: u64,
+    /// Upper bound for a single backoff.
+    pub max_delay_ms: u64,
+    /// Also retry POSTs that may already have been applied, e.g. a tweet that timed out.
+    pub retry_non_idempotent: bool,
+    /// How many times a request waits for a rate limit window to reset before it fails.
+    pub max_rate_limit_waits: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 8_000,
+            retry_non_idempotent: false,
+            max_rate_limit_waits: 3,
+        }
+    }
+}
+
+/// A failure that may go away on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Transient {
+    /// The connection was never established, so the server cannot have acted on the request.
+    NotSent,
+    /// The server may or may not have acted on the request: a 5xx, a reset or a timeout.
+    Ambiguous,
+}
+
+impl Transient {
+    pub(crate) fn from_status(status: u16) -> Option<Self> {
+        match status {
+            500 | 502 | 503 | 504 => Some(Transient::Ambiguous),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_transport(err: &curl_rest::Error) -> Option<Self> {
+        let curl_rest::Error::Client(err) = err else {
+            return None;
+        };
+
+        if err.is_couldnt_connect()
+            || err.is_couldnt_resolve_host()
+            || err.is_couldnt_resolve_proxy()
+        {
+            Some(Transient::NotSent)
+        } else if err.is_operation_timedout()
+            || err.is_send_error()
+            || err.is_recv_error()
+            || err.is_got_nothing()
+            || err.is_partial_file()
+        {
+            Some(Transient::Ambiguous)
+        } else {
+            None
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether attempt number `attempt` (1-based) may be followed by another one.
+    pub(crate) fn allows(&self, attempt: u32, transient: Transient, idempotent: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match transient {
+            Transient::NotSent => true,
+            Transient::Ambiguous => idempotent || self.retry_non_idempotent,
+        }
+    }
+
+    /// The longest backoff after attempt number `attempt` (1-based).
+    pub(crate) fn backoff_cap(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32);
+        let delay = self
+            .base_delay_ms
+            .saturating_mul(1_u64 << exponent)
+            .min(self.max_delay_ms);
+        Duration::from_millis(delay)
+    }
+
+    /// A backoff with full jitter: anywhere between zero and [`Self::backoff_cap`].
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let cap = self.backoff_cap(attempt).as_millis() as u64;
+        if cap == 0 {
+            return Duration::ZERO;
+        }
+
+        Duration::from_millis(random_u64() % (cap + 1))
+    }
+}
+
+pub(crate) fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy {
+            base_delay_ms: 500,
+            max_delay_ms: 3_000,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.backoff_cap(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff_cap(2), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff_cap(3), Duration::from_millis(2_000));
+        assert_eq!(policy.backoff_cap(4), Duration::from_millis(3_000));
+        assert_eq!(policy.backoff_cap(60), Duration::from_millis(3_000));
+
+        for attempt in 1..6 {
+            assert!(policy.backoff(attempt) <= policy.backoff_cap(attempt));
+        }
+    }
+
+    #[test]
+    fn test_only_idempotent_requests_retry_ambiguous_failures() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.allows(1, Transient::Ambiguous, true));
+        assert!(!policy.allows(1, Transient::Ambiguous, false));
+        assert!(policy.allows(1, Transient::NotSent, false));
+        assert!(!policy.allows(3, Transient::NotSent, true));
+
+        let lenient = RetryPolicy {
+            retry_non_idempotent: true,
+            ..Default::default()
+        };
+        assert!(lenient.allows(1, Transient::Ambiguous, false));
+    }
+
+    #[test]
+    fn test_policy_fields_default_when_missing_from_config() {
+        let policy: RetryPolicy = toml::from_str("max_attempts = 5").unwrap();
+
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.base_delay_ms, 500);
+        assert!(!policy.retry_non_idempotent);
+    }
+}
@@ -1,58 +1,52 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    twitter::Response,
+    twitter::{
+        Response,
+        client::{ApiClient, api},
+        error::TwitterError,
+        pagination::{impl_page, impl_paginated},
+    },
     utils::{bearer_auth_header, get_current_user_id, oauth_post_header},
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateRetweetData {
     pub retweeted: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CreateRetweetResponse {
     pub data: CreateRetweetData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateRetweetError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteRetweetData {
     pub retweeted: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteRetweetResponse {
     pub data: DeleteRetweetData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteRetweetError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct RetweetedByMeta {
     #[allow(dead_code)]
     pub result_count: u32,
-    #[allow(dead_code)]
     pub next_token: Option<String>,
     #[allow(dead_code)]
     pub previous_token: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RetweetedUser {
     pub id: String,
     pub name: String,
     pub username: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct RetweetedByResponse {
     #[serde(default)]
     pub data: Vec<RetweetedUser>,
@@ -60,11 +54,6 @@ pub struct RetweetedByResponse {
     pub meta: Option<RetweetedByMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct RetweetedByError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct CreateRetweet {
     user_id: String,
@@ -89,51 +78,33 @@ struct CreateRetweetBody<'a> {
 }
 
 impl CreateRetweet {
-    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, CreateRetweetError> {
-        let user_id = get_current_user_id().map_err(|message| CreateRetweetError { message })?;
+    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             tweet_id: tweet_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/retweets", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/retweets", self.user_id))
     }
 
-    pub fn send(&self) -> Result<Response<CreateRetweetResponse>, CreateRetweetError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<CreateRetweetResponse>, TwitterError> {
+        let url = self.url(api());
         let body = serde_json::to_string(&CreateRetweetBody {
             tweet_id: self.tweet_id.as_str(),
         })
-        .map_err(|err| CreateRetweetError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .idempotent()
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url.as_str())
-            .map_err(|err| CreateRetweetError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: CreateRetweetResponse =
-                serde_json::from_slice(&response.body).map_err(|err| CreateRetweetError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(CreateRetweetError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -150,84 +121,55 @@ impl RetweetedBy {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/tweets/{}/retweeted_by", self.tweet_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/tweets/{}/retweeted_by", self.tweet_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<RetweetedByResponse>, RetweetedByError> {
-        let url = self.url();
+    pub fn fetch_page(
+        &self,
+        pagination_token: Option<&str>,
+    ) -> Result<Response<RetweetedByResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results.to_string();
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results.as_str())
+            .query_param_opt("pagination_token", pagination_token)
             .query_param_kv("user.fields", "name,username")
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url.as_str())
-            .map_err(|err| RetweetedByError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: RetweetedByResponse =
-                serde_json::from_slice(&response.body).map_err(|err| RetweetedByError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(RetweetedByError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
 impl DeleteRetweet {
-    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, DeleteRetweetError> {
-        let user_id = get_current_user_id().map_err(|message| DeleteRetweetError { message })?;
+    pub fn for_current_user(tweet_id: impl Into<String>) -> Result<Self, TwitterError> {
+        let user_id = get_current_user_id()?;
         Ok(Self {
             user_id,
             tweet_id: tweet_id.into(),
         })
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://api.x.com/2/users/{}/retweets/{}",
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!(
+            "/2/users/{}/retweets/{}",
             self.user_id, self.tweet_id
-        )
+        ))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteRetweetResponse>, DeleteRetweetError> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteRetweetResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| DeleteRetweetError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let data: DeleteRetweetResponse =
-                serde_json::from_slice(&response.body).map_err(|err| DeleteRetweetError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: data,
-            })
-        } else {
-            Err(DeleteRetweetError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -250,6 +192,9 @@ impl std::fmt::Display for RetweetedByResponse {
     }
 }
 
+impl_page!(RetweetedByResponse);
+impl_paginated!(RetweetedBy => RetweetedByResponse);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,7 +206,10 @@ mod tests {
             tweet_id: "456".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/retweets");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/retweets"
+        );
     }
 
     #[test]
@@ -271,7 +219,10 @@ mod tests {
             tweet_id: "456".to_string(),
         };
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123/retweets/456");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123/retweets/456"
+        );
     }
 
     #[test]
@@ -282,7 +233,7 @@ mod tests {
         };
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/tweets/456/retweeted_by"
         );
     }
@@ -3,7 +3,14 @@ use std::fmt::Display;
 use curl::easy::{Easy, List};
 use serde::{Deserialize, Serialize};
 
-use crate::{twitter::Response, utils::bearer_auth_header};
+use crate::{
+    twitter::{
+        Response,
+        client::{ApiClient, api},
+        error::TwitterError,
+    },
+    utils::bearer_auth_header,
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StreamRule {
@@ -13,7 +20,7 @@ pub struct StreamRule {
     pub tag: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct StreamRulesMeta {
     #[allow(dead_code)]
     pub sent: Option<String>,
@@ -21,7 +28,7 @@ pub struct StreamRulesMeta {
     pub result_count: Option<u32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct StreamRulesResponse {
     #[serde(default)]
     pub data: Vec<StreamRule>,
@@ -29,12 +36,7 @@ pub struct StreamRulesResponse {
     pub meta: Option<StreamRulesMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct StreamRulesError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct StreamRulesUpdateMeta {
     #[allow(dead_code)]
     pub summary: Option<serde_json::Value>,
@@ -42,7 +44,7 @@ pub struct StreamRulesUpdateMeta {
     pub sent: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct StreamRulesUpdateResponse {
     #[serde(default)]
     pub data: Vec<StreamRule>,
@@ -50,11 +52,6 @@ pub struct StreamRulesUpdateResponse {
     pub meta: Option<StreamRulesUpdateMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct StreamRulesUpdateError {
-    pub message: String,
-}
-
 pub struct StreamRules;
 
 #[derive(Debug)]
@@ -112,36 +109,20 @@ impl Display for StreamRulesResponse {
 }
 
 impl StreamRules {
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets/search/stream/rules"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets/search/stream/rules")
     }
 
-    pub fn fetch(&self) -> Result<Response<StreamRulesResponse>, StreamRulesError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<StreamRulesResponse>, TwitterError> {
+        let url = self.url(api());
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url)
-            .map_err(|err| StreamRulesError {
-                message: err.to_string(),
-            })?;
+            .send(url.as_str())?;
 
-        if (200..300).contains(&response.status.as_u16()) {
-            let rules_data: StreamRulesResponse =
-                serde_json::from_slice(&response.body).map_err(|err| StreamRulesError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: rules_data,
-            })
-        } else {
-            Err(StreamRulesError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+        Response::decode(response)
     }
 }
 
@@ -153,12 +134,12 @@ impl AddStreamRule {
         }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets/search/stream/rules"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets/search/stream/rules")
     }
 
-    pub fn send(&self) -> Result<Response<StreamRulesUpdateResponse>, StreamRulesUpdateError> {
-        let url = self.url();
+    pub fn send(&self) -> Result<Response<StreamRulesUpdateResponse>, TwitterError> {
+        let url = self.url(api());
         let authorization = bearer_auth_header();
         let body = serde_json::to_string(&StreamRulesUpdatePayload {
             add: Some(vec![AddStreamRulePayload {
@@ -167,33 +148,15 @@ impl AddStreamRule {
             }]),
             delete: None,
         })
-        .map_err(|err| StreamRulesUpdateError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
             .header(curl_rest::Header::Authorization(authorization.into()))
             .body_json(body)
-            .send(url)
-            .map_err(|err| StreamRulesUpdateError {
-                message: err.to_string(),
-            })?;
+            .send(url.as_str())?;
 
-        if (200..300).contains(&response.status.as_u16()) {
-            let rules_data: StreamRulesUpdateResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| StreamRulesUpdateError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: rules_data,
-            })
-        } else {
-            Err(StreamRulesUpdateError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+        Response::decode(response)
     }
 }
 
@@ -202,12 +165,12 @@ impl DeleteStreamRules {
         Self { ids }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets/search/stream/rules"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets/search/stream/rules")
     }
 
-    pub fn send(&self) -> Result<Response<StreamRulesUpdateResponse>, StreamRulesUpdateError> {
-        let url = self.url();
+    pub fn send(&self) -> Result<Response<StreamRulesUpdateResponse>, TwitterError> {
+        let url = self.url(api());
         let authorization = bearer_auth_header();
         let body = serde_json::to_string(&StreamRulesUpdatePayload {
             add: None,
@@ -215,33 +178,15 @@ impl DeleteStreamRules {
                 ids: self.ids.as_slice(),
             }),
         })
-        .map_err(|err| StreamRulesUpdateError {
-            message: err.to_string(),
-        })?;
+        .map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
             .header(curl_rest::Header::Authorization(authorization.into()))
             .body_json(body)
-            .send(url)
-            .map_err(|err| StreamRulesUpdateError {
-                message: err.to_string(),
-            })?;
+            .send(url.as_str())?;
 
-        if (200..300).contains(&response.status.as_u16()) {
-            let rules_data: StreamRulesUpdateResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| StreamRulesUpdateError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: rules_data,
-            })
-        } else {
-            Err(StreamRulesUpdateError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+        Response::decode(response)
     }
 }
 
@@ -257,72 +202,52 @@ impl FilteredStream {
         self
     }
 
-    fn url(&self) -> String {
-        let base = "https://api.x.com/2/tweets/search/stream";
+    fn url(&self, client: &ApiClient) -> String {
+        let base = client.url("/2/tweets/search/stream");
         match self.backfill_minutes {
             Some(backfill_minutes) => format!("{base}?backfill_minutes={backfill_minutes}"),
-            None => base.to_string(),
+            None => base,
         }
     }
 
-    pub fn connect(&self) -> Result<(), StreamRulesError> {
-        let url = self.url();
+    pub fn connect(&self, mut on_event: impl FnMut(&str)) -> Result<(), TwitterError> {
+        let url = self.url(api());
         let authorization = bearer_auth_header();
 
         let mut easy = Easy::new();
-        easy.url(url.as_str()).map_err(|err| StreamRulesError {
-            message: err.to_string(),
-        })?;
-        easy.get(true).map_err(|err| StreamRulesError {
-            message: err.to_string(),
-        })?;
+        easy.url(url.as_str())?;
+        easy.get(true)?;
 
         let mut headers = List::new();
-        headers
-            .append(format!("Authorization: {authorization}").as_str())
-            .map_err(|err| StreamRulesError {
-                message: err.to_string(),
-            })?;
-        headers
-            .append("User-Agent: twitter-cli")
-            .map_err(|err| StreamRulesError {
-                message: err.to_string(),
-            })?;
-        easy.http_headers(headers).map_err(|err| StreamRulesError {
-            message: err.to_string(),
-        })?;
+        headers.append(format!("Authorization: {authorization}").as_str())?;
+        headers.append("User-Agent: twitter-cli")?;
+        easy.http_headers(headers)?;
 
         let mut buffer = Vec::new();
         {
             let mut transfer = easy.transfer();
-            transfer
-                .write_function(|data| {
-                    buffer.extend_from_slice(data);
-
-                    while let Some(pos) = buffer.iter().position(|byte| *byte == b'\n') {
-                        let line = buffer.drain(..=pos).collect::<Vec<_>>();
-                        let line = String::from_utf8_lossy(&line);
-                        let line = line.trim();
-                        if !line.is_empty() {
-                            println!("{line}");
-                        }
+            transfer.write_function(|data| {
+                buffer.extend_from_slice(data);
+
+                while let Some(pos) = buffer.iter().position(|byte| *byte == b'\n') {
+                    let line = buffer.drain(..=pos).collect::<Vec<_>>();
+                    let line = String::from_utf8_lossy(&line);
+                    let line = line.trim();
+                    if !line.is_empty() {
+                        on_event(line);
                     }
+                }
 
-                    Ok(data.len())
-                })
-                .map_err(|err| StreamRulesError {
-                    message: err.to_string(),
-                })?;
-            transfer.perform().map_err(|err| StreamRulesError {
-                message: err.to_string(),
+                Ok(data.len())
             })?;
+            transfer.perform()?;
         }
 
         if !buffer.is_empty() {
             let line = String::from_utf8_lossy(&buffer);
             let line = line.trim();
             if !line.is_empty() {
-                println!("{line}");
+                on_event(line);
             }
         }
 
@@ -339,7 +264,7 @@ mod tests {
         let endpoint = StreamRules;
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/tweets/search/stream/rules"
         );
     }
@@ -349,7 +274,7 @@ mod tests {
         let endpoint = AddStreamRule::new("from:openai", Some("openai".to_string()));
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/tweets/search/stream/rules"
         );
     }
@@ -359,7 +284,7 @@ mod tests {
         let endpoint = DeleteStreamRules::new(vec!["1".to_string(), "2".to_string()]);
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/tweets/search/stream/rules"
         );
     }
@@ -368,7 +293,10 @@ mod tests {
     fn test_filtered_stream_url_uses_stream_endpoint() {
         let endpoint = FilteredStream::new();
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/tweets/search/stream");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/tweets/search/stream"
+        );
     }
 
     #[test]
@@ -376,7 +304,7 @@ mod tests {
         let endpoint = FilteredStream::new().backfill_minutes(Some(5));
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/tweets/search/stream?backfill_minutes=5"
         );
     }
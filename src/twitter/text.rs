@@ -0,0 +1,498 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::twitter::error::TwitterError;
+
+/// The longest tweet, in weighted characters.
+pub const MAX_TWEET_LENGTH: usize = 280;
+
+/// Every URL counts as this many characters, whatever its real length, since X shortens it.
+pub const URL_LENGTH: usize = 23;
+
+/// Code points that count as one character. Everything else, including CJK, counts as two.
+const LIGHT_RANGES: [(u32, u32); 4] = [
+    (0x0000, 0x10FF),
+    (0x2000, 0x200D),
+    (0x2010, 0x201F),
+    (0x2032, 0x2037),
+];
+
+/// Characters stripped from the end of a URL, as they usually end the sentence around it.
+const URL_TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '\'', '"'];
+
+/// The length of a tweet as X counts it: text is NFC normalized, URLs count as 23,
+/// CJK characters count as two and an emoji counts as two however many code points it has.
+pub fn weighted_length(text: &str) -> usize {
+    let text: String = text.nfc().collect();
+
+    text.split_inclusive(char::is_whitespace)
+        .map(|token| {
+            let word = token.trim_end();
+            let space = &token[word.len()..];
+            let length = match find_url(word) {
+                Some((start, end)) => {
+                    text_length(&word[..start]) + URL_LENGTH + text_length(&word[end..])
+                }
+                None => text_length(word),
+            };
+            length + text_length(space)
+        })
+        .sum()
+}
+
+/// Finds a `http://`, `https://` or `www.` link in a word, returning its byte range.
+fn find_url(word: &str) -> Option<(usize, usize)> {
+    if !word.contains('.') {
+        return None;
+    }
+
+    let lowercase = word.to_ascii_lowercase();
+    let start = ["https://", "http://", "www."]
+        .iter()
+        .filter_map(|prefix| lowercase.find(prefix))
+        .filter(|&start| {
+            word[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric())
+        })
+        .min()?;
+
+    let url = word[start..].trim_end_matches(URL_TRAILING_PUNCTUATION);
+    let host = url.split("://").last().unwrap_or_default();
+    if !host.contains('.') || host.ends_with('.') {
+        return None;
+    }
+
+    Some((start, start + url.len()))
+}
+
+fn text_length(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_length).sum()
+}
+
+fn grapheme_length(grapheme: &str) -> usize {
+    if is_emoji(grapheme) {
+        return 2;
+    }
+
+    grapheme
+        .chars()
+        .map(|c| {
+            let code = c as u32;
+            if LIGHT_RANGES
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&code))
+            {
+                1
+            } else {
+                2
+            }
+        })
+        .sum()
+}
+
+/// Emoji sequences (skin tones, ZWJ families, flags, keycaps) count as a single emoji.
+fn is_emoji(grapheme: &str) -> bool {
+    grapheme.chars().any(|c| {
+        matches!(
+            c,
+            '\u{200D}' | '\u{FE0F}' | '\u{20E3}' | '\u{1F000}'..='\u{1FAFF}'
+        )
+    })
+}
+
+/// The length of one part of a tweet or thread, as shown by `tweet --check`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct PartLength {
+    pub part: usize,
+    pub length: usize,
+    pub max: usize,
+    pub text: String,
+}
+
+impl PartLength {
+    pub fn is_too_long(&self) -> bool {
+        self.length > self.max
+    }
+}
+
+/// Measures every part of a thread.
+pub fn measure(parts: &[String]) -> Vec<PartLength> {
+    parts
+        .iter()
+        .enumerate()
+        .map(|(index, text)| PartLength {
+            part: index + 1,
+            length: weighted_length(text),
+            max: MAX_TWEET_LENGTH,
+            text: text.clone(),
+        })
+        .collect()
+}
+
+/// How a thread is cut into parts and marked up. Configured under `[thread]` in `config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThreadFormat {
+    /// A line on its own that ends one part and starts the next.
+    pub separator: String,
+    /// Start a new part at every blank line instead of at the separator.
+    pub paragraphs: bool,
+    /// Where the `n/N` counters go.
+    pub numbering: Numbering,
+    /// Added to the end of the first part of a thread, e.g. "🧵".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+}
+
+impl Default for ThreadFormat {
+    fn default() -> Self {
+        Self {
+            separator: "---".to_string(),
+            paragraphs: false,
+            numbering: Numbering::Off,
+            continuation: None,
+        }
+    }
+}
+
+impl ThreadFormat {
+    /// Turns the counters on, keeping a configured prefix or suffix style.
+    pub fn numbered(&mut self) {
+        if self.numbering == Numbering::Off {
+            self.numbering = Numbering::Suffix;
+        }
+    }
+
+    /// Fails when the counters and continuation marker leave the first part of a thread no
+    /// room for text.
+    pub fn validate(&self) -> Result<(), TwitterError> {
+        if self.reserved(0, 2) >= MAX_TWEET_LENGTH {
+            return Err(TwitterError::local(format!(
+                "The thread continuation marker is too long: it leaves no room in the first {MAX_TWEET_LENGTH} characters."
+            )));
+        }
+        Ok(())
+    }
+
+    /// Joins parts that are already cut, such as rendered Markdown, changing the format so it
+    /// cuts the text back at the same places only. Blank lines stay inside their part, and
+    /// the separator grows until no part has a line equal to it.
+    pub fn join_exactly(&mut self, parts: &[String]) -> String {
+        self.paragraphs = false;
+        while parts
+            .iter()
+            .any(|part| part.lines().any(|line| line.trim() == self.separator))
+        {
+            let last = self.separator.chars().last().unwrap_or('-');
+            self.separator.push(last);
+        }
+        self.join(parts)
+    }
+
+    /// Joins parts into one text that this format cuts back into the same parts.
+    pub fn join(&self, parts: &[String]) -> String {
+        if self.paragraphs {
+            parts.join("\n\n")
+        } else {
+            parts.join(&format!("\n{}\n", self.separator))
+        }
+    }
+
+    /// The room the counters and continuation marker take from a part of a `total` long thread.
+    fn reserved(&self, index: usize, total: usize) -> usize {
+        if total < 2 {
+            return 0;
+        }
+        let counter = match self.numbering {
+            Numbering::Off => 0,
+            Numbering::Prefix | Numbering::Suffix => weighted_length(&format!(" {total}/{total}")),
+        };
+        let marker = match (&self.continuation, index) {
+            (Some(marker), 0) => weighted_length(&format!(" {marker}")),
+            _ => 0,
+        };
+        counter + marker
+    }
+}
+
+/// Where the `n/N` counters of a thread go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Numbering {
+    #[default]
+    Off,
+    /// `1/5 First tweet`
+    Prefix,
+    /// `First tweet 1/5`
+    Suffix,
+}
+
+/// Splits every part that is too long into several, leaving room for the counters and
+/// marker of `format`, and pairs every new part with the index of the part it was cut
+/// from. Parts that already fit are kept as they are.
+pub fn split_thread_indexed(parts: &[String], format: &ThreadFormat) -> Vec<(usize, String)> {
+    let mut total = parts.len();
+    loop {
+        // The first part is cut shorter when it has to make room for the continuation marker.
+        let split: Vec<(usize, String)> = parts
+            .iter()
+            .enumerate()
+            .flat_map(|(index, part)| {
+                split_text(
+                    part,
+                    MAX_TWEET_LENGTH.saturating_sub(format.reserved(index, total)),
+                )
+                .into_iter()
+                .map(move |part| (index, part))
+            })
+            .collect();
+
+        // The counters and marker take room, which can change how many parts there are.
+        let plain = format.numbering == Numbering::Off && format.continuation.is_none();
+        if split.len() <= total || plain {
+            let (sources, parts): (Vec<usize>, Vec<String>) = split.into_iter().unzip();
+            return sources.into_iter().zip(decorate(parts, format)).collect();
+        }
+        total = split.len();
+    }
+}
+
+/// Adds the continuation marker to the first part and the counters to every part, as set
+/// in `format`. A lone tweet is left alone.
+pub fn decorate(parts: Vec<String>, format: &ThreadFormat) -> Vec<String> {
+    let total = parts.len();
+    if total < 2 {
+        return parts;
+    }
+
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let part = match (&format.continuation, index) {
+                (Some(marker), 0) => format!("{part} {marker}"),
+                _ => part,
+            };
+            let counter = format!("{}/{total}", index + 1);
+            match format.numbering {
+                Numbering::Off => part,
+                Numbering::Prefix => format!("{counter} {part}"),
+                Numbering::Suffix => format!("{part} {counter}"),
+            }
+        })
+        .collect()
+}
+
+/// Breaks `text` into parts of at most `limit` weighted characters, preferring sentence
+/// boundaries, then word boundaries, and cutting inside a word only when it alone is too long.
+pub fn split_text(text: &str, limit: usize) -> Vec<String> {
+    let text = text.trim();
+    if weighted_length(text) <= limit {
+        return vec![text.to_string()];
+    }
+
+    let mut splitter = Splitter {
+        limit,
+        parts: vec![],
+        current: String::new(),
+    };
+    for sentence in text.split_sentence_bounds() {
+        splitter.push(sentence, Boundary::Sentence);
+    }
+    splitter.finish()
+}
+
+#[derive(Clone, Copy)]
+enum Boundary {
+    Sentence,
+    Word,
+    Grapheme,
+}
+
+struct Splitter {
+    limit: usize,
+    parts: Vec<String>,
+    current: String,
+}
+
+impl Splitter {
+    fn push(&mut self, piece: &str, boundary: Boundary) {
+        let candidate = format!("{}{piece}", self.current);
+        if weighted_length(candidate.trim()) <= self.limit {
+            self.current = candidate;
+            return;
+        }
+
+        self.flush();
+        // A grapheme cannot be cut further, so one over the limit is a part of its own.
+        if weighted_length(piece.trim()) <= self.limit || matches!(boundary, Boundary::Grapheme) {
+            self.current = piece.trim_start().to_string();
+            return;
+        }
+
+        match boundary {
+            Boundary::Sentence => {
+                for word in piece.split_inclusive(char::is_whitespace) {
+                    self.push(word, Boundary::Word);
+                }
+            }
+            Boundary::Word | Boundary::Grapheme => {
+                for grapheme in piece.graphemes(true) {
+                    self.push(grapheme, Boundary::Grapheme);
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        let part = self.current.trim();
+        if !part.is_empty() {
+            self.parts.push(part.to_string());
+        }
+        self.current.clear();
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.flush();
+        self.parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_length_counts_urls_cjk_and_emoji() {
+        assert_eq!(weighted_length("hello"), 5);
+        assert_eq!(
+            weighted_length("read https://example.com/a/very/long/path?with=query."),
+            5 + URL_LENGTH + 1
+        );
+        assert_eq!(weighted_length("(www.example.com)"), URL_LENGTH + 2);
+        assert_eq!(weighted_length("こんにちは"), 10);
+        assert_eq!(weighted_length("👍🏽"), 2);
+        assert_eq!(weighted_length("👨‍👩‍👧"), 2);
+        assert_eq!(weighted_length("“quoted”"), 8);
+    }
+
+    #[test]
+    fn test_weighted_length_normalizes_to_nfc() {
+        assert_eq!(weighted_length("e\u{301}"), weighted_length("\u{e9}"));
+        assert_eq!(weighted_length("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn test_split_text_prefers_sentence_boundaries() {
+        let sentence = format!("Word {}.", "word ".repeat(19).trim());
+        let text = [sentence.as_str(); 3].join(" ");
+        let parts = split_text(&text, MAX_TWEET_LENGTH);
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0], format!("{sentence} {sentence}"));
+        assert_eq!(parts[1], sentence);
+    }
+
+    #[test]
+    fn test_split_text_falls_back_to_words_and_graphemes() {
+        let words = split_text(&"abcd ".repeat(100), 20);
+        assert!(words.iter().all(|part| weighted_length(part) <= 20));
+        assert_eq!(words[0], "abcd abcd abcd abcd");
+
+        let long_word = split_text(&"x".repeat(50), 20);
+        assert_eq!(
+            long_word,
+            vec!["x".repeat(20), "x".repeat(20), "x".repeat(10)]
+        );
+    }
+
+    fn numbered() -> ThreadFormat {
+        ThreadFormat {
+            numbering: Numbering::Suffix,
+            ..Default::default()
+        }
+    }
+
+    fn split(parts: &[String], format: &ThreadFormat) -> Vec<String> {
+        split_thread_indexed(parts, format)
+            .into_iter()
+            .map(|(_, part)| part)
+            .collect()
+    }
+
+    #[test]
+    fn test_split_thread_numbers_parts_within_the_limit() {
+        let parts = split(&["word ".repeat(200)], &numbered());
+
+        assert_eq!(parts.len(), 4);
+        assert!(parts[0].ends_with(" 1/4"));
+        assert!(parts[3].ends_with(" 4/4"));
+        assert!(
+            parts
+                .iter()
+                .all(|part| weighted_length(part) <= MAX_TWEET_LENGTH)
+        );
+    }
+
+    #[test]
+    fn test_decorate_places_counters_and_marker() {
+        let parts = vec!["first".to_string(), "second".to_string()];
+        let format = ThreadFormat {
+            numbering: Numbering::Prefix,
+            continuation: Some("🧵".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            decorate(parts.clone(), &format),
+            vec!["1/2 first 🧵", "2/2 second"]
+        );
+        assert_eq!(decorate(vec!["alone".to_string()], &format), vec!["alone"]);
+    }
+
+    #[test]
+    fn test_split_thread_makes_room_for_the_marker() {
+        let format = ThreadFormat {
+            continuation: Some("🧵".to_string()),
+            ..Default::default()
+        };
+        let parts = split_thread_indexed(&["word ".repeat(100)], &format);
+
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].1.ends_with(" 🧵"));
+        assert!(weighted_length(&parts[0].1) <= MAX_TWEET_LENGTH);
+        assert_eq!(
+            split_thread_indexed(&["short".to_string()], &format)[0].1,
+            "short"
+        );
+    }
+
+    #[test]
+    fn test_markers_that_leave_no_room_are_rejected() {
+        let format = ThreadFormat {
+            continuation: Some("x".repeat(MAX_TWEET_LENGTH)),
+            ..Default::default()
+        };
+
+        assert!(format.validate().is_err());
+        assert!(ThreadFormat::default().validate().is_ok());
+        // Splitting still ends instead of underflowing the room left for text.
+        let parts = split_thread_indexed(&["a".to_string(), "b".to_string()], &format);
+        assert_eq!(parts.len(), 2);
+    }
+
+    #[test]
+    fn test_short_parts_are_kept_as_is() {
+        let parts = vec!["first".to_string(), "second".to_string()];
+
+        assert_eq!(split(&parts, &ThreadFormat::default()), parts);
+        assert_eq!(
+            decorate(vec!["alone".to_string()], &numbered()),
+            vec!["alone"]
+        );
+    }
+}
@@ -1,31 +1,29 @@
 use std::fmt::Error;
 use std::str::FromStr;
 
-use crate::twitter::{Response, TweetCreateResponse, TweetData};
-use crate::utils::oauth_post_header;
+use crate::twitter::{
+    Response, TweetCreateResponse, TweetData,
+    client::{ApiClient, api, dry_run},
+    error::TwitterError,
+    media::{Image, check_thread_images, upload_thread_images},
+    preflight::MediaOptions,
+    progress::Threads,
+    text::{self, PartLength, ThreadFormat},
+};
+use crate::utils::{self, oauth_post_header};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
-pub struct CreateTweetErr {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteTweetData {
     pub deleted: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteTweetResponse {
     pub data: DeleteTweetData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DeleteTweetErr {
-    pub message: String,
-}
-
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TweetBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
@@ -33,6 +31,10 @@ pub struct TweetBody {
     pub reply: Option<Reply>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub media: Option<Media>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_tweet_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<Poll>,
 }
 
 impl FromStr for TweetBody {
@@ -43,30 +45,205 @@ impl FromStr for TweetBody {
             text: Some(s.to_owned()),
             reply: None,
             media: None,
+            quote_tweet_id: None,
+            poll: None,
         })
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Reply {
     pub in_reply_to_tweet_id: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Media {
     pub media_ids: Vec<String>,
 }
 
+/// The fewest and most choices a poll can offer.
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 4;
+
+/// The longest poll choice the API accepts, in characters.
+pub const MAX_POLL_OPTION_CHARS: usize = 25;
+
+/// How long a poll can stay open, in minutes: five minutes to seven days.
+pub const MIN_POLL_MINUTES: u32 = 5;
+pub const MAX_POLL_MINUTES: u32 = 7 * 24 * 60;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poll {
+    pub options: Vec<String>,
+    pub duration_minutes: u32,
+}
+
+impl Poll {
+    /// Builds a poll, checking the limits the API would otherwise reject it for.
+    pub fn new(options: Vec<String>, duration_minutes: u32) -> Result<Self, TwitterError> {
+        if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&options.len()) {
+            return Err(TwitterError::local(format!(
+                "A poll needs {MIN_POLL_OPTIONS} to {MAX_POLL_OPTIONS} options, got {}.",
+                options.len()
+            )));
+        }
+
+        if let Some(option) = options.iter().find(|option| {
+            let chars = option.trim().chars().count();
+            chars == 0 || chars > MAX_POLL_OPTION_CHARS
+        }) {
+            return Err(TwitterError::local(format!(
+                "Poll options must be 1 to {MAX_POLL_OPTION_CHARS} characters long, got {option:?}."
+            )));
+        }
+
+        if !(MIN_POLL_MINUTES..=MAX_POLL_MINUTES).contains(&duration_minutes) {
+            return Err(TwitterError::local(format!(
+                "A poll can run for {MIN_POLL_MINUTES} minutes to 7 days, got {duration_minutes} minutes."
+            )));
+        }
+
+        Ok(Self {
+            options: options
+                .into_iter()
+                .map(|option| option.trim().to_string())
+                .collect(),
+            duration_minutes,
+        })
+    }
+}
+
+/// Reads a poll duration in minutes, either as a bare number or with a unit:
+/// `90`, `90m`, `6h`, `1d`, or combined like `1d12h`.
+pub fn parse_poll_duration(value: &str) -> Result<u32, String> {
+    let value = value.trim();
+    if let Ok(minutes) = value.parse() {
+        return Ok(minutes);
+    }
+
+    let invalid = || format!("expected a duration like 30m, 6h or 1d, got {value:?}");
+    let mut minutes: u32 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c {
+            'm' => 1,
+            'h' => 60,
+            'd' => 24 * 60,
+            _ => return Err(invalid()),
+        };
+        let amount: u32 = number.parse().map_err(|_| invalid())?;
+        minutes = amount
+            .checked_mul(unit)
+            .and_then(|amount| minutes.checked_add(amount))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+
+    if !number.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(minutes)
+}
+
+/// Reads a tweet id from either the id itself or a tweet url such as
+/// `https://x.com/user/status/123?s=20`.
+pub fn parse_tweet_id(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    let is_id = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+    if is_id(value) {
+        return Ok(value.to_string());
+    }
+
+    let path = value
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let mut segments = path.rsplit('/');
+    match (segments.next(), segments.next()) {
+        (Some(id), Some("status" | "statuses")) if is_id(id) => Ok(id.to_string()),
+        _ => Err(format!(
+            "expected a tweet id or a url like https://x.com/user/status/123, got {value:?}"
+        )),
+    }
+}
+
+/// Splits `tweet` at blank lines. Text that is a single paragraph is returned unchanged.
+fn split_paragraphs(tweet: &str) -> Vec<String> {
+    let paragraphs: Vec<String> = tweet
+        .lines()
+        .collect::<Vec<&str>>()
+        .split(|line| line.trim().is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.join("\n").trim().to_string())
+        .collect();
+
+    if paragraphs.len() < 2 {
+        return vec![tweet.to_string()];
+    }
+    paragraphs
+}
+
+/// Pulls the `![alt](path)` lines out of a part. Text without them is returned unchanged.
+fn take_inline_images(part: &str) -> (String, Vec<Image>) {
+    let mut images = vec![];
+    let mut lines = vec![];
+    for line in part.lines() {
+        match parse_inline_image(line) {
+            Some(image) => images.push(image),
+            None => lines.push(line),
+        }
+    }
+
+    if images.is_empty() {
+        return (part.to_string(), images);
+    }
+    (lines.join("\n").trim().to_string(), images)
+}
+
+fn parse_inline_image(line: &str) -> Option<Image> {
+    let inner = line.trim().strip_prefix("![")?.strip_suffix(')')?;
+    let (alt, path) = inner.split_once("](")?;
+    let path = path.trim();
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(Image {
+        path: path.into(),
+        alt: (!alt.trim().is_empty()).then(|| alt.trim().to_string()),
+    })
+}
+
 pub trait TwitterApi {
-    fn create(&mut self) -> Result<Response<TweetCreateResponse>, CreateTweetErr>;
+    fn create(&mut self) -> Result<Response<TweetCreateResponse>, TwitterError>;
 }
 
 #[derive(Default)]
-pub struct Tweet<'t> {
+pub struct Tweet {
     previous_tweet: Option<String>,
-    separator: &'t str,
     payload: TweetBody,
     tweet_parts: Vec<String>,
+    auto_split: bool,
+    format: ThreadFormat,
+    /// The media of every part, once uploaded.
+    part_media: Option<Vec<Option<Media>>>,
+    /// Set when continuing a thread recorded in [`Threads`].
+    thread_id: Option<i64>,
+    first_part: usize,
+}
+
+/// One tweet of a thread, with the images given on its `![alt](path)` lines.
+#[derive(Debug, PartialEq)]
+pub struct ThreadPart {
+    pub text: String,
+    pub images: Vec<Image>,
 }
 
 #[derive(Debug)]
@@ -74,35 +251,205 @@ pub struct DeleteTweet {
     tweet_id: String,
 }
 
-impl<'t> FromStr for Tweet<'t> {
-    type Err = CreateTweetErr;
+impl FromStr for Tweet {
+    type Err = TwitterError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self {
             previous_tweet: None,
-            separator: "---",
             payload: TweetBody {
                 text: Some(s.to_string()),
                 reply: None,
                 media: None,
+                quote_tweet_id: None,
+                poll: None,
             },
             tweet_parts: vec![],
+            auto_split: false,
+            format: ThreadFormat::default(),
+            part_media: None,
+            thread_id: None,
+            first_part: 0,
         })
     }
 }
 
-impl<'t> Tweet<'t> {
+impl Tweet {
     pub fn new(payload: TweetBody) -> Self {
         Self {
             previous_tweet: None,
-            separator: "---",
             payload,
             tweet_parts: vec![],
+            auto_split: false,
+            format: ThreadFormat::default(),
+            part_media: None,
+            thread_id: None,
+            first_part: 0,
         }
     }
 
+    /// Continues a thread that stopped part way, from the first part that is not live yet.
+    pub fn resume(thread_id: i64) -> Result<Self, TwitterError> {
+        Self::resume_from(&Threads::default(), thread_id)
+    }
+
+    /// Like [`Tweet::resume`], with the progress read from `threads`.
+    pub(crate) fn resume_from(threads: &Threads, thread_id: i64) -> Result<Self, TwitterError> {
+        let Some(progress) = threads.get(thread_id)? else {
+            return Err(TwitterError::local(format!(
+                "No unfinished thread {thread_id}. It may already be fully posted."
+            )));
+        };
+
+        // The rest of the thread replies from the account that posted its live parts.
+        utils::use_account(progress.account);
+        let mut tweet = Self::new(progress.payload);
+        tweet.previous_tweet = progress.posted_ids.last().cloned();
+        tweet.first_part = progress.posted_ids.len();
+        tweet.tweet_parts = progress.parts;
+        tweet.part_media = Some(progress.part_media);
+        tweet.thread_id = Some(progress.id);
+        Ok(tweet)
+    }
+
+    /// The `--resume` handle of a thread that `create` left part way posted.
+    pub fn unfinished_thread(&self) -> Option<i64> {
+        self.thread_id
+    }
+
+    /// Cuts and marks up parts as `format` says instead of the defaults.
+    pub fn format(mut self, format: ThreadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Splits parts that are over the length limit at sentence or word boundaries.
+    pub fn auto_split(mut self, auto_split: bool) -> Self {
+        self.auto_split = auto_split;
+        self
+    }
+
+    /// The tweets `create` would post, in order.
+    pub fn parts(&self) -> Vec<String> {
+        self.thread().into_iter().map(|part| part.text).collect()
+    }
+
+    /// The tweets `create` would post, in order, with their inline images. Images stay with
+    /// the first tweet cut from their part when `auto_split` breaks it up.
+    pub fn thread(&self) -> Vec<ThreadPart> {
+        let (texts, images): (Vec<String>, Vec<Vec<Image>>) = self
+            .sections()
+            .into_iter()
+            .map(|part| (part.text, part.images))
+            .unzip();
+
+        let parts = if self.auto_split {
+            text::split_thread_indexed(&texts, &self.format)
+        } else {
+            text::decorate(texts, &self.format)
+                .into_iter()
+                .enumerate()
+                .collect()
+        };
+
+        let mut images: Vec<Option<Vec<Image>>> = images.into_iter().map(Some).collect();
+        parts
+            .into_iter()
+            .map(|(source, text)| ThreadPart {
+                text,
+                images: images[source].take().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// The parts as written, cut at separators or blank lines, before `auto_split`, counters
+    /// and the continuation marker are applied.
+    pub fn sections(&self) -> Vec<ThreadPart> {
+        let text = self.payload.text.clone().unwrap_or_default();
+        let sections = if self.format.paragraphs {
+            split_paragraphs(&text)
+        } else if self.is_thread(&text) {
+            self.split_tweet(&text, &self.format.separator)
+        } else {
+            vec![text]
+        };
+
+        sections
+            .iter()
+            .map(|section| {
+                let (text, images) = take_inline_images(section);
+                ThreadPart { text, images }
+            })
+            .collect()
+    }
+
+    /// Uploads the images of every part, with `images` going to the first tweet ahead of its
+    /// inline ones. Every file is checked first, so a bad image stops the thread before
+    /// anything is posted.
+    pub fn upload_media(
+        &mut self,
+        images: Vec<Image>,
+        options: &MediaOptions,
+    ) -> Result<(), TwitterError> {
+        let part_media: Vec<Option<Media>> =
+            upload_thread_images(self.part_images(images), options)?
+                .into_iter()
+                .map(|media_ids| (!media_ids.is_empty()).then_some(Media { media_ids }))
+                .collect();
+        self.payload.media = part_media.first().cloned().flatten();
+        self.part_media = Some(part_media);
+        Ok(())
+    }
+
+    /// Checks the files `upload_media` would upload, without uploading them.
+    pub fn check_media(
+        &self,
+        images: Vec<Image>,
+        options: &MediaOptions,
+    ) -> Result<(), TwitterError> {
+        check_thread_images(&self.part_images(images), options)
+    }
+
+    fn part_images(&self, images: Vec<Image>) -> Vec<Vec<Image>> {
+        let mut part_images: Vec<Vec<Image>> =
+            self.thread().into_iter().map(|part| part.images).collect();
+        if let Some(first) = part_images.first_mut() {
+            first.splice(0..0, images);
+        }
+        part_images
+    }
+
+    /// Fails if any part is over the length limit, before anything is sent.
+    pub fn check_length(&self) -> Result<(), TwitterError> {
+        self.format.validate()?;
+        let parts = self.parts();
+        let Some(part) = text::measure(&parts)
+            .into_iter()
+            .find(PartLength::is_too_long)
+        else {
+            return Ok(());
+        };
+
+        let which = if parts.len() > 1 {
+            format!("Tweet {}/{}", part.part, parts.len())
+        } else {
+            "The tweet".to_string()
+        };
+        Err(TwitterError::local(format!(
+            "{which} is {} characters long, over the {} limit. Shorten it or pass --auto-split.",
+            part.length, part.max
+        )))
+    }
+
+    /// Whether any tweet went live, so retrying from scratch would post duplicates.
+    pub fn is_partially_posted(&self) -> bool {
+        self.previous_tweet.is_some()
+    }
+
     fn is_thread(&self, tweet: &str) -> bool {
-        tweet.lines().any(|line| line.trim() == self.separator)
+        tweet
+            .lines()
+            .any(|line| line.trim() == self.format.separator)
     }
 
     pub fn split_tweet(&self, tweet: &str, separator: &str) -> Vec<String> {
@@ -114,16 +461,16 @@ impl<'t> Tweet<'t> {
             .collect()
     }
 
-    fn send(&mut self, index: Option<usize>) -> Result<TweetCreateResponse, CreateTweetErr> {
-        let url = "https://api.twitter.com/2/tweets";
-        let auth_header = oauth_post_header(url, &());
+    fn send(&mut self, index: Option<usize>) -> Result<TweetCreateResponse, TwitterError> {
+        let url = api().url("/2/tweets");
         let media = self.payload.media.clone();
-        let mut reply = None;
-        if self.previous_tweet.is_some() {
-            reply = Some(Reply {
-                in_reply_to_tweet_id: self.previous_tweet.clone().unwrap(),
-            });
-        }
+        // The first tweet keeps the reply it was given; the rest of a thread answers the one before.
+        let reply = match &self.previous_tweet {
+            Some(previous_tweet) => Some(Reply {
+                in_reply_to_tweet_id: previous_tweet.clone(),
+            }),
+            None => self.payload.reply.clone(),
+        };
 
         let mut tweet_text: String = self.payload.text.clone().unwrap_or_default();
 
@@ -136,34 +483,23 @@ impl<'t> Tweet<'t> {
         }
 
         let new_tweet = TweetBody {
-            text: Some(tweet_text),
+            // A part made only of images is posted without text.
+            text: (!tweet_text.is_empty() || media.is_none()).then_some(tweet_text),
             reply,
             media,
+            quote_tweet_id: self.payload.quote_tweet_id.clone(),
+            poll: self.payload.poll.clone(),
         };
 
-        let body = serde_json::to_string(&new_tweet).map_err(|e| CreateTweetErr {
-            message: e.to_string(),
-        })?;
+        let body = serde_json::to_string(&new_tweet).map_err(TwitterError::local)?;
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .post()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
+            .authorization(|| oauth_post_header(url.as_str(), &()))
             .body_json(body)
-            .send(url)
-            .map_err(|e| CreateTweetErr {
-                message: e.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let res_data: TweetCreateResponse =
-                serde_json::from_slice(&response.body).map_err(|_| CreateTweetErr {
-                    message: "Invalid response body.".into(),
-                })?;
-            Ok(res_data)
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(CreateTweetErr { message: err_data })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response).map(|res| res.content)
     }
 }
 
@@ -174,47 +510,31 @@ impl DeleteTweet {
         }
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/tweets/{}", self.tweet_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/tweets/{}", self.tweet_id))
     }
 
-    pub fn send(&self) -> Result<Response<DeleteTweetResponse>, DeleteTweetErr> {
-        let url = self.url();
-        let auth_header = oauth_post_header(url.as_str(), &());
+    pub fn send(&self) -> Result<Response<DeleteTweetResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .delete()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|e| DeleteTweetErr {
-                message: e.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let res_data: DeleteTweetResponse =
-                serde_json::from_slice(&response.body).map_err(|e| DeleteTweetErr {
-                    message: e.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: res_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(DeleteTweetErr { message: err_data })
-        }
+            .authorization(|| oauth_post_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
-impl<'t> TwitterApi for Tweet<'t> {
-    fn create(&mut self) -> Result<Response<TweetCreateResponse>, CreateTweetErr> {
-        let text = self.payload.text.clone().unwrap_or_default();
+impl TwitterApi for Tweet {
+    fn create(&mut self) -> Result<Response<TweetCreateResponse>, TwitterError> {
         let tweet_data = TweetData {
             text: "".to_string(),
             edit_history_tweet_ids: vec![],
             id: 0.to_string(),
             author_id: None,
             created_at: None,
+            attachments: None,
         };
         let content = TweetCreateResponse {
             data: tweet_data,
@@ -225,23 +545,83 @@ impl<'t> TwitterApi for Tweet<'t> {
             content,
         };
 
-        if self.is_thread(&text) {
-            let parts = self.split_tweet(&text, self.separator);
-            self.tweet_parts = parts.clone();
+        let parts = if self.thread_id.is_some() {
+            self.tweet_parts.clone()
+        } else {
+            self.check_length()?;
+            self.parts()
+        };
+        if self.part_media.is_none() {
+            self.upload_media(vec![], &MediaOptions::default())?;
+        }
+        let part_media = self.part_media.clone().unwrap_or_default();
+        if parts.len() > 1 {
             let num_of_tweets = parts.len();
-            for index in 0..num_of_tweets {
-                // Only attach media to the first tweet
+            self.tweet_parts = parts;
+
+            // Progress is saved part by part so a failure in the middle can be resumed.
+            let threads = (!dry_run()).then(Threads::default);
+            if let (Some(threads), None) = (&threads, self.thread_id) {
+                self.thread_id = Some(threads.start(
+                    &self.payload,
+                    &self.tweet_parts,
+                    &part_media,
+                    utils::load_config().current_account,
+                )?);
+            }
+
+            for index in self.first_part..num_of_tweets {
+                // Every part carries its own media. The quote and poll go on the first tweet only
                 if index > 0 {
-                    self.payload.media = None
+                    self.payload.media = part_media.get(index).cloned().flatten();
+                    self.payload.quote_tweet_id = None;
+                    self.payload.poll = None;
                 }
                 println!("> Sending tweet {}/{}", index + 1, num_of_tweets);
-                let res = self.send(Some(index))?;
+                let res = match self.send(Some(index)) {
+                    Ok(res) => res,
+                    // Keep going so a dry run prints every part, replying to a placeholder.
+                    Err(err) if err.is_dry_run() => {
+                        self.previous_tweet = Some(format!("<tweet {}>", index + 1));
+                        continue;
+                    }
+                    Err(err) => {
+                        // Nothing is live, so there is nothing to resume.
+                        if let (Some(threads), Some(thread_id), 0) =
+                            (&threads, self.thread_id, index)
+                        {
+                            threads.finished(thread_id);
+                            self.thread_id = None;
+                        } else if let (Some(threads), Some(thread_id)) = (&threads, self.thread_id)
+                        {
+                            threads.failed(thread_id, &err);
+                            eprintln!(
+                                "> {index} of {num_of_tweets} tweets are live. Resume from tweet {} with: twitter tweet --resume {thread_id}",
+                                index + 1
+                            );
+                        }
+                        return Err(err);
+                    }
+                };
                 let tweet_id = &res.data.id;
+                if let (Some(threads), Some(thread_id)) = (&threads, self.thread_id)
+                    && let Err(err) = threads.posted(thread_id, tweet_id)
+                {
+                    eprintln!("{err}");
+                }
                 self.previous_tweet = Some(tweet_id.to_string());
                 response.content = res;
             }
+
+            if let (Some(threads), Some(thread_id)) = (&threads, self.thread_id) {
+                threads.finished(thread_id);
+            }
+            if dry_run() {
+                return Err(TwitterError::DryRun);
+            }
         } else {
-            let res = self.send(None)?;
+            self.tweet_parts = parts;
+            let res = self.send(Some(0))?;
             response.content = res;
         }
 
@@ -252,6 +632,10 @@ impl<'t> TwitterApi for Tweet<'t> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::twitter::{
+        markdown::{self, Emphasis},
+        text::Numbering,
+    };
 
     #[test]
     fn test_split_tweet() {
@@ -287,10 +671,175 @@ mod tests {
         assert!(!tweet_2.is_thread(&tweet));
     }
 
+    #[test]
+    fn test_parse_tweet_id_accepts_ids_and_urls() {
+        for value in [
+            "1460323737035677698",
+            "https://x.com/jack/status/1460323737035677698",
+            "https://twitter.com/jack/status/1460323737035677698?s=20&t=abc",
+            "https://mobile.twitter.com/i/web/status/1460323737035677698/",
+        ] {
+            assert_eq!(parse_tweet_id(value).unwrap(), "1460323737035677698");
+        }
+
+        assert!(parse_tweet_id("https://x.com/jack").is_err());
+        assert!(parse_tweet_id("https://x.com/jack/status/photo").is_err());
+    }
+
+    #[test]
+    fn test_quote_tweet_id_is_only_sent_when_set() {
+        let mut body = TweetBody::from_str("hello").unwrap();
+        assert_eq!(serde_json::to_string(&body).unwrap(), r#"{"text":"hello"}"#);
+
+        body.quote_tweet_id = Some("42".to_string());
+        assert_eq!(
+            serde_json::to_string(&body).unwrap(),
+            r#"{"text":"hello","quote_tweet_id":"42"}"#
+        );
+    }
+
+    #[test]
+    fn test_poll_limits_are_checked_locally() {
+        let options = |options: &[&str]| options.iter().map(|o| o.to_string()).collect();
+
+        assert!(Poll::new(options(&["Yes", "No"]), 60).is_ok());
+        assert!(Poll::new(options(&["Yes"]), 60).is_err());
+        assert!(Poll::new(options(&["a", "b", "c", "d", "e"]), 60).is_err());
+        assert!(Poll::new(options(&["Yes", &"n".repeat(26)]), 60).is_err());
+        assert!(Poll::new(options(&["Yes", " "]), 60).is_err());
+        assert!(Poll::new(options(&["Yes", "No"]), MIN_POLL_MINUTES - 1).is_err());
+        assert!(Poll::new(options(&["Yes", "No"]), MAX_POLL_MINUTES + 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_poll_duration() {
+        assert_eq!(parse_poll_duration("90"), Ok(90));
+        assert_eq!(parse_poll_duration("45m"), Ok(45));
+        assert_eq!(parse_poll_duration("6h"), Ok(360));
+        assert_eq!(parse_poll_duration("1d12h"), Ok(2160));
+        assert!(parse_poll_duration("1w").is_err());
+        assert!(parse_poll_duration("12").is_ok());
+        assert!(parse_poll_duration("h").is_err());
+        assert!(parse_poll_duration("3d4").is_err());
+    }
+
+    #[test]
+    fn test_parts_are_auto_split_and_numbered() {
+        let long = "word ".repeat(70);
+        let tweet = Tweet::new(TweetBody::from_str(&format!("intro\n---\n{long}")).unwrap());
+
+        assert_eq!(tweet.parts().len(), 2);
+
+        let parts = tweet
+            .auto_split(true)
+            .format(ThreadFormat {
+                numbering: Numbering::Suffix,
+                ..Default::default()
+            })
+            .parts();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], "intro 1/3");
+        assert!(parts[2].ends_with(" 3/3"));
+    }
+
+    #[test]
+    fn test_thread_format_changes_how_parts_are_cut() {
+        let text = "Intro\n---\nstill intro\n\nSecond\n===\nThird";
+        let tweet = |format: ThreadFormat| {
+            Tweet::new(TweetBody::from_str(text).unwrap())
+                .format(format)
+                .parts()
+        };
+
+        let separator = tweet(ThreadFormat {
+            separator: "===".to_string(),
+            numbering: Numbering::Suffix,
+            ..Default::default()
+        });
+        assert_eq!(
+            separator,
+            vec!["Intro\n---\nstill intro\n\nSecond 1/2", "Third 2/2"]
+        );
+
+        let paragraphs = tweet(ThreadFormat {
+            paragraphs: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            paragraphs,
+            vec!["Intro\n---\nstill intro", "Second\n===\nThird"]
+        );
+    }
+
+    #[test]
+    fn test_markdown_sections_stay_whole() {
+        let sections = markdown::render(
+            "# One\n\nFirst paragraph.\n\nSecond paragraph.\n\n```\n---\n```\n\n# Two\n\nLast.",
+            Emphasis::default(),
+        );
+        let mut format = ThreadFormat {
+            paragraphs: true,
+            ..Default::default()
+        };
+        let text = format.join_exactly(&sections);
+        let parts = Tweet::new(TweetBody::from_str(&text).unwrap())
+            .format(format)
+            .parts();
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts, sections);
+    }
+
+    #[test]
+    fn test_inline_images_stay_with_their_part() {
+        let long = "word ".repeat(70);
+        let text = format!("intro\n---\n![A chart](chart.png)\n{long}\n![](logo.png)");
+        let thread = Tweet::new(TweetBody::from_str(&text).unwrap())
+            .auto_split(true)
+            .thread();
+
+        assert_eq!(thread.len(), 3);
+        assert_eq!(thread[0].text, "intro");
+        assert!(thread[0].images.is_empty());
+        assert_eq!(
+            thread[1].images,
+            vec![
+                Image {
+                    path: "chart.png".into(),
+                    alt: Some("A chart".to_string())
+                },
+                Image {
+                    path: "logo.png".into(),
+                    alt: None
+                },
+            ]
+        );
+        assert!(!thread[1].text.contains("!["));
+        assert!(thread[2].images.is_empty());
+    }
+
+    #[test]
+    fn test_image_syntax_inside_a_line_is_text() {
+        let text = "See ![this](a.png) inline";
+
+        assert_eq!(take_inline_images(text), (text.to_string(), vec![]));
+    }
+
+    #[test]
+    fn test_create_rejects_long_tweets_before_sending() {
+        let mut tweet = Tweet::new(TweetBody::from_str(&"a".repeat(281)).unwrap());
+
+        let err = tweet.create().err().unwrap();
+        assert!(err.to_string().contains("281 characters long"));
+    }
+
     #[test]
     fn test_delete_tweet_url_uses_tweet_id() {
         let endpoint = DeleteTweet::new("123");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/tweets/123");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/tweets/123"
+        );
     }
 }
@@ -1,18 +1,15 @@
 use crate::{
     twitter::{
-        AUTHOR_EXPANSION, Includes, Response, TWEET_FIELDS, TweetCreateResponse, TweetData,
-        USER_FIELDS,
+        AUTHOR_AND_POLL_EXPANSIONS, AUTHOR_EXPANSION, Includes, POLL_FIELDS, Response,
+        TWEET_FIELDS, TweetCreateResponse, TweetData, USER_FIELDS,
+        client::{ApiClient, api},
+        error::TwitterError,
     },
     utils::{bearer_auth_header, oauth_get_header},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-#[derive(Debug, Deserialize)]
-pub struct TweetLookupError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct TweetLookup {
     tweet_id: String,
@@ -23,7 +20,7 @@ pub struct TweetsLookup {
     tweet_ids: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct RecentTweetsMeta {
     #[allow(dead_code)]
     pub newest_id: Option<String>,
@@ -35,7 +32,7 @@ pub struct RecentTweetsMeta {
     pub next_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct RecentTweetsResponse {
     pub data: Vec<TweetData>,
     #[serde(default)]
@@ -44,25 +41,20 @@ pub struct RecentTweetsResponse {
     pub meta: Option<RecentTweetsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct RecentTweetsError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct RecentTweets {
     query: String,
     max_results: u8,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct TweetCount {
     pub start: String,
     pub end: String,
     pub tweet_count: u64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct TweetCountsMeta {
     #[allow(dead_code)]
     pub total_tweet_count: Option<u64>,
@@ -70,18 +62,13 @@ pub struct TweetCountsMeta {
     pub next_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct TweetCountsResponse {
     pub data: Vec<TweetCount>,
     #[allow(dead_code)]
     pub meta: Option<TweetCountsMeta>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct TweetCountsError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct RecentTweetCounts {
     query: String,
@@ -111,46 +98,33 @@ impl TweetLookup {
         }
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/tweets/{}", self.tweet_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/tweets/{}", self.tweet_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<TweetCreateResponse>, TweetLookupError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<TweetCreateResponse>, TwitterError> {
+        let url = self.url(api());
         let tweet_fields = TWEET_FIELDS.to_string();
         let user_fields = USER_FIELDS.to_string();
-        let expansions = AUTHOR_EXPANSION.to_string();
+        let poll_fields = POLL_FIELDS.to_string();
+        let expansions = AUTHOR_AND_POLL_EXPANSIONS.to_string();
         let auth_params = oauth::ParameterList::new([
             ("tweet.fields", &tweet_fields as &dyn std::fmt::Display),
             ("user.fields", &user_fields as &dyn std::fmt::Display),
+            ("poll.fields", &poll_fields as &dyn std::fmt::Display),
             ("expansions", &expansions as &dyn std::fmt::Display),
         ]);
-        let auth_header = oauth_get_header(url.as_str(), &auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("tweet.fields", tweet_fields.as_str())
             .query_param_kv("user.fields", user_fields.as_str())
+            .query_param_kv("poll.fields", poll_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| TweetLookupError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweet_data: TweetCreateResponse =
-                serde_json::from_slice(&response.body).map_err(|err| TweetLookupError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweet_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(TweetLookupError { message: err_data })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -159,12 +133,12 @@ impl TweetsLookup {
         Self { tweet_ids }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets")
     }
 
-    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, RecentTweetsError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, TwitterError> {
+        let url = self.url(api());
         let ids = self.tweet_ids.join(",");
         let tweet_fields = TWEET_FIELDS.to_string();
         let user_fields = USER_FIELDS.to_string();
@@ -175,33 +149,17 @@ impl TweetsLookup {
             ("user.fields", &user_fields as &dyn Display),
             ("expansions", &expansions as &dyn Display),
         ]);
-        let auth_header = oauth_get_header(url, &auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("ids", ids.as_str())
             .query_param_kv("tweet.fields", tweet_fields.as_str())
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url)
-            .map_err(|err| RecentTweetsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweets_data: RecentTweetsResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| RecentTweetsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweets_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(RecentTweetsError { message: err_data })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -218,12 +176,12 @@ impl RecentTweets {
         self
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets/search/recent"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets/search/recent")
     }
 
-    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, RecentTweetsError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, TwitterError> {
+        let url = self.url(api());
         let query = self.query.as_str();
         let max_results = self.max_results;
         let max_results_query = max_results.to_string();
@@ -232,7 +190,7 @@ impl RecentTweets {
         let expansions = AUTHOR_EXPANSION.to_string();
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("query", query)
             .query_param_kv("max_results", max_results_query.as_str())
@@ -240,24 +198,9 @@ impl RecentTweets {
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url)
-            .map_err(|err| RecentTweetsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweets_data: RecentTweetsResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| RecentTweetsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweets_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(RecentTweetsError { message: err_data })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -268,38 +211,22 @@ impl RecentTweetCounts {
         }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets/counts/recent"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets/counts/recent")
     }
 
-    pub fn fetch(&self) -> Result<Response<TweetCountsResponse>, TweetCountsError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<TweetCountsResponse>, TwitterError> {
+        let url = self.url(api());
         let query = self.query.as_str();
         let auth_params = oauth::ParameterList::new([("query", &query as &dyn Display)]);
-        let auth_header = oauth_get_header(url, &auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("query", query)
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url)
-            .map_err(|err| TweetCountsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweets_data: TweetCountsResponse =
-                serde_json::from_slice(&response.body).map_err(|err| TweetCountsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweets_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(TweetCountsError { message: err_data })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -316,12 +243,12 @@ impl AllTweets {
         self
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets/search/all"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets/search/all")
     }
 
-    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, RecentTweetsError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, TwitterError> {
+        let url = self.url(api());
         let query = self.query.as_str();
         let max_results_query = self.max_results.to_string();
         let tweet_fields = TWEET_FIELDS.to_string();
@@ -329,7 +256,7 @@ impl AllTweets {
         let expansions = AUTHOR_EXPANSION.to_string();
         let authorization = bearer_auth_header();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("query", query)
             .query_param_kv("max_results", max_results_query.as_str())
@@ -337,24 +264,9 @@ impl AllTweets {
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
             .header(curl_rest::Header::Authorization(authorization.into()))
-            .send(url)
-            .map_err(|err| RecentTweetsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweets_data: RecentTweetsResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| RecentTweetsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweets_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(RecentTweetsError { message: err_data })
-        }
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -365,38 +277,22 @@ impl AllTweetCounts {
         }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/tweets/counts/all"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/tweets/counts/all")
     }
 
-    pub fn fetch(&self) -> Result<Response<TweetCountsResponse>, TweetCountsError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<TweetCountsResponse>, TwitterError> {
+        let url = self.url(api());
         let query = self.query.as_str();
         let auth_params = oauth::ParameterList::new([("query", &query as &dyn Display)]);
-        let auth_header = oauth_get_header(url, &auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("query", query)
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url)
-            .map_err(|err| TweetCountsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweets_data: TweetCountsResponse =
-                serde_json::from_slice(&response.body).map_err(|err| TweetCountsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweets_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(TweetCountsError { message: err_data })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -413,12 +309,12 @@ impl UserTweets {
         self
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}/tweets", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}/tweets", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, RecentTweetsError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<RecentTweetsResponse>, TwitterError> {
+        let url = self.url(api());
         let max_results = self.max_results;
         let tweet_fields = TWEET_FIELDS.to_string();
         let user_fields = USER_FIELDS.to_string();
@@ -429,34 +325,18 @@ impl UserTweets {
             ("user.fields", &user_fields as &dyn Display),
             ("expansions", &expansions as &dyn Display),
         ]);
-        let auth_header = oauth_get_header(url.as_str(), &auth_params);
         let max_results_query = max_results.to_string();
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("max_results", max_results_query.as_str())
             .query_param_kv("tweet.fields", tweet_fields.as_str())
             .query_param_kv("user.fields", user_fields.as_str())
             .query_param_kv("expansions", expansions.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| RecentTweetsError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let tweets_data: RecentTweetsResponse = serde_json::from_slice(&response.body)
-                .map_err(|err| RecentTweetsError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: tweets_data,
-            })
-        } else {
-            let err_data = String::from_utf8_lossy(&response.body).to_string();
-            Err(RecentTweetsError { message: err_data })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -486,20 +366,29 @@ mod tests {
     fn test_tweets_lookup_url_uses_collection_endpoint() {
         let endpoint = TweetsLookup::new(vec!["1".to_string(), "2".to_string()]);
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/tweets");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/tweets"
+        );
     }
 
     #[test]
     fn test_recent_tweet_counts_url_uses_recent_counts_endpoint() {
         let endpoint = RecentTweetCounts::new("rustlang");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/tweets/counts/recent");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/tweets/counts/recent"
+        );
     }
 
     #[test]
     fn test_all_tweet_counts_url_uses_all_counts_endpoint() {
         let endpoint = AllTweetCounts::new("rustlang");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/tweets/counts/all");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/tweets/counts/all"
+        );
     }
 }
@@ -1,26 +1,28 @@
 use std::fmt::Display;
 
-use serde::Deserialize;
-
-use crate::{twitter::Response, utils::oauth_get_header};
-
-#[derive(Debug, Deserialize)]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    twitter::{
+        Response,
+        client::{ApiClient, api},
+        error::TwitterError,
+    },
+    utils::oauth_get_header,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UserData {
     pub id: String,
     pub name: String,
     pub username: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CurrentUserResponse {
     pub data: UserData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CurrentUserError {
-    pub message: String,
-}
-
 #[derive(Debug)]
 pub struct UserLookup {
     user_id: String,
@@ -41,27 +43,17 @@ pub struct UsersLookupByUsernames {
     usernames: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UserLookupResponse {
     pub data: UserData,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct UserLookupError {
-    pub message: String,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UsersLookupResponse {
     #[serde(default)]
     pub data: Vec<UserData>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct UsersLookupError {
-    pub message: String,
-}
-
 impl Display for CurrentUserResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -108,36 +100,19 @@ impl UserLookup {
         }
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/{}", self.user_id)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/{}", self.user_id))
     }
 
-    pub fn fetch(&self) -> Result<Response<UserLookupResponse>, UserLookupError> {
-        let url = self.url();
-        let auth_header = oauth_get_header(url.as_str(), &());
+    pub fn fetch(&self) -> Result<Response<UserLookupResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| UserLookupError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let user_data: UserLookupResponse =
-                serde_json::from_slice(&response.body).map_err(|err| UserLookupError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: user_data,
-            })
-        } else {
-            Err(UserLookupError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -146,39 +121,22 @@ impl UsersLookup {
         Self { user_ids }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/users"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/users")
     }
 
-    pub fn fetch(&self) -> Result<Response<UsersLookupResponse>, UsersLookupError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<UsersLookupResponse>, TwitterError> {
+        let url = self.url(api());
         let ids = self.user_ids.join(",");
         let auth_params = oauth::ParameterList::new([("ids", &ids as &dyn Display)]);
-        let auth_header = oauth_get_header(url, &auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("ids", ids.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url)
-            .map_err(|err| UsersLookupError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let user_data: UsersLookupResponse =
-                serde_json::from_slice(&response.body).map_err(|err| UsersLookupError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: user_data,
-            })
-        } else {
-            Err(UsersLookupError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -189,36 +147,19 @@ impl UserLookupByUsername {
         }
     }
 
-    fn url(&self) -> String {
-        format!("https://api.x.com/2/users/by/username/{}", self.username)
+    fn url(&self, client: &ApiClient) -> String {
+        client.url(&format!("/2/users/by/username/{}", self.username))
     }
 
-    pub fn fetch(&self) -> Result<Response<UserLookupResponse>, UserLookupError> {
-        let url = self.url();
-        let auth_header = oauth_get_header(url.as_str(), &());
+    pub fn fetch(&self) -> Result<Response<UserLookupResponse>, TwitterError> {
+        let url = self.url(api());
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url.as_str())
-            .map_err(|err| UserLookupError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let user_data: UserLookupResponse =
-                serde_json::from_slice(&response.body).map_err(|err| UserLookupError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: user_data,
-            })
-        } else {
-            Err(UserLookupError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &()))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
@@ -227,68 +168,34 @@ impl UsersLookupByUsernames {
         Self { usernames }
     }
 
-    fn url(&self) -> &'static str {
-        "https://api.x.com/2/users/by"
+    fn url(&self, client: &ApiClient) -> String {
+        client.url("/2/users/by")
     }
 
-    pub fn fetch(&self) -> Result<Response<UsersLookupResponse>, UsersLookupError> {
-        let url = self.url();
+    pub fn fetch(&self) -> Result<Response<UsersLookupResponse>, TwitterError> {
+        let url = self.url(api());
         let usernames = self.usernames.join(",");
         let auth_params = oauth::ParameterList::new([("usernames", &usernames as &dyn Display)]);
-        let auth_header = oauth_get_header(url, &auth_params);
 
-        let response = curl_rest::Client::default()
+        let response = api()
             .get()
             .query_param_kv("usernames", usernames.as_str())
-            .header(curl_rest::Header::Authorization(auth_header.into()))
-            .send(url)
-            .map_err(|err| UsersLookupError {
-                message: err.to_string(),
-            })?;
-
-        if (200..300).contains(&response.status.as_u16()) {
-            let user_data: UsersLookupResponse =
-                serde_json::from_slice(&response.body).map_err(|err| UsersLookupError {
-                    message: err.to_string(),
-                })?;
-            Ok(Response {
-                status: response.status.as_u16(),
-                content: user_data,
-            })
-        } else {
-            Err(UsersLookupError {
-                message: String::from_utf8_lossy(&response.body).to_string(),
-            })
-        }
+            .authorization(|| oauth_get_header(url.as_str(), &auth_params))
+            .send(url.as_str())?;
+
+        Response::decode(response)
     }
 }
 
-pub fn me() -> Result<Response<CurrentUserResponse>, CurrentUserError> {
-    let url = "https://api.x.com/2/users/me";
-    let auth_header = oauth_get_header(url, &());
+pub fn me() -> Result<Response<CurrentUserResponse>, TwitterError> {
+    let url = api().url("/2/users/me");
 
-    let response = curl_rest::Client::default()
+    let response = api()
         .get()
-        .header(curl_rest::Header::Authorization(auth_header.into()))
-        .send(url)
-        .map_err(|err| CurrentUserError {
-            message: err.to_string(),
-        })?;
-
-    if (200..300).contains(&response.status.as_u16()) {
-        let user_data: CurrentUserResponse =
-            serde_json::from_slice(&response.body).map_err(|err| CurrentUserError {
-                message: err.to_string(),
-            })?;
-        Ok(Response {
-            status: response.status.as_u16(),
-            content: user_data,
-        })
-    } else {
-        Err(CurrentUserError {
-            message: String::from_utf8_lossy(&response.body).to_string(),
-        })
-    }
+        .authorization(|| oauth_get_header(url.as_str(), &()))
+        .send(url.as_str())?;
+
+    Response::decode(response)
 }
 
 #[cfg(test)]
@@ -299,14 +206,20 @@ mod tests {
     fn test_user_lookup_url_uses_user_id() {
         let endpoint = UserLookup::new("123");
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/123");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/123"
+        );
     }
 
     #[test]
     fn test_users_lookup_url_uses_collection_endpoint() {
         let endpoint = UsersLookup::new(vec!["123".to_string(), "456".to_string()]);
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users"
+        );
     }
 
     #[test]
@@ -337,7 +250,7 @@ mod tests {
         let endpoint = UserLookupByUsername::new("janedoe");
 
         assert_eq!(
-            endpoint.url(),
+            endpoint.url(&ApiClient::new("https://api.x.com")),
             "https://api.x.com/2/users/by/username/janedoe"
         );
     }
@@ -347,6 +260,9 @@ mod tests {
         let endpoint =
             UsersLookupByUsernames::new(vec!["janedoe".to_string(), "johndoe".to_string()]);
 
-        assert_eq!(endpoint.url(), "https://api.x.com/2/users/by");
+        assert_eq!(
+            endpoint.url(&ApiClient::new("https://api.x.com")),
+            "https://api.x.com/2/users/by"
+        );
     }
 }
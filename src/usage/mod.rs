@@ -1,13 +1,17 @@
-use crate::utils::load_config;
-use serde::Deserialize;
+use crate::{
+    cli::output::OutputFormat,
+    twitter::{Response, client::api},
+    utils::{exit_with_error, load_config},
+};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct OkResponse {
     data: TweetUsage,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct TweetUsage {
     project_usage: String,
     project_cap: String,
@@ -23,33 +27,25 @@ impl Display for OkResponse {
     }
 }
 
-pub fn show() {
+pub fn show(output: OutputFormat) {
     let mut cfg = load_config();
     let account = cfg.current_account();
     let token = account.bearer_token.as_str();
     let auth_header = format!("Bearer {}", token);
 
-    let response = match curl_rest::Client::default()
+    let response = api()
         .get()
         .query_param_kv("days", "1")
         .header(curl_rest::Header::Authorization(auth_header.into()))
-        .send("https://api.x.com/2/usage/tweets")
-    {
-        Ok(response) => response,
-        Err(err) => {
-            eprintln!("{}", err);
-            return;
-        }
-    };
-
-    let response_text = String::from_utf8_lossy(&response.body).to_string();
-    if (200..300).contains(&response.status.as_u16()) {
-        let usage: OkResponse = serde_json::from_str(&response_text).unwrap();
+        .send(api().url("/2/usage/tweets").as_str())
+        .and_then(Response::<OkResponse>::decode);
 
-        println!("{}", usage);
-    } else if response.status.as_u16() == 429 {
-        eprintln!("You have reached a rate limit. Try again later.")
-    } else {
-        eprintln!("{}", response_text)
+    match response {
+        Ok(usage) => output.print(usage.content, |usage| println!("{usage}")),
+        Err(err) if err.is_rate_limited() => {
+            eprintln!("You have reached a rate limit. Try again later.");
+            exit_with_error(&err)
+        }
+        Err(err) => exit_with_error(&err),
     }
 }
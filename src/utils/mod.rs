@@ -1,26 +1,41 @@
 use std::{
     env::{self, var},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{self, Command, ExitStatus},
     str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
-use dirs::home_dir;
+use jiff::Timestamp;
 use oauth::{HMAC_SHA1, Request, Token};
 use rusqlite::{Connection, params};
 use serde::Deserialize;
 
 use crate::{
     config::{Account, Config},
-    schedule::Schedule,
-    twitter::tweet::{Tweet, TwitterApi},
+    schedule::{
+        Schedule, ScheduleStatus, ScheduledTweet, local_time, retry::ScheduleRetry,
+        runs::ScheduleRuns,
+    },
+    twitter::{
+        Response as TwitterResponse, client::api, error::TwitterError, text::ThreadFormat,
+        tweet::TwitterApi,
+    },
 };
 
 const CACHE_DIR: &str = "twitter-cli";
 const DB_FILENAME: &str = "db.sqlite3";
 const USER_CACHE_TABLE: &str = "account_user_cache";
 
+static ACCOUNT_OVERRIDE: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Acts as another configured account for the rest of the process, e.g. the one a draft
+/// was written for, without changing `current_account` in the config file.
+pub fn use_account(index: usize) {
+    ACCOUNT_OVERRIDE.store(index, Ordering::Relaxed);
+}
+
 #[derive(Deserialize)]
 struct UsersMeResponse {
     data: UsersMeData,
@@ -32,28 +47,58 @@ struct UsersMeData {
 }
 
 pub fn load_config() -> Config {
-    let config_dir = home_dir()
-        .expect("Home dir not found!")
-        .join(".config/twitter_cli/config.toml");
+    let mut cfg = read_config();
+    let account = ACCOUNT_OVERRIDE.load(Ordering::Relaxed);
+    if account != usize::MAX {
+        cfg.current_account = account;
+    }
+    cfg
+}
 
-    let binary_name = var("CARGO_BIN_NAME").unwrap_or("twitter".to_string());
+/// The `current_account` of the config file as it is now, ignoring [`use_account`], so a
+/// long running process follows changes to it.
+pub(crate) fn configured_account() -> usize {
+    read_config().current_account
+}
 
-    let data = match fs::read_to_string(config_dir) {
-        Ok(data) => data,
-        Err(_) => {
+fn read_config() -> Config {
+    match read_config_file() {
+        Some(cfg) => cfg,
+        None => {
+            let binary_name = var("CARGO_BIN_NAME").unwrap_or("twitter".to_string());
             let message =
                 format!("Failed to read the config file.\nPlease run {binary_name} config --init");
             gracefully_exit(&message)
         }
-    };
+    }
+}
+
+/// The config file, or `None` before `config --init` has written it. A malformed file stops
+/// the program.
+fn read_config_file() -> Option<Config> {
+    let data = fs::read_to_string(get_config_file()).ok()?;
     match Config::from_str(&data) {
-        Ok(cfg) => cfg,
-        Err(_) => {
-            gracefully_exit("Failed to load config. Try and run {binary_name} config --init");
-        }
+        Ok(cfg) => Some(cfg),
+        Err(err) => gracefully_exit(&err),
     }
 }
 
+/// The config file for its optional tables, such as `[thread]` and `[schedule_retry]`. Every
+/// table is left out when there is no config file yet.
+pub fn load_optional_config() -> Config {
+    read_config_file().unwrap_or_default()
+}
+
+/// The `[thread]` settings of the config, or the defaults.
+pub fn thread_format() -> ThreadFormat {
+    load_optional_config().thread.unwrap_or_default()
+}
+
+/// The `[schedule_retry]` settings of the config, or the defaults.
+pub fn schedule_retry() -> ScheduleRetry {
+    load_optional_config().schedule_retry.unwrap_or_default()
+}
+
 pub fn get_config_dir() -> PathBuf {
     dirs::home_dir()
         .expect("Home directory missing!")
@@ -66,18 +111,24 @@ pub fn get_config_file() -> PathBuf {
         .join(".config/twitter_cli/config.toml")
 }
 
-pub fn get_current_user_id() -> Result<String, String> {
+pub fn get_current_user_id() -> Result<String, TwitterError> {
     let mut cfg = load_config();
     let account_index = cfg.current_account;
     let account = cfg.current_account();
-    let connection = open_cache_connection()?;
+    let connection = open_cache_connection().map_err(TwitterError::local)?;
 
-    if let Some(cached_user_id) = get_cached_user_id(&connection, account_index)? {
+    if let Some(cached_user_id) =
+        get_cached_user_id(&connection, account_index).map_err(TwitterError::local)?
+    {
         return Ok(cached_user_id);
     }
 
-    let user_id = fetch_user_id(account)?;
-    save_cached_user_id(&connection, account_index, &user_id)?;
+    let user_id = match fetch_user_id(account) {
+        // Nothing is sent in a dry run, so the printed URLs show a placeholder instead.
+        Err(err) if err.is_dry_run() => return Ok(":id".to_string()),
+        user_id => user_id?,
+    };
+    save_cached_user_id(&connection, account_index, &user_id).map_err(TwitterError::local)?;
     Ok(user_id)
 }
 
@@ -108,6 +159,22 @@ where
     oauth_put_header_for_account(account, url, request)
 }
 
+/// Signs a request whose method has no shorthand above, such as `DELETE` or `PATCH`.
+pub fn oauth_header<R>(method: &str, url: &str, request: &R) -> String
+where
+    R: Request + ?Sized,
+{
+    let mut cfg = load_config();
+    let account = cfg.current_account();
+    let token = Token::from_parts(
+        account.consumer_key.as_str(),
+        account.consumer_secret.as_str(),
+        account.access_token.as_str(),
+        account.access_secret.as_str(),
+    );
+    oauth::authorize(method, url, request, &token, HMAC_SHA1)
+}
+
 pub fn bearer_auth_header() -> String {
     let mut cfg = load_config();
     let account = cfg.current_account();
@@ -118,7 +185,7 @@ pub(crate) fn format_bearer_auth_header(token: &str) -> String {
     format!("Bearer {token}")
 }
 
-pub fn open_editor(file: &PathBuf) -> ExitStatus {
+pub fn open_editor(file: &Path) -> ExitStatus {
     #[cfg(unix)]
     let editor = env::var("EDITOR")
         .or_else(|_| env::var("VISUAL"))
@@ -163,67 +230,155 @@ pub(crate) fn gracefully_exit(message: &str) -> ! {
     process::exit(1)
 }
 
+/// Reports an API failure on stderr and exits with a code scripts can branch on.
+/// A request held back by `--dry-run` has already been printed and exits quietly.
+pub(crate) fn exit_with_error(err: &TwitterError) -> ! {
+    if !err.is_dry_run() {
+        eprintln!("{err}");
+    }
+    process::exit(err.kind().exit_code())
+}
+
 pub(crate) fn send_due_tweets() {
     let schedule = Schedule::default();
-    let due_tweets = schedule.due();
+    let runs = ScheduleRuns::default();
+    let retry = schedule_retry();
+    let mut due_tweets = schedule.due();
+    due_tweets.extend(schedule.due_retries(&retry));
     if due_tweets.is_empty() {
         println!("No pending scheduled tweets to run.");
+        runs.record(None);
         return;
     }
 
+    let (sent_count, failed_count, last_error) =
+        send_scheduled_tweets(&schedule, &due_tweets, &retry);
+    runs.record(last_error.as_deref());
+
+    println!(
+        "Finished sending scheduled tweets. Sent: {}, Failed: {}.",
+        sent_count, failed_count
+    );
+}
+
+/// Sends failed scheduled tweets again now: the one with `id`, or all of them. The retry
+/// policy only applies to what fails again.
+pub(crate) fn retry_failed_tweets(id: Option<u32>) {
+    let schedule = Schedule::default();
+    let failed_tweets = match id {
+        Some(id) => match schedule.get(id) {
+            Some(tweet) => match tweet.status {
+                ScheduleStatus::Failed => vec![tweet],
+                ScheduleStatus::Pending => {
+                    gracefully_exit(&format!("Scheduled tweet {id} is still pending."))
+                }
+                ScheduleStatus::Sent => {
+                    gracefully_exit(&format!("Scheduled tweet {id} was already sent."))
+                }
+            },
+            None => gracefully_exit(&format!("No scheduled tweet with id {id}.")),
+        },
+        None => schedule.failed(),
+    };
+    if failed_tweets.is_empty() {
+        println!("No failed scheduled tweets to retry.");
+        return;
+    }
+
+    let (sent_count, failed_count, _) =
+        send_scheduled_tweets(&schedule, &failed_tweets, &schedule_retry());
+    println!(
+        "Finished retrying scheduled tweets. Sent: {}, Failed: {}.",
+        sent_count, failed_count
+    );
+}
+
+/// Sends `tweets` one after another. Returns how many were sent and failed, and the last error.
+fn send_scheduled_tweets(
+    schedule: &Schedule,
+    tweets: &[ScheduledTweet],
+    retry: &ScheduleRetry,
+) -> (usize, usize, Option<String>) {
+    // Read before any tweet switches accounts, for tweets saved without one.
+    let current_account = load_config().current_account;
     let mut sent_count = 0;
     let mut failed_count = 0;
-    for (index, due_tweet) in due_tweets.iter().enumerate() {
-        println!("> Sending tweet {}/{}", index + 1, due_tweets.len());
-        let mut tweet = match Tweet::from_str(&due_tweet.body) {
-            Ok(tweet) => tweet,
+    let mut last_error = None;
+    for (index, tweet) in tweets.iter().enumerate() {
+        println!("> Sending tweet {}/{}", index + 1, tweets.len());
+        match send_scheduled_tweet(schedule, tweet, current_account, retry) {
+            Ok(()) => sent_count += 1,
+            Err(err) if err.is_dry_run() => {}
             Err(err) => {
-                eprintln!(
-                    "Failed to build tweet payload for schedule id {}: {}",
-                    due_tweet.id, err.message
-                );
-                schedule.mark_failed(due_tweet.id, &err.message);
                 failed_count += 1;
-                continue;
+                last_error = Some(err.to_string());
             }
+        }
+    }
+    (sent_count, failed_count, last_error)
+}
+
+/// Sends one scheduled tweet from its account, or `default_account` when it has none, and
+/// records the outcome. Transient failures are retried as `retry` says. A recurring tweet gets
+/// its next occurrence on its first attempt, whether it is sent or not.
+pub(crate) fn send_scheduled_tweet(
+    schedule: &Schedule,
+    due_tweet: &ScheduledTweet,
+    default_account: usize,
+    retry: &ScheduleRetry,
+) -> Result<(), TwitterError> {
+    use_account(due_tweet.account.unwrap_or(default_account));
+    // Kept when the thread cannot be resumed, so a retry never posts its live parts again.
+    let mut unfinished = due_tweet.thread_id;
+    let api_res = due_tweet.next_attempt().and_then(|mut tweet| {
+        let res = match due_tweet.thread_id {
+            // Its media went up with the attempt that started it.
+            Some(_) => tweet.create(),
+            None => tweet
+                .upload_media(due_tweet.images.clone(), &due_tweet.media_options)
+                .and_then(|_| tweet.create()),
         };
-        let api_res = tweet.create();
-        match api_res {
-            Ok(res) => {
-                println!("{}", res.content);
-                schedule.mark_sent(due_tweet.id);
-                sent_count += 1;
-            }
-            Err(err) => {
-                eprintln!("{}", err.message);
-                schedule.mark_failed(due_tweet.id, &err.message);
-                failed_count += 1;
+        unfinished = tweet.unfinished_thread();
+        res
+    });
+    let result = match api_res {
+        Ok(res) => {
+            println!("{}", res.content);
+            schedule.mark_sent(due_tweet.id);
+            Ok(())
+        }
+        // Printed rather than sent; it stays pending for the real run.
+        Err(err) if err.is_dry_run() => return Err(err),
+        Err(err) => {
+            eprintln!("{err}");
+            let retryable = err.is_transient();
+            schedule.mark_failed(due_tweet.id, &err.to_string(), retryable, unfinished);
+            let attempts = due_tweet.attempts + 1;
+            if retryable && retry.allows(attempts) {
+                let at = Timestamp::now() + retry.backoff(attempts);
+                println!("> Retrying at {}", local_time(at));
             }
+            Err(err)
         }
+    };
+    // A failed occurrence does not end the series. Retries already have their next occurrence.
+    if due_tweet.attempts == 0
+        && let Some(next) = schedule.schedule_next(due_tweet)
+    {
+        println!("> Repeats on {}", local_time(next));
     }
-
-    println!(
-        "Finished sending scheduled tweets. Sent: {}, Failed: {}.",
-        sent_count, failed_count
-    );
+    result
 }
 
-fn fetch_user_id(account: &Account) -> Result<String, String> {
-    let url = "https://api.x.com/2/users/me";
-    let auth_header = oauth_get_header_for_account(account, url, &());
-    let response = curl_rest::Client::default()
+fn fetch_user_id(account: &Account) -> Result<String, TwitterError> {
+    let url = api().url("/2/users/me");
+    let response = api()
         .get()
-        .header(curl_rest::Header::Authorization(auth_header.into()))
-        .send(url)
-        .map_err(|err| err.to_string())?;
+        .authorization(|| oauth_get_header_for_account(account, url.as_str(), &()))
+        .send(url.as_str())?;
 
-    if (200..300).contains(&response.status.as_u16()) {
-        let user_res: UsersMeResponse = serde_json::from_slice(&response.body)
-            .map_err(|err| format!("Failed to parse users/me response: {err}"))?;
-        Ok(user_res.data.id)
-    } else {
-        Err(String::from_utf8_lossy(&response.body).to_string())
-    }
+    let user_res: TwitterResponse<UsersMeResponse> = TwitterResponse::decode(response)?;
+    Ok(user_res.content.data.id)
 }
 
 fn oauth_get_header_for_account<R>(account: &Account, url: &str, request: &R) -> String